@@ -9,6 +9,7 @@ use syn::parse_macro_input;
 
 mod component;
 mod props;
+mod store;
 mod utils;
 
 use dioxus_rsx as rsx;
@@ -23,6 +24,16 @@ pub fn derive_props(input: TokenStream) -> TokenStream {
     }
 }
 
+#[doc = include_str!("../docs/store.md")]
+#[proc_macro_derive(Store, attributes(store))]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match store::derive_store(&input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
 #[doc = include_str!("../docs/rsx.md")]
 #[proc_macro]
 pub fn rsx(tokens: TokenStream) -> TokenStream {
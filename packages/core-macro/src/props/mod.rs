@@ -853,6 +853,16 @@ Finally, call `.build()` to create the instance of `{name}`.
                         .then(|| quote!(owner: Owner::default())),
                 );
 
+            // Only props without generics can check if they implement Debug
+            let debug_props = self.generics.params.is_empty().then(|| {
+                quote! {
+                    fn debug_props(&self) -> Option<String> {
+                        use dioxus_core::internal::{ViaDebug as _, ViaFallback as _};
+                        (&&dioxus_core::internal::DebugProps(self)).debug_props()
+                    }
+                }
+            });
+
             Ok(quote! {
                 impl #impl_generics #name #ty_generics #where_clause {
                     #[doc = #builder_method_doc]
@@ -885,6 +895,7 @@ Finally, call `.build()` to create the instance of `{name}`.
                     fn memoize(&mut self, new: &Self) -> bool {
                         #memoize
                     }
+                    #debug_props
                 }
             })
         }
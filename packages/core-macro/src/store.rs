@@ -0,0 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, GenericParam, Path};
+
+pub(crate) fn derive_store(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "Store can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Store can only be derived for structs",
+            ))
+        }
+    };
+
+    if let Some(lifetime) = input.generics.lifetimes().next() {
+        return Err(syn::Error::new(
+            lifetime.span(),
+            "Store cannot be derived for structs with lifetimes",
+        ));
+    }
+
+    let signals = signals_path(input)?;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let extension = format_ident!("{}StoreExt", name);
+
+    // The extension trait is generic over the struct's generics and the lens of the store
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(syn::parse_quote!('static));
+        }
+    }
+    generics.params.push(syn::parse_quote!(
+        __Lens: #signals::Lens<Target = #name #ty_generics>
+    ));
+    let (impl_generics, trait_generics, _) = generics.split_for_impl();
+
+    let mut signatures = Vec::new();
+    let mut methods = Vec::new();
+    for (id, field) in fields.iter().enumerate() {
+        let id = id as u64;
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let doc = format!("Get the store of the `{field_name}` field.");
+        let signature = quote! {
+            fn #field_name(&self) -> #signals::Store<#ty, #signals::FieldLens<__Lens, #ty>>
+        };
+        signatures.push(quote! {
+            #[doc = #doc]
+            #signature;
+        });
+        methods.push(quote! {
+            #signature {
+                #signals::Store::field(
+                    self,
+                    #id,
+                    |value| &value.#field_name,
+                    |value| &mut value.#field_name,
+                )
+            }
+        });
+    }
+
+    let trait_doc = format!("Get the store of each field of a `Store<{name}>`.");
+
+    Ok(quote! {
+        #[doc = #trait_doc]
+        #vis trait #extension #impl_generics #where_clause {
+            #(#signatures)*
+        }
+
+        impl #impl_generics #extension #trait_generics for #signals::Store<#name #ty_generics, __Lens> #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+/// Get the path to the signals crate from the `#[store(crate = "...")]` attribute, or `dioxus::signals` if it is not set
+fn signals_path(input: &DeriveInput) -> syn::Result<Path> {
+    let mut path = syn::parse_quote!(dioxus::signals);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("store"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                path = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate`"))
+            }
+        })?;
+    }
+    Ok(path)
+}
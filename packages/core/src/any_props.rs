@@ -15,12 +15,17 @@ pub(crate) trait AnyProps: 'static {
     fn props_mut(&mut self) -> &mut dyn Any;
     /// Duplicate this component into a new boxed component.
     fn duplicate(&self) -> BoxedAnyProps;
+    /// Get the name of the props type for debugging purposes.
+    fn props_type_name(&self) -> &'static str;
+    /// Format the props for debugging purposes if they support it.
+    fn props_debug(&self) -> Option<String>;
 }
 
 /// A component along with the props the component uses to render.
 pub(crate) struct VProps<F: ComponentFunction<P, M>, P, M> {
     render_fn: F,
     memo: fn(&mut P, &P) -> bool,
+    debug: fn(&P) -> Option<String>,
     props: P,
     name: &'static str,
     phantom: std::marker::PhantomData<M>,
@@ -31,6 +36,7 @@ impl<F: ComponentFunction<P, M>, P: Clone, M> Clone for VProps<F, P, M> {
         Self {
             render_fn: self.render_fn.clone(),
             memo: self.memo,
+            debug: self.debug,
             props: self.props.clone(),
             name: self.name,
             phantom: std::marker::PhantomData,
@@ -43,12 +49,14 @@ impl<F: ComponentFunction<P, M> + Clone, P: Clone + 'static, M: 'static> VProps<
     pub fn new(
         render_fn: F,
         memo: fn(&mut P, &P) -> bool,
+        debug: fn(&P) -> Option<String>,
         props: P,
         name: &'static str,
     ) -> VProps<F, P, M> {
         VProps {
             render_fn,
             memo,
+            debug,
             props,
             name,
             phantom: std::marker::PhantomData,
@@ -92,10 +100,19 @@ impl<F: ComponentFunction<P, M> + Clone, P: Clone + 'static, M: 'static> AnyProp
         }
     }
 
+    fn props_type_name(&self) -> &'static str {
+        std::any::type_name::<P>()
+    }
+
+    fn props_debug(&self) -> Option<String> {
+        (self.debug)(&self.props)
+    }
+
     fn duplicate(&self) -> BoxedAnyProps {
         Box::new(Self {
             render_fn: self.render_fn.clone(),
             memo: self.memo,
+            debug: self.debug,
             props: self.props.clone(),
             name: self.name,
             phantom: std::marker::PhantomData,
@@ -84,7 +84,9 @@ impl VirtualDom {
             context.height
         };
 
-        self.dirty_scopes.remove(&ScopeOrder::new(height, id));
+        let order = ScopeOrder::new(height, id);
+        self.dirty_scopes.remove(&order);
+        self.input_scopes.remove(&order);
 
         // If this scope was a suspense boundary, remove it from the resolved scopes
         self.resolved_scopes.retain(|s| s != &id);
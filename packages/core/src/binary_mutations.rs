@@ -0,0 +1,898 @@
+//! A compact, versioned binary encoding for the [`WriteMutations`] stream.
+//!
+//! [`BinaryMutations`] implements [`WriteMutations`] by appending every call to a byte buffer. The resulting frame can
+//! be stored, sent to another process, and replayed onto any other [`WriteMutations`] implementation with a
+//! [`MutationDecoder`].
+//!
+//! # Format
+//!
+//! Every frame starts with a four byte header: the magic bytes `DXM` followed by the format version
+//! ([`MUTATION_ENCODING_VERSION`]). The header is followed by a sequence of instructions. Each instruction is a single
+//! opcode byte followed by its operands:
+//!
+//! | Opcode | Instruction | Operands |
+//! |--------|-------------|----------|
+//! | 0 | `register_template` | template |
+//! | 1 | `append_children` | id, m |
+//! | 2 | `assign_node_id` | path, id |
+//! | 3 | `create_placeholder` | id |
+//! | 4 | `create_text_node` | string, id |
+//! | 5 | `hydrate_text_node` | path, string, id |
+//! | 6 | `load_template` | static string (name), index, id |
+//! | 7 | `replace_node_with` | id, m |
+//! | 8 | `replace_placeholder_with_nodes` | path, m |
+//! | 9 | `insert_nodes_after` | id, m |
+//! | 10 | `insert_nodes_before` | id, m |
+//! | 11 | `set_attribute` | static string, optional static string, attribute value, id |
+//! | 12 | `set_node_text` | string, id |
+//! | 13 | `create_event_listener` | static string, id |
+//! | 14 | `remove_event_listener` | static string, id |
+//! | 15 | `remove_node` | id |
+//! | 16 | `push_root` | id |
+//! | 17 | `swap_subtree` | index |
+//! | 18 | `mark_scope_dirty` | scope id |
+//!
+//! Operands are encoded as follows:
+//! - Integers (ids, counts, indices, lengths) are unsigned LEB128 varints.
+//! - Strings are a varint byte length followed by UTF-8 bytes.
+//! - Paths are a varint length followed by the raw path bytes.
+//! - Static strings (template, tag, attribute and event names) are interned per frame. A varint of `0` is followed by
+//!   a new string which is assigned the next index in the table, any other value `n` refers to entry `n - 1`.
+//! - Optional static strings are a `0` byte for `None` or a `1` byte followed by the static string.
+//! - Attribute values are a tag byte: `0` text (string), `1` float (8 byte little endian), `2` int (zigzag varint),
+//!   `3` false, `4` true, `5` none. Listeners and `Any` values cannot be encoded, so they are written as none.
+//! - Templates are the template name (static string), the roots (varint count followed by template nodes), then the
+//!   node paths and attribute paths (varint count followed by paths).
+//! - Template nodes are a tag byte: `0` element (static string tag, optional static string namespace, varint count of
+//!   attributes, varint count of children), `1` text (static string), `2` dynamic (varint id).
+//! - Template attributes are a tag byte: `0` static (static string name, static string value, optional static string
+//!   namespace), `1` dynamic (varint id). Templates may nest at most [`MAX_TEMPLATE_DEPTH`] elements deep.
+//!
+//! Each frame is self contained: the interning table is reset whenever a new frame is started.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    arena::ElementId, AttributeValue, ScopeId, Template, TemplateAttribute, TemplateNode,
+    WriteMutations,
+};
+
+/// The version of the binary mutation format written by [`BinaryMutations`] and read by [`MutationDecoder`].
+pub const MUTATION_ENCODING_VERSION: u8 = 1;
+
+/// The deepest element nesting [`MutationDecoder`] accepts in a template. Deeper templates are rejected instead of
+/// overflowing the stack.
+pub const MAX_TEMPLATE_DEPTH: usize = 128;
+
+const MAGIC: &[u8; 3] = b"DXM";
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+mod op {
+    pub const REGISTER_TEMPLATE: u8 = 0;
+    pub const APPEND_CHILDREN: u8 = 1;
+    pub const ASSIGN_ID: u8 = 2;
+    pub const CREATE_PLACEHOLDER: u8 = 3;
+    pub const CREATE_TEXT_NODE: u8 = 4;
+    pub const HYDRATE_TEXT: u8 = 5;
+    pub const LOAD_TEMPLATE: u8 = 6;
+    pub const REPLACE_WITH: u8 = 7;
+    pub const REPLACE_PLACEHOLDER: u8 = 8;
+    pub const INSERT_AFTER: u8 = 9;
+    pub const INSERT_BEFORE: u8 = 10;
+    pub const SET_ATTRIBUTE: u8 = 11;
+    pub const SET_TEXT: u8 = 12;
+    pub const NEW_EVENT_LISTENER: u8 = 13;
+    pub const REMOVE_EVENT_LISTENER: u8 = 14;
+    pub const REMOVE: u8 = 15;
+    pub const PUSH_ROOT: u8 = 16;
+    pub const SWAP_SUBTREE: u8 = 17;
+    pub const MARK_SCOPE_DIRTY: u8 = 18;
+}
+
+/// A [`WriteMutations`] implementation that encodes every mutation into a compact binary frame.
+///
+/// See the [module level documentation](self) for a description of the format.
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// # use dioxus_core::{BinaryMutations, Mutations, MutationDecoder};
+/// fn app() -> Element {
+///     rsx! { div { "hello world" } }
+/// }
+///
+/// let mut dom = VirtualDom::new(app);
+/// let mut encoded = BinaryMutations::new();
+/// dom.rebuild(&mut encoded);
+///
+/// // Send the bytes somewhere else and replay them onto another renderer
+/// let bytes = encoded.take();
+/// let mut mutations = Mutations::default();
+/// MutationDecoder::new().decode(&bytes, &mut mutations).unwrap();
+/// assert!(!mutations.edits.is_empty());
+/// ```
+pub struct BinaryMutations {
+    buffer: Vec<u8>,
+    interned: FxHashMap<&'static str, usize>,
+}
+
+impl Default for BinaryMutations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BinaryMutations {
+    /// Create a new encoder with an empty frame
+    pub fn new() -> Self {
+        let mut myself = Self {
+            buffer: Vec::new(),
+            interned: FxHashMap::default(),
+        };
+        myself.write_header();
+        myself
+    }
+
+    /// Get the bytes of the current frame, including the header
+    pub fn bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Check if any mutations have been written to the current frame
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == HEADER_LEN
+    }
+
+    /// Take the current frame out of the encoder and start a new one
+    pub fn take(&mut self) -> Vec<u8> {
+        let frame = std::mem::take(&mut self.buffer);
+        self.interned.clear();
+        self.write_header();
+        frame
+    }
+
+    fn write_header(&mut self) {
+        self.buffer.extend_from_slice(MAGIC);
+        self.buffer.push(MUTATION_ENCODING_VERSION);
+    }
+
+    fn write_u8(&mut self, byte: u8) {
+        self.buffer.push(byte);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_varint(value as u64);
+    }
+
+    fn write_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.buffer.push(byte);
+                break;
+            }
+            self.buffer.push(byte | 0x80);
+        }
+    }
+
+    fn write_id(&mut self, id: ElementId) {
+        self.write_usize(id.0);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    fn write_static_str(&mut self, value: &'static str) {
+        match self.interned.get(value) {
+            Some(&index) => self.write_usize(index + 1),
+            None => {
+                let index = self.interned.len();
+                self.interned.insert(value, index);
+                self.write_usize(0);
+                self.write_str(value);
+            }
+        }
+    }
+
+    fn write_optional_static_str(&mut self, value: Option<&'static str>) {
+        match value {
+            Some(value) => {
+                self.write_u8(1);
+                self.write_static_str(value);
+            }
+            None => self.write_u8(0),
+        }
+    }
+
+    fn write_attribute_value(&mut self, value: &AttributeValue) {
+        match value {
+            AttributeValue::Text(text) => {
+                self.write_u8(0);
+                self.write_str(text);
+            }
+            AttributeValue::Float(float) => {
+                self.write_u8(1);
+                self.buffer.extend_from_slice(&float.to_le_bytes());
+            }
+            AttributeValue::Int(int) => {
+                self.write_u8(2);
+                // zigzag encode the integer so small negative numbers stay small
+                self.write_varint(((int << 1) ^ (int >> 63)) as u64);
+            }
+            AttributeValue::Bool(false) => self.write_u8(3),
+            AttributeValue::Bool(true) => self.write_u8(4),
+            // Listeners and arbitrary values only mean something inside of the VirtualDom, so the other side of the
+            // frame sees them as removed
+            AttributeValue::None | AttributeValue::Listener(_) | AttributeValue::Any(_) => {
+                self.write_u8(5)
+            }
+        }
+    }
+
+    fn write_template_node(&mut self, node: &TemplateNode) {
+        match node {
+            TemplateNode::Element {
+                tag,
+                namespace,
+                attrs,
+                children,
+            } => {
+                self.write_u8(0);
+                self.write_static_str(tag);
+                self.write_optional_static_str(*namespace);
+                self.write_usize(attrs.len());
+                for attr in attrs.iter() {
+                    match attr {
+                        TemplateAttribute::Static {
+                            name,
+                            value,
+                            namespace,
+                        } => {
+                            self.write_u8(0);
+                            self.write_static_str(name);
+                            self.write_static_str(value);
+                            self.write_optional_static_str(*namespace);
+                        }
+                        TemplateAttribute::Dynamic { id } => {
+                            self.write_u8(1);
+                            self.write_usize(*id);
+                        }
+                    }
+                }
+                self.write_usize(children.len());
+                for child in children.iter() {
+                    self.write_template_node(child);
+                }
+            }
+            TemplateNode::Text { text } => {
+                self.write_u8(1);
+                self.write_static_str(text);
+            }
+            TemplateNode::Dynamic { id } => {
+                self.write_u8(2);
+                self.write_usize(*id);
+            }
+        }
+    }
+
+    fn write_paths(&mut self, paths: &[&[u8]]) {
+        self.write_usize(paths.len());
+        for path in paths {
+            self.write_bytes(path);
+        }
+    }
+}
+
+impl WriteMutations for BinaryMutations {
+    fn register_template(&mut self, template: Template) {
+        self.write_u8(op::REGISTER_TEMPLATE);
+        self.write_static_str(template.name);
+        self.write_usize(template.roots.len());
+        for root in template.roots {
+            self.write_template_node(root);
+        }
+        self.write_paths(template.node_paths);
+        self.write_paths(template.attr_paths);
+    }
+
+    fn append_children(&mut self, id: ElementId, m: usize) {
+        self.write_u8(op::APPEND_CHILDREN);
+        self.write_id(id);
+        self.write_usize(m);
+    }
+
+    fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
+        self.write_u8(op::ASSIGN_ID);
+        self.write_bytes(path);
+        self.write_id(id);
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) {
+        self.write_u8(op::CREATE_PLACEHOLDER);
+        self.write_id(id);
+    }
+
+    fn create_text_node(&mut self, value: &str, id: ElementId) {
+        self.write_u8(op::CREATE_TEXT_NODE);
+        self.write_str(value);
+        self.write_id(id);
+    }
+
+    fn hydrate_text_node(&mut self, path: &'static [u8], value: &str, id: ElementId) {
+        self.write_u8(op::HYDRATE_TEXT);
+        self.write_bytes(path);
+        self.write_str(value);
+        self.write_id(id);
+    }
+
+    fn load_template(&mut self, name: &'static str, index: usize, id: ElementId) {
+        self.write_u8(op::LOAD_TEMPLATE);
+        self.write_static_str(name);
+        self.write_usize(index);
+        self.write_id(id);
+    }
+
+    fn replace_node_with(&mut self, id: ElementId, m: usize) {
+        self.write_u8(op::REPLACE_WITH);
+        self.write_id(id);
+        self.write_usize(m);
+    }
+
+    fn replace_placeholder_with_nodes(&mut self, path: &'static [u8], m: usize) {
+        self.write_u8(op::REPLACE_PLACEHOLDER);
+        self.write_bytes(path);
+        self.write_usize(m);
+    }
+
+    fn insert_nodes_after(&mut self, id: ElementId, m: usize) {
+        self.write_u8(op::INSERT_AFTER);
+        self.write_id(id);
+        self.write_usize(m);
+    }
+
+    fn insert_nodes_before(&mut self, id: ElementId, m: usize) {
+        self.write_u8(op::INSERT_BEFORE);
+        self.write_id(id);
+        self.write_usize(m);
+    }
+
+    fn set_attribute(
+        &mut self,
+        name: &'static str,
+        ns: Option<&'static str>,
+        value: &AttributeValue,
+        id: ElementId,
+    ) {
+        self.write_u8(op::SET_ATTRIBUTE);
+        self.write_static_str(name);
+        self.write_optional_static_str(ns);
+        self.write_attribute_value(value);
+        self.write_id(id);
+    }
+
+    fn set_node_text(&mut self, value: &str, id: ElementId) {
+        self.write_u8(op::SET_TEXT);
+        self.write_str(value);
+        self.write_id(id);
+    }
+
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.write_u8(op::NEW_EVENT_LISTENER);
+        self.write_static_str(name);
+        self.write_id(id);
+    }
+
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.write_u8(op::REMOVE_EVENT_LISTENER);
+        self.write_static_str(name);
+        self.write_id(id);
+    }
+
+    fn remove_node(&mut self, id: ElementId) {
+        self.write_u8(op::REMOVE);
+        self.write_id(id);
+    }
+
+    fn push_root(&mut self, id: ElementId) {
+        self.write_u8(op::PUSH_ROOT);
+        self.write_id(id);
+    }
+
+    fn swap_subtree(&mut self, subtree_index: usize) {
+        self.write_u8(op::SWAP_SUBTREE);
+        self.write_usize(subtree_index);
+    }
+
+    fn mark_scope_dirty(&mut self, scope_id: ScopeId) {
+        self.write_u8(op::MARK_SCOPE_DIRTY);
+        self.write_usize(scope_id.0);
+    }
+}
+
+/// An error that occurred while decoding a binary mutation frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MutationDecodeError {
+    /// The frame did not start with the expected magic bytes
+    InvalidHeader,
+
+    /// The frame was encoded with a version of the format this decoder does not understand
+    UnsupportedVersion(u8),
+
+    /// The frame ended in the middle of an instruction
+    UnexpectedEof,
+
+    /// The frame contained an unknown opcode
+    InvalidOpcode(u8),
+
+    /// The frame contained an unknown tag for an attribute value, template node or template attribute
+    InvalidTag(u8),
+
+    /// The frame referenced a static string that was never defined
+    InvalidStringReference(usize),
+
+    /// The frame contained a string that was not valid UTF-8
+    InvalidUtf8,
+
+    /// The frame contained a varint that does not fit in a usize
+    VarintOverflow,
+
+    /// The frame contained a template nested deeper than [`MAX_TEMPLATE_DEPTH`]
+    TemplateTooDeep,
+}
+
+impl std::fmt::Display for MutationDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "invalid mutation frame header"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported mutation encoding version {version} (expected {MUTATION_ENCODING_VERSION})"
+            ),
+            Self::UnexpectedEof => write!(f, "unexpected end of mutation frame"),
+            Self::InvalidOpcode(op) => write!(f, "invalid mutation opcode {op}"),
+            Self::InvalidTag(tag) => write!(f, "invalid tag {tag} in mutation frame"),
+            Self::InvalidStringReference(index) => {
+                write!(f, "reference to undefined static string {index}")
+            }
+            Self::InvalidUtf8 => write!(f, "invalid utf-8 in mutation frame"),
+            Self::VarintOverflow => write!(f, "varint in mutation frame overflowed"),
+            Self::TemplateTooDeep => write!(
+                f,
+                "template in mutation frame is nested deeper than {MAX_TEMPLATE_DEPTH} elements"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MutationDecodeError {}
+
+/// Replays binary mutation frames created by [`BinaryMutations`] onto any [`WriteMutations`] implementation.
+///
+/// [`WriteMutations`] requires static strings for names and paths. The decoder leaks each distinct string and path it
+/// sees exactly once and reuses them across frames. Templates are cached by name and contents: registering the same
+/// template again returns the template that was already leaked, while a template with new contents under a known name
+/// (for example after hot reloading) replaces the cached one. A long lived decoder only grows with the number of unique
+/// names and templates in your application.
+#[derive(Default)]
+pub struct MutationDecoder {
+    strings: FxHashSet<&'static str>,
+    paths: FxHashSet<&'static [u8]>,
+    // The contents each template was decoded from, so a changed template under the same name is decoded again
+    templates: FxHashMap<&'static str, (Vec<usize>, Template)>,
+}
+
+impl MutationDecoder {
+    /// Create a new decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode a frame and apply every mutation in it to the target.
+    ///
+    /// If the frame is malformed, the mutations before the error will already have been applied.
+    pub fn decode(
+        &mut self,
+        bytes: &[u8],
+        to: &mut impl WriteMutations,
+    ) -> Result<(), MutationDecodeError> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(MutationDecodeError::InvalidHeader);
+        }
+        let version = bytes[MAGIC.len()];
+        if version != MUTATION_ENCODING_VERSION {
+            return Err(MutationDecodeError::UnsupportedVersion(version));
+        }
+
+        let mut frame = Frame {
+            bytes,
+            cursor: HEADER_LEN,
+            table: Vec::new(),
+            skipping_template: false,
+            contents: None,
+            decoder: self,
+        };
+
+        while frame.cursor < bytes.len() {
+            frame.decode_instruction(to)?;
+        }
+
+        Ok(())
+    }
+
+    fn intern_str(&mut self, value: &str) -> &'static str {
+        if let Some(interned) = self.strings.get(value) {
+            return interned;
+        }
+        let leaked: &'static str = Box::leak(value.to_string().into_boxed_str());
+        self.strings.insert(leaked);
+        leaked
+    }
+
+    fn intern_path(&mut self, path: &[u8]) -> &'static [u8] {
+        if let Some(interned) = self.paths.get(path) {
+            return interned;
+        }
+        let leaked: &'static [u8] = Box::leak(path.to_vec().into_boxed_slice());
+        self.paths.insert(leaked);
+        leaked
+    }
+}
+
+struct Frame<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+    table: Vec<&'static str>,
+    // Set while reading past a template that was already decoded
+    skipping_template: bool,
+    // Records what a template body decodes to while it is read. Interned strings and paths are recorded by address
+    contents: Option<Vec<usize>>,
+    decoder: &'a mut MutationDecoder,
+}
+
+impl<'a> Frame<'a> {
+    fn decode_instruction(
+        &mut self,
+        to: &mut impl WriteMutations,
+    ) -> Result<(), MutationDecodeError> {
+        match self.read_u8()? {
+            op::REGISTER_TEMPLATE => {
+                let template = self.read_template()?;
+                to.register_template(template);
+            }
+            op::APPEND_CHILDREN => {
+                let id = self.read_id()?;
+                let m = self.read_usize()?;
+                to.append_children(id, m);
+            }
+            op::ASSIGN_ID => {
+                let path = self.read_path()?;
+                let id = self.read_id()?;
+                to.assign_node_id(path, id);
+            }
+            op::CREATE_PLACEHOLDER => {
+                let id = self.read_id()?;
+                to.create_placeholder(id);
+            }
+            op::CREATE_TEXT_NODE => {
+                let value = self.read_str()?;
+                let id = self.read_id()?;
+                to.create_text_node(value, id);
+            }
+            op::HYDRATE_TEXT => {
+                let path = self.read_path()?;
+                let value = self.read_str()?;
+                let id = self.read_id()?;
+                to.hydrate_text_node(path, value, id);
+            }
+            op::LOAD_TEMPLATE => {
+                let name = self.read_static_str()?;
+                let index = self.read_usize()?;
+                let id = self.read_id()?;
+                to.load_template(name, index, id);
+            }
+            op::REPLACE_WITH => {
+                let id = self.read_id()?;
+                let m = self.read_usize()?;
+                to.replace_node_with(id, m);
+            }
+            op::REPLACE_PLACEHOLDER => {
+                let path = self.read_path()?;
+                let m = self.read_usize()?;
+                to.replace_placeholder_with_nodes(path, m);
+            }
+            op::INSERT_AFTER => {
+                let id = self.read_id()?;
+                let m = self.read_usize()?;
+                to.insert_nodes_after(id, m);
+            }
+            op::INSERT_BEFORE => {
+                let id = self.read_id()?;
+                let m = self.read_usize()?;
+                to.insert_nodes_before(id, m);
+            }
+            op::SET_ATTRIBUTE => {
+                let name = self.read_static_str()?;
+                let ns = self.read_optional_static_str()?;
+                let value = self.read_attribute_value()?;
+                let id = self.read_id()?;
+                to.set_attribute(name, ns, &value, id);
+            }
+            op::SET_TEXT => {
+                let value = self.read_str()?;
+                let id = self.read_id()?;
+                to.set_node_text(value, id);
+            }
+            op::NEW_EVENT_LISTENER => {
+                let name = self.read_static_str()?;
+                let id = self.read_id()?;
+                to.create_event_listener(name, id);
+            }
+            op::REMOVE_EVENT_LISTENER => {
+                let name = self.read_static_str()?;
+                let id = self.read_id()?;
+                to.remove_event_listener(name, id);
+            }
+            op::REMOVE => {
+                let id = self.read_id()?;
+                to.remove_node(id);
+            }
+            op::PUSH_ROOT => {
+                let id = self.read_id()?;
+                to.push_root(id);
+            }
+            op::SWAP_SUBTREE => {
+                let index = self.read_usize()?;
+                to.swap_subtree(index);
+            }
+            op::MARK_SCOPE_DIRTY => {
+                let scope = self.read_usize()?;
+                to.mark_scope_dirty(ScopeId(scope));
+            }
+            other => return Err(MutationDecodeError::InvalidOpcode(other)),
+        }
+
+        Ok(())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, MutationDecodeError> {
+        let byte = *self
+            .bytes
+            .get(self.cursor)
+            .ok_or(MutationDecodeError::UnexpectedEof)?;
+        self.cursor += 1;
+        if let Some(contents) = &mut self.contents {
+            contents.push(byte as usize);
+        }
+        Ok(byte)
+    }
+
+    fn read_usize(&mut self) -> Result<usize, MutationDecodeError> {
+        let value = self.read_varint()?;
+        usize::try_from(value).map_err(|_| MutationDecodeError::VarintOverflow)
+    }
+
+    fn read_varint(&mut self) -> Result<u64, MutationDecodeError> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 {
+                return Err(MutationDecodeError::VarintOverflow);
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        Ok(value)
+    }
+
+    fn read_id(&mut self) -> Result<ElementId, MutationDecodeError> {
+        self.read_usize().map(ElementId)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], MutationDecodeError> {
+        let len = self.read_usize()?;
+        let end = self
+            .cursor
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(MutationDecodeError::UnexpectedEof)?;
+        let bytes = &self.bytes[self.cursor..end];
+        self.cursor = end;
+        Ok(bytes)
+    }
+
+    fn read_str(&mut self) -> Result<&'a str, MutationDecodeError> {
+        std::str::from_utf8(self.read_bytes()?).map_err(|_| MutationDecodeError::InvalidUtf8)
+    }
+
+    fn read_path(&mut self) -> Result<&'static [u8], MutationDecodeError> {
+        let contents = self.contents.take();
+        let path = self.read_bytes();
+        self.contents = contents;
+        let path = self.decoder.intern_path(path?);
+        if let Some(contents) = &mut self.contents {
+            contents.push(path.as_ptr() as usize);
+        }
+        Ok(path)
+    }
+
+    fn read_static_str(&mut self) -> Result<&'static str, MutationDecodeError> {
+        // The same string can be defined inline or referenced from the table, so only the interned string is recorded
+        let contents = self.contents.take();
+        let value = self.read_static_str_inner();
+        self.contents = contents;
+        let value = value?;
+        if let Some(contents) = &mut self.contents {
+            contents.push(value.as_ptr() as usize);
+        }
+        Ok(value)
+    }
+
+    fn read_static_str_inner(&mut self) -> Result<&'static str, MutationDecodeError> {
+        match self.read_usize()? {
+            0 => {
+                let value = self.read_str()?;
+                let interned = self.decoder.intern_str(value);
+                self.table.push(interned);
+                Ok(interned)
+            }
+            index => self
+                .table
+                .get(index - 1)
+                .copied()
+                .ok_or(MutationDecodeError::InvalidStringReference(index - 1)),
+        }
+    }
+
+    fn read_optional_static_str(&mut self) -> Result<Option<&'static str>, MutationDecodeError> {
+        match self.read_u8()? {
+            0 => Ok(None),
+            1 => self.read_static_str().map(Some),
+            other => Err(MutationDecodeError::InvalidTag(other)),
+        }
+    }
+
+    fn read_attribute_value(&mut self) -> Result<AttributeValue, MutationDecodeError> {
+        Ok(match self.read_u8()? {
+            0 => AttributeValue::Text(self.read_str()?.to_string()),
+            1 => {
+                let mut float = [0; 8];
+                for byte in float.iter_mut() {
+                    *byte = self.read_u8()?;
+                }
+                AttributeValue::Float(f64::from_le_bytes(float))
+            }
+            2 => {
+                let zigzag = self.read_varint()?;
+                AttributeValue::Int((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64))
+            }
+            3 => AttributeValue::Bool(false),
+            4 => AttributeValue::Bool(true),
+            5 => AttributeValue::None,
+            other => return Err(MutationDecodeError::InvalidTag(other)),
+        })
+    }
+
+    fn read_template(&mut self) -> Result<Template, MutationDecodeError> {
+        let name = self.read_static_str()?;
+        let body = (self.cursor, self.table.len());
+
+        // Read the body without leaking anything to find out if we already decoded the same template
+        self.skipping_template = true;
+        self.contents = Some(Vec::new());
+        let skipped = self.read_template_body(name);
+        self.skipping_template = false;
+        let contents = self.contents.take().unwrap_or_default();
+        skipped?;
+        if let Some((cached, template)) = self.decoder.templates.get(name) {
+            if *cached == contents {
+                return Ok(*template);
+            }
+        }
+
+        // The template is new or changed, so read the body again and leak it this time
+        (self.cursor, _) = body;
+        self.table.truncate(body.1);
+        let template = self.read_template_body(name)?;
+        self.decoder.templates.insert(name, (contents, template));
+        Ok(template)
+    }
+
+    fn read_template_body(&mut self, name: &'static str) -> Result<Template, MutationDecodeError> {
+        Ok(Template {
+            name,
+            roots: self.read_template_nodes(0)?,
+            node_paths: self.read_paths()?,
+            attr_paths: self.read_paths()?,
+        })
+    }
+
+    fn read_template_nodes(
+        &mut self,
+        depth: usize,
+    ) -> Result<&'static [TemplateNode], MutationDecodeError> {
+        if depth > MAX_TEMPLATE_DEPTH {
+            return Err(MutationDecodeError::TemplateTooDeep);
+        }
+        let len = self.read_usize()?;
+        let mut nodes = Vec::with_capacity(len.min(self.remaining()));
+        for _ in 0..len {
+            nodes.push(self.read_template_node(depth)?);
+        }
+        Ok(self.leak(nodes))
+    }
+
+    fn read_template_node(&mut self, depth: usize) -> Result<TemplateNode, MutationDecodeError> {
+        Ok(match self.read_u8()? {
+            0 => {
+                let tag = self.read_static_str()?;
+                let namespace = self.read_optional_static_str()?;
+                let attr_count = self.read_usize()?;
+                let mut attrs = Vec::with_capacity(attr_count.min(self.remaining()));
+                for _ in 0..attr_count {
+                    attrs.push(self.read_template_attribute()?);
+                }
+                let attrs = self.leak(attrs);
+                let children = self.read_template_nodes(depth + 1)?;
+                TemplateNode::Element {
+                    tag,
+                    namespace,
+                    attrs,
+                    children,
+                }
+            }
+            1 => TemplateNode::Text {
+                text: self.read_static_str()?,
+            },
+            2 => TemplateNode::Dynamic {
+                id: self.read_usize()?,
+            },
+            other => return Err(MutationDecodeError::InvalidTag(other)),
+        })
+    }
+
+    fn read_template_attribute(&mut self) -> Result<TemplateAttribute, MutationDecodeError> {
+        Ok(match self.read_u8()? {
+            0 => TemplateAttribute::Static {
+                name: self.read_static_str()?,
+                value: self.read_static_str()?,
+                namespace: self.read_optional_static_str()?,
+            },
+            1 => TemplateAttribute::Dynamic {
+                id: self.read_usize()?,
+            },
+            other => return Err(MutationDecodeError::InvalidTag(other)),
+        })
+    }
+
+    fn read_paths(&mut self) -> Result<&'static [&'static [u8]], MutationDecodeError> {
+        let len = self.read_usize()?;
+        let mut paths = Vec::with_capacity(len.min(self.remaining()));
+        for _ in 0..len {
+            paths.push(self.read_path()?);
+        }
+        Ok(self.leak(paths))
+    }
+
+    /// Leak part of a template so it can be stored in a [`Template`]
+    fn leak<T>(&self, items: Vec<T>) -> &'static [T] {
+        if self.skipping_template {
+            return &[];
+        }
+        Box::leak(items.into_boxed_slice())
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.cursor
+    }
+}
@@ -6,8 +6,8 @@ use std::{
 use crate::{
     any_props::AnyProps,
     innerlude::{
-        ElementRef, MountId, ScopeOrder, SuspenseBoundaryProps, SuspenseBoundaryPropsWithOwner,
-        VComponent, WriteMutations,
+        ElementRef, MountId, ProfileEventKind, ScopeOrder, SuspenseBoundaryProps,
+        SuspenseBoundaryPropsWithOwner, VComponent, WriteMutations,
     },
     nodes::VNode,
     prelude::SuspenseContext,
@@ -27,7 +27,12 @@ impl VirtualDom {
             SuspenseBoundaryProps::diff(scope_id, self, to)
         } else {
             let new_nodes = self.run_scope(scope_id);
+            let profiling = self.runtime.start_profile();
             self.diff_scope(to, scope_id, new_nodes);
+            if profiling {
+                self.runtime
+                    .finish_profile(scope_id, ProfileEventKind::Diff);
+            }
         }
     }
 
@@ -149,7 +154,9 @@ impl VNode {
         dom.run_and_diff_scope(to, scope_id);
 
         let height = dom.runtime.get_state(scope_id).unwrap().height;
-        dom.dirty_scopes.remove(&ScopeOrder::new(height, scope_id));
+        let order = ScopeOrder::new(height, scope_id);
+        dom.dirty_scopes.remove(&order);
+        dom.input_scopes.remove(&order);
     }
 
     fn replace_vcomponent(
@@ -20,6 +20,7 @@ use crate::{
 mod component;
 mod iterator;
 mod node;
+mod replay;
 
 impl VirtualDom {
     pub(crate) fn create_children(
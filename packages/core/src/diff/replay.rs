@@ -0,0 +1,133 @@
+//! Writing the mutations for nodes that are already mounted.
+//!
+//! This mirrors [`VNode::create`], but reuses the ids of the mounted nodes instead of creating new nodes or scopes, so
+//! the VirtualDom stays in sync with a renderer that rebuilt its DOM from the replayed mutations.
+
+use crate::{
+    arena::ElementId,
+    innerlude::{MountId, WriteMutations},
+    nodes::{AttributeValue, DynamicNode, VNode},
+    scopes::ScopeId,
+    virtual_dom::VirtualDom,
+    TemplateNode,
+};
+
+impl VirtualDom {
+    /// Replay the nodes the scope rendered last and return the number of nodes created on the stack
+    pub(crate) fn replay_scope(&mut self, scope: ScopeId, to: &mut impl WriteMutations) -> usize {
+        match self.scopes[scope.0]
+            .last_rendered_node
+            .as_deref()
+            .map(VNode::clone_mounted)
+        {
+            Some(node) => node.replay(self, to),
+            None => 0,
+        }
+    }
+}
+
+impl VNode {
+    fn replay(&self, dom: &mut VirtualDom, to: &mut impl WriteMutations) -> usize {
+        let mount = self.mount.get();
+        if !mount.mounted() {
+            return 0;
+        }
+        let template = self.template;
+        dom.register_template(to, template);
+
+        let mut nodes = template.node_paths.iter().copied().enumerate().peekable();
+        let mut attrs = template.attr_paths.iter().copied().enumerate().peekable();
+
+        template
+            .roots
+            .iter()
+            .enumerate()
+            .map(|(root_idx, root)| match root {
+                TemplateNode::Dynamic { id } => {
+                    nodes.next().unwrap();
+                    self.replay_dynamic_node(mount, *id, dom, to)
+                }
+                TemplateNode::Text { .. } | TemplateNode::Element { .. } => {
+                    to.load_template(
+                        template.name,
+                        root_idx,
+                        dom.mounts[mount.0].root_ids[root_idx],
+                    );
+
+                    if matches!(root, TemplateNode::Element { .. }) {
+                        let from_root_node =
+                            |(_, path): &(usize, &[u8])| path.first() == Some(&(root_idx as u8));
+
+                        // Replace the placeholders under the root with the dynamic nodes
+                        while let Some((idx, path)) = nodes.next_if(from_root_node) {
+                            let m = self.replay_dynamic_node(mount, idx, dom, to);
+                            if m > 0 {
+                                to.replace_placeholder_with_nodes(&path[1..], m);
+                            }
+                        }
+
+                        // Then write the attributes
+                        let mut last_path = None;
+                        while let Some((idx, path)) = attrs.next_if(from_root_node) {
+                            let id = dom.mounts[mount.0].mounted_attributes[idx];
+                            if path.len() > 1 && last_path != Some(path) {
+                                to.assign_node_id(&path[1..], id);
+                            }
+                            last_path = Some(path);
+                            for attribute in &*self.dynamic_attrs[idx] {
+                                match &attribute.value {
+                                    AttributeValue::Listener(_) => {
+                                        to.create_event_listener(&attribute.name[2..], id)
+                                    }
+                                    value => to.set_attribute(
+                                        attribute.name,
+                                        attribute.namespace,
+                                        value,
+                                        id,
+                                    ),
+                                }
+                            }
+                        }
+                    }
+
+                    1
+                }
+            })
+            .sum()
+    }
+
+    fn replay_dynamic_node(
+        &self,
+        mount: MountId,
+        idx: usize,
+        dom: &mut VirtualDom,
+        to: &mut impl WriteMutations,
+    ) -> usize {
+        let mounted = dom.mounts[mount.0].mounted_dynamic_nodes[idx];
+        let path = &self.template.node_paths[idx][1..];
+        match &self.dynamic_nodes[idx] {
+            DynamicNode::Component(_) => dom.replay_scope(ScopeId(mounted), to),
+            DynamicNode::Fragment(nodes) => nodes.iter().map(|node| node.replay(dom, to)).sum(),
+            DynamicNode::Text(text) => {
+                let id = ElementId(mounted);
+                if path.is_empty() {
+                    to.create_text_node(&text.value, id);
+                    1
+                } else {
+                    to.hydrate_text_node(path, &text.value, id);
+                    0
+                }
+            }
+            DynamicNode::Placeholder(_) => {
+                let id = ElementId(mounted);
+                if path.is_empty() {
+                    to.create_placeholder(id);
+                    1
+                } else {
+                    to.assign_node_id(path, id);
+                    0
+                }
+            }
+        }
+    }
+}
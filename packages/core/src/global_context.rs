@@ -415,6 +415,24 @@ pub fn after_render(f: impl FnMut() + 'static) {
     let _ = Runtime::with_current_scope(|cx| cx.push_after_render(f));
 }
 
+/// Attach a debug value to the last hook that was called in the current component. Devtools show the value next to the
+/// hook when inspecting the component.
+///
+/// This is useful to show the value of a signal or other state when inspecting the component tree:
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// fn app() -> Element {
+///     let count = use_signal(|| 0);
+///     use_debug_value(count);
+///
+///     rsx! { "{count}" }
+/// }
+/// ```
+pub fn use_debug_value(value: impl std::fmt::Debug) {
+    let _ = Runtime::with_current_scope(|cx| cx.set_debug_value(format!("{value:?}")));
+}
+
 /// Use a hook with a cleanup function
 pub fn use_hook_with_cleanup<T: Clone + 'static>(
     hook: impl FnOnce() -> T,
@@ -0,0 +1,203 @@
+//! Snapshots of the live component tree for devtools.
+
+use crate::{innerlude::DynamicNode, ElementId, ScopeId, VNode, VirtualDom};
+
+/// A snapshot of every mounted component in a [`VirtualDom`]. Created with [`VirtualDom::component_tree`]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentTree {
+    /// The scope at the top of the tree
+    pub root: ScopeId,
+
+    /// Every mounted scope in the tree in depth first order
+    pub scopes: Vec<ScopeSnapshot>,
+}
+
+impl ComponentTree {
+    /// Get the snapshot of a scope in the tree
+    pub fn get(&self, id: ScopeId) -> Option<&ScopeSnapshot> {
+        self.scopes.iter().find(|scope| scope.id == id)
+    }
+}
+
+/// A snapshot of a single component. Created with [`VirtualDom::inspect_scope`]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeSnapshot {
+    /// The id of the scope
+    pub id: ScopeId,
+
+    /// The name of the component
+    pub name: String,
+
+    /// The scope that rendered this component
+    pub parent: Option<ScopeId>,
+
+    /// The components this component rendered, in the order they appear in the tree
+    pub children: Vec<ScopeId>,
+
+    /// The depth of the scope in the tree
+    pub height: u32,
+
+    /// The number of times the component has run
+    pub render_count: usize,
+
+    /// The type name of the props the component was rendered with
+    pub props: String,
+
+    /// The [`Debug`](std::fmt::Debug) output of the props. This is only set for props without generics that use
+    /// `#[derive(Props)]` and implement `Debug`
+    pub props_debug: Option<String>,
+
+    /// The hooks the component uses, in the order they are called
+    pub hooks: Vec<HookSnapshot>,
+
+    /// The type names of the contexts this component provides. Contexts inserted without a type, like the root
+    /// contexts renderers add at launch, are named `<unknown>` until they are read with their type
+    pub contexts: Vec<String>,
+
+    /// The top level elements, text nodes and placeholders the component is mounted to. Renderers can use these to
+    /// highlight the component.
+    pub elements: Vec<ElementId>,
+}
+
+/// A snapshot of a single hook in a component. Only the type of the hook is known, so the value is only captured if
+/// the component describes it with [`use_debug_value`](crate::prelude::use_debug_value)
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookSnapshot {
+    /// The type of the value the hook stores
+    pub type_name: String,
+
+    /// The value set with [`use_debug_value`](crate::prelude::use_debug_value) after calling the hook, if any
+    pub debug_value: Option<String>,
+}
+
+impl VirtualDom {
+    /// Take a snapshot of every mounted component in the tree for devtools.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_core::*;
+    /// fn app() -> Element {
+    ///     let count = use_signal(|| 0);
+    ///     use_debug_value(count);
+    ///     rsx! { "{count}" }
+    /// }
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.rebuild(&mut NoOpMutations);
+    ///
+    /// let tree = dom.component_tree();
+    /// let app = tree.get(ScopeId::APP).unwrap();
+    /// assert_eq!(app.hooks[0].debug_value.as_deref(), Some("0"));
+    /// ```
+    pub fn component_tree(&self) -> ComponentTree {
+        let mut scopes = Vec::new();
+        let mut stack = vec![ScopeId::ROOT];
+        while let Some(id) = stack.pop() {
+            if let Some(snapshot) = self.inspect_scope(id) {
+                stack.extend(snapshot.children.iter().rev());
+                scopes.push(snapshot);
+            }
+        }
+
+        ComponentTree {
+            root: ScopeId::ROOT,
+            scopes,
+        }
+    }
+
+    /// Take a snapshot of a single component for devtools. Returns `None` if the scope does not exist.
+    pub fn inspect_scope(&self, id: ScopeId) -> Option<ScopeSnapshot> {
+        let scope = self.get_scope(id)?;
+        let state = scope.state();
+
+        let mut children = Vec::new();
+        let mut elements = Vec::new();
+        if let Some(node) = scope.try_root_node() {
+            self.collect_child_scopes(node, &mut children);
+            self.collect_root_elements(node, &mut elements);
+        }
+
+        let debug_values = state.debug_values.borrow();
+        let hooks = state
+            .hook_names
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(index, type_name)| HookSnapshot {
+                type_name: type_name.to_string(),
+                debug_value: debug_values.get(&index).cloned(),
+            })
+            .collect();
+        let contexts = state
+            .context_names
+            .borrow()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        Some(ScopeSnapshot {
+            id,
+            name: state.name.to_string(),
+            parent: state.parent_id,
+            children,
+            height: state.height,
+            render_count: state.render_count.get(),
+            props: scope.props.props_type_name().to_string(),
+            props_debug: scope.props.props_debug(),
+            hooks,
+            contexts,
+            elements,
+        })
+    }
+
+    fn collect_child_scopes(&self, node: &VNode, to: &mut Vec<ScopeId>) {
+        for (idx, dynamic) in node.dynamic_nodes.iter().enumerate() {
+            match dynamic {
+                DynamicNode::Component(component) => {
+                    to.extend(
+                        component
+                            .mounted_scope_id(idx, node, self)
+                            .filter(|id| self.get_scope(*id).is_some()),
+                    );
+                }
+                DynamicNode::Fragment(nodes) => {
+                    for node in nodes {
+                        self.collect_child_scopes(node, to);
+                    }
+                }
+                DynamicNode::Text(_) | DynamicNode::Placeholder(_) => {}
+            }
+        }
+    }
+
+    fn collect_root_elements(&self, node: &VNode, to: &mut Vec<ElementId>) {
+        for (root_idx, root) in node.template.roots.iter().enumerate() {
+            let Some(idx) = root.dynamic_id() else {
+                to.extend(node.mounted_root(root_idx, self));
+                continue;
+            };
+            match &node.dynamic_nodes[idx] {
+                DynamicNode::Component(component) => {
+                    if let Some(child) = component
+                        .mounted_scope(idx, node, self)
+                        .and_then(|scope| scope.try_root_node())
+                    {
+                        self.collect_root_elements(child, to);
+                    }
+                }
+                DynamicNode::Fragment(nodes) => {
+                    for node in nodes {
+                        self.collect_root_elements(node, to);
+                    }
+                }
+                DynamicNode::Text(_) | DynamicNode::Placeholder(_) => {
+                    to.extend(node.mounted_dynamic_node(idx, self));
+                }
+            }
+        }
+    }
+}
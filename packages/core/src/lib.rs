@@ -5,6 +5,7 @@
 
 mod any_props;
 mod arena;
+mod binary_mutations;
 mod diff;
 mod effect;
 mod error_boundary;
@@ -12,8 +13,10 @@ mod events;
 mod fragment;
 mod generational_box;
 mod global_context;
+mod inspect;
 mod mutations;
 mod nodes;
+mod profiler;
 mod properties;
 mod reactive_context;
 mod render_error;
@@ -25,6 +28,7 @@ mod scope_context;
 mod scopes;
 mod suspense;
 mod tasks;
+mod test_dom;
 mod virtual_dom;
 
 mod hotreload_utils;
@@ -34,6 +38,9 @@ mod hotreload_utils;
 pub mod internal {
     pub use crate::properties::verify_component_called_as_component;
 
+    #[doc(hidden)]
+    pub use crate::properties::{DebugProps, ViaDebug, ViaFallback};
+
     #[doc(hidden)]
     pub use crate::hotreload_utils::{
         DynamicLiteralPool, DynamicValuePool, FmtSegment, FmtedSegments, HotReloadAttributeValue,
@@ -45,14 +52,17 @@ pub mod internal {
 pub(crate) mod innerlude {
     pub(crate) use crate::any_props::*;
     pub use crate::arena::*;
+    pub use crate::binary_mutations::*;
     pub(crate) use crate::effect::*;
     pub use crate::error_boundary::*;
     pub use crate::events::*;
     pub use crate::fragment::*;
     pub use crate::generational_box::*;
     pub use crate::global_context::*;
+    pub use crate::inspect::*;
     pub use crate::mutations::*;
     pub use crate::nodes::*;
+    pub use crate::profiler::*;
     pub use crate::properties::*;
     pub use crate::reactive_context::*;
     pub use crate::render_error::*;
@@ -61,6 +71,7 @@ pub(crate) mod innerlude {
     pub use crate::scopes::*;
     pub use crate::suspense::*;
     pub use crate::tasks::*;
+    pub use crate::test_dom::*;
     pub use crate::virtual_dom::*;
 
     /// An [`Element`] is a possibly-none [`VNode`] created by calling `render` on [`ScopeId`] or [`ScopeState`].
@@ -74,11 +85,15 @@ pub(crate) mod innerlude {
 
 pub use crate::innerlude::{
     fc_to_builder, generation, schedule_update, schedule_update_any, use_hook, vdom_is_rendering,
-    AnyValue, Attribute, AttributeValue, CapturedError, Component, ComponentFunction, DynamicNode,
-    Element, ElementId, Event, Fragment, HasAttributes, IntoDynNode, MarkerWrapper, Mutation,
-    Mutations, NoOpMutations, Ok, Properties, RenderReturn, Result, Runtime, ScopeId, ScopeState,
-    SpawnIfAsync, Task, Template, TemplateAttribute, TemplateNode, VComponent, VNode, VNodeInner,
-    VPlaceholder, VText, VirtualDom, WriteMutations,
+    AnyValue, Attribute, AttributeValue, BinaryMutations, CapturedError, Component,
+    ComponentFunction, ComponentTree, DynamicNode, Element, ElementId, Event, Fragment,
+    HasAttributes, HookSnapshot, IntoDynNode, MarkerWrapper, Mutation, MutationDecodeError,
+    MutationDecoder, Mutations, NoOpMutations, NodeHandle, Ok, Profile, ProfileEvent,
+    ProfileEventKind, Properties, RenderPriority, RenderReturn, RerunCause, Result, Runtime,
+    ScopeId, ScopeProfile, ScopeSnapshot, ScopeState, SpawnIfAsync, Task, Template,
+    TemplateAttribute, TemplateNode, TestAttribute, TestDom, TestNode, TestNodeKind, VComponent,
+    VNode, VNodeInner, VPlaceholder, VText, VirtualDom, WriteMutations, MAX_TEMPLATE_DEPTH,
+    MUTATION_ENCODING_VERSION,
 };
 
 /// The purpose of this module is to alleviate imports of many common types
@@ -86,18 +101,18 @@ pub use crate::innerlude::{
 /// This includes types like [`Element`], and [`Component`].
 pub mod prelude {
     pub use crate::innerlude::{
-        consume_context, consume_context_from_scope, current_owner, current_scope_id,
+        batch, consume_context, consume_context_from_scope, current_owner, current_scope_id,
         fc_to_builder, generation, has_context, needs_update, needs_update_any, parent_scope,
         provide_context, provide_error_boundary, provide_root_context, queue_effect, remove_future,
         schedule_update, schedule_update_any, spawn, spawn_forever, spawn_isomorphic, suspend,
-        throw_error, try_consume_context, use_after_render, use_before_render, use_drop, use_hook,
-        use_hook_with_cleanup, with_owner, AnyValue, Attribute, Callback, Component,
-        ComponentFunction, Context, Element, ErrorBoundary, ErrorContext, Event, EventHandler,
-        Fragment, HasAttributes, IntoAttributeValue, IntoDynNode, OptionStringFromMarker,
-        Properties, ReactiveContext, RenderError, RenderReturn, Runtime, RuntimeGuard, ScopeId,
-        ScopeState, SuperFrom, SuperInto, SuspendedFuture, SuspenseBoundary, SuspenseBoundaryProps,
-        SuspenseContext, SuspenseExtension, Task, Template, TemplateAttribute, TemplateNode, VNode,
-        VNodeInner, VirtualDom,
+        throw_error, try_consume_context, use_after_render, use_before_render, use_debug_value,
+        use_drop, use_hook, use_hook_with_cleanup, with_owner, AnyValue, Attribute, Callback,
+        Component, ComponentFunction, Context, Element, ErrorBoundary, ErrorContext, Event,
+        EventHandler, Fragment, HasAttributes, IntoAttributeValue, IntoDynNode,
+        OptionStringFromMarker, Properties, ReactiveContext, RenderError, RenderReturn, Runtime,
+        RuntimeGuard, ScopeId, ScopeState, SuperFrom, SuperInto, SuspendedFuture, SuspenseBoundary,
+        SuspenseBoundaryProps, SuspenseContext, SuspenseExtension, Task, Template,
+        TemplateAttribute, TemplateNode, VNode, VNodeInner, VirtualDom,
     };
 }
 
@@ -601,6 +601,7 @@ impl VComponent {
         let props = Box::new(VProps::new(
             component,
             <P as Properties>::memoize,
+            <P as Properties>::debug_props,
             props,
             fn_name,
         ));
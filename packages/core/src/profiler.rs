@@ -0,0 +1,401 @@
+//! An opt-in profiler that records how long each scope takes to render and diff.
+
+use std::{fmt::Write, panic::Location, rc::Rc, time::Duration};
+
+use rustc_hash::FxHashMap;
+
+use crate::{innerlude::WriteMutations, AttributeValue, ElementId, Runtime, ScopeId, Template};
+
+/// The reason a scope was rerun
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RerunCause {
+    /// The location the value (signal, memo, etc) that changed was created at.
+    ///
+    /// This is only known in debug builds and for reactive values that report their source when they mark their
+    /// subscribers as dirty.
+    pub source: Option<&'static Location<'static>>,
+}
+
+/// The kind of work a [`ProfileEvent`] measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileEventKind {
+    /// Running the component function
+    Render,
+
+    /// Diffing the new nodes against the last rendered nodes and writing the mutations
+    Diff,
+}
+
+/// A single measured render or diff of a scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileEvent {
+    /// The scope that was rendered or diffed
+    pub scope: ScopeId,
+
+    /// The name of the component the scope belongs to
+    pub name: &'static str,
+
+    /// The kind of work that was measured
+    pub kind: ProfileEventKind,
+
+    /// When the work started, relative to when profiling was enabled
+    pub start: Duration,
+
+    /// How long the work took, including any renders and diffs of child scopes that happened during it
+    pub duration: Duration,
+
+    /// How long the work took, excluding renders and diffs of child scopes that happened during it
+    pub self_duration: Duration,
+
+    /// The number of mutations written while diffing, excluding mutations written by child scopes. This is always zero
+    /// for renders
+    pub mutations: usize,
+
+    /// The reactive values that caused this render. This is always empty for diffs and for renders that were not
+    /// caused by a reactive value like the initial render or rerunning a component with new props
+    pub causes: Vec<RerunCause>,
+}
+
+/// The aggregated profile of a single scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeProfile {
+    /// The id of the scope
+    pub id: ScopeId,
+
+    /// The name of the component the scope belongs to
+    pub name: &'static str,
+
+    /// The number of times the component was run
+    pub render_count: usize,
+
+    /// The total time spent running the component
+    pub render_time: Duration,
+
+    /// The total time spent diffing the component, excluding time spent rendering and diffing child components
+    pub diff_time: Duration,
+
+    /// The total number of mutations written while diffing the component, excluding mutations written by child
+    /// components
+    pub mutations: usize,
+
+    /// Every reactive value that caused the component to rerun, in order
+    pub causes: Vec<RerunCause>,
+}
+
+impl ScopeProfile {
+    fn new(id: ScopeId, name: &'static str) -> Self {
+        Self {
+            id,
+            name,
+            render_count: 0,
+            render_time: Duration::ZERO,
+            diff_time: Duration::ZERO,
+            mutations: 0,
+            causes: Vec::new(),
+        }
+    }
+}
+
+/// A snapshot of everything the profiler has recorded. Created with [`VirtualDom::profile`](crate::VirtualDom::profile)
+/// or [`VirtualDom::disable_profiling`](crate::VirtualDom::disable_profiling)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profile {
+    /// The aggregated profile of every scope that was rendered, sorted by scope id
+    pub scopes: Vec<ScopeProfile>,
+
+    /// Every render and diff in the order they finished
+    pub events: Vec<ProfileEvent>,
+}
+
+impl Profile {
+    /// Get the aggregated profile for a scope
+    pub fn scope(&self, id: ScopeId) -> Option<&ScopeProfile> {
+        self.scopes.iter().find(|scope| scope.id == id)
+    }
+
+    /// Export the events as [Chrome trace event](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU)
+    /// JSON that can be loaded in `chrome://tracing`, [Perfetto](https://ui.perfetto.dev) or the Chrome devtools
+    /// performance panel.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut json = String::from("{\"traceEvents\":[");
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let category = match event.kind {
+                ProfileEventKind::Render => "render",
+                ProfileEventKind::Diff => "diff",
+            };
+            json.push_str("{\"name\":");
+            write_json_string(event.name, &mut json);
+            _ = write!(
+                json,
+                ",\"cat\":\"{category}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1,\"args\":{{\"scope\":{},\"mutations\":{},\"causes\":[",
+                event.start.as_micros(),
+                event.duration.as_micros(),
+                event.scope.0,
+                event.mutations,
+            );
+            for (i, cause) in event.causes.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                match cause.source {
+                    Some(source) => write_json_string(&source.to_string(), &mut json),
+                    None => json.push_str("null"),
+                }
+            }
+            json.push_str("]}}");
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_json_string(value: &str, to: &mut String) {
+    to.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => to.push_str("\\\""),
+            '\\' => to.push_str("\\\\"),
+            c if c.is_control() => _ = write!(to, "\\u{:04x}", c as u32),
+            c => to.push(c),
+        }
+    }
+    to.push('"');
+}
+
+/// The state of an active profiler. This lives in the [`Runtime`](crate::Runtime) so reactive contexts can report
+/// why scopes are marked dirty.
+pub(crate) struct Profiler {
+    clock: Box<dyn Fn() -> Duration>,
+    scopes: FxHashMap<ScopeId, ScopeProfile>,
+    events: Vec<ProfileEvent>,
+    pending_causes: FxHashMap<ScopeId, Vec<RerunCause>>,
+    // The renders and diffs that are currently being measured. Diffs can render and diff child scopes, so they nest
+    stack: Vec<Frame>,
+}
+
+struct Frame {
+    start: Duration,
+    mutations_before: usize,
+    nested_time: Duration,
+    nested_mutations: usize,
+}
+
+impl Profiler {
+    pub(crate) fn new(clock: Box<dyn Fn() -> Duration>) -> Self {
+        Self {
+            clock,
+            scopes: FxHashMap::default(),
+            events: Vec::new(),
+            pending_causes: FxHashMap::default(),
+            stack: Vec::new(),
+        }
+    }
+
+    fn now(&self) -> Duration {
+        (self.clock)()
+    }
+
+    /// Start measuring a render or diff. `mutations_written` is the total number of mutations written so far
+    pub(crate) fn start(&mut self, mutations_written: usize) {
+        let start = self.now();
+        self.stack.push(Frame {
+            start,
+            mutations_before: mutations_written,
+            nested_time: Duration::ZERO,
+            nested_mutations: 0,
+        });
+    }
+
+    pub(crate) fn record_cause(&mut self, scope: ScopeId, cause: RerunCause) {
+        self.pending_causes.entry(scope).or_default().push(cause);
+    }
+
+    /// Finish measuring the last render or diff that was started
+    pub(crate) fn finish(
+        &mut self,
+        scope: ScopeId,
+        name: &'static str,
+        kind: ProfileEventKind,
+        mutations_written: usize,
+    ) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        let duration = self.now().saturating_sub(frame.start);
+        let total_mutations = mutations_written.wrapping_sub(frame.mutations_before);
+        if let Some(parent) = self.stack.last_mut() {
+            parent.nested_time += duration;
+            parent.nested_mutations += total_mutations;
+        }
+        let self_duration = duration.saturating_sub(frame.nested_time);
+        let mutations = match kind {
+            ProfileEventKind::Render => 0,
+            ProfileEventKind::Diff => total_mutations.saturating_sub(frame.nested_mutations),
+        };
+        let causes = match kind {
+            ProfileEventKind::Render => self.pending_causes.remove(&scope).unwrap_or_default(),
+            ProfileEventKind::Diff => Vec::new(),
+        };
+
+        let profile = self
+            .scopes
+            .entry(scope)
+            .or_insert_with(|| ScopeProfile::new(scope, name));
+        // Scope ids are reused, so make sure we don't mix up the stats of different components
+        if profile.name != name {
+            *profile = ScopeProfile::new(scope, name);
+        }
+        match kind {
+            ProfileEventKind::Render => {
+                profile.render_count += 1;
+                profile.render_time += self_duration;
+                profile.causes.extend(causes.iter().copied());
+            }
+            ProfileEventKind::Diff => {
+                profile.diff_time += self_duration;
+                profile.mutations += mutations;
+            }
+        }
+
+        self.events.push(ProfileEvent {
+            scope,
+            name,
+            kind,
+            start: frame.start,
+            duration,
+            self_duration,
+            mutations,
+            causes,
+        });
+    }
+
+    pub(crate) fn snapshot(&self) -> Profile {
+        let mut scopes: Vec<_> = self.scopes.values().cloned().collect();
+        scopes.sort_by_key(|scope| scope.id);
+        Profile {
+            scopes,
+            events: self.events.clone(),
+        }
+    }
+}
+
+/// A [`WriteMutations`] implementation that counts the mutations written to the inner writer in the runtime so the
+/// profiler can attribute them to the scope being diffed. This is only used while profiling is enabled
+pub(crate) struct CountingMutations<'a, M> {
+    inner: &'a mut M,
+    runtime: Rc<Runtime>,
+}
+
+impl<'a, M> CountingMutations<'a, M> {
+    pub(crate) fn new(inner: &'a mut M, runtime: Rc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    fn count(&self) {
+        let written = &self.runtime.mutations_written;
+        written.set(written.get().wrapping_add(1));
+    }
+}
+
+impl<M: WriteMutations> WriteMutations for CountingMutations<'_, M> {
+    fn register_template(&mut self, template: Template) {
+        self.inner.register_template(template)
+    }
+
+    fn append_children(&mut self, id: ElementId, m: usize) {
+        self.count();
+        self.inner.append_children(id, m)
+    }
+
+    fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
+        self.count();
+        self.inner.assign_node_id(path, id)
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) {
+        self.count();
+        self.inner.create_placeholder(id)
+    }
+
+    fn create_text_node(&mut self, value: &str, id: ElementId) {
+        self.count();
+        self.inner.create_text_node(value, id)
+    }
+
+    fn hydrate_text_node(&mut self, path: &'static [u8], value: &str, id: ElementId) {
+        self.count();
+        self.inner.hydrate_text_node(path, value, id)
+    }
+
+    fn load_template(&mut self, name: &'static str, index: usize, id: ElementId) {
+        self.count();
+        self.inner.load_template(name, index, id)
+    }
+
+    fn replace_node_with(&mut self, id: ElementId, m: usize) {
+        self.count();
+        self.inner.replace_node_with(id, m)
+    }
+
+    fn replace_placeholder_with_nodes(&mut self, path: &'static [u8], m: usize) {
+        self.count();
+        self.inner.replace_placeholder_with_nodes(path, m)
+    }
+
+    fn insert_nodes_after(&mut self, id: ElementId, m: usize) {
+        self.count();
+        self.inner.insert_nodes_after(id, m)
+    }
+
+    fn insert_nodes_before(&mut self, id: ElementId, m: usize) {
+        self.count();
+        self.inner.insert_nodes_before(id, m)
+    }
+
+    fn set_attribute(
+        &mut self,
+        name: &'static str,
+        ns: Option<&'static str>,
+        value: &AttributeValue,
+        id: ElementId,
+    ) {
+        self.count();
+        self.inner.set_attribute(name, ns, value, id)
+    }
+
+    fn set_node_text(&mut self, value: &str, id: ElementId) {
+        self.count();
+        self.inner.set_node_text(value, id)
+    }
+
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.count();
+        self.inner.create_event_listener(name, id)
+    }
+
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.count();
+        self.inner.remove_event_listener(name, id)
+    }
+
+    fn remove_node(&mut self, id: ElementId) {
+        self.count();
+        self.inner.remove_node(id)
+    }
+
+    fn push_root(&mut self, id: ElementId) {
+        self.count();
+        self.inner.push_root(id)
+    }
+
+    fn swap_subtree(&mut self, subtree_index: usize) {
+        self.inner.swap_subtree(subtree_index)
+    }
+
+    fn mark_scope_dirty(&mut self, scope_id: ScopeId) {
+        self.inner.mark_scope_dirty(scope_id)
+    }
+}
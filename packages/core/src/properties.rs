@@ -56,6 +56,12 @@ pub trait Properties: Clone + Sized + 'static {
     /// Make the old props equal to the new props. Return if the props were equal and should be memoized.
     fn memoize(&mut self, other: &Self) -> bool;
 
+    /// Format the props for devtools. The derive macro uses the [`Debug`](std::fmt::Debug) implementation of props
+    /// without generics if there is one. Returns `None` by default.
+    fn debug_props(&self) -> Option<String> {
+        None
+    }
+
     /// Create a component from the props.
     fn into_vcomponent<M: 'static>(self, render_fn: impl ComponentFunction<Self, M>) -> VComponent {
         let type_name = std::any::type_name_of_val(&render_fn);
@@ -143,6 +149,33 @@ pub fn verify_component_called_as_component<C: ComponentFunction<P, M>, P, M>(co
     component_called_as_function(component);
 }
 
+/// Wraps props so the derive macro can format them with [`Debug`](std::fmt::Debug) only if they implement it.
+/// `(&&DebugProps(props)).debug_props()` picks [`ViaDebug`] when the props are `Debug` and [`ViaFallback`] otherwise.
+#[doc(hidden)]
+pub struct DebugProps<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaDebug {
+    fn debug_props(&self) -> Option<String>;
+}
+
+impl<T: std::fmt::Debug> ViaDebug for &DebugProps<'_, T> {
+    fn debug_props(&self) -> Option<String> {
+        Some(format!("{:?}", self.0))
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaFallback {
+    fn debug_props(&self) -> Option<String>;
+}
+
+impl<T> ViaFallback for DebugProps<'_, T> {
+    fn debug_props(&self) -> Option<String> {
+        None
+    }
+}
+
 /// Any component that implements the `ComponentFn` trait can be used as a component.
 ///
 /// This trait is automatically implemented for functions that are in one of the following forms:
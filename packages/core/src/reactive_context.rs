@@ -2,7 +2,7 @@ use crate::{
     prelude::{current_scope_id, ScopeId},
     scope_context::Scope,
     tasks::SchedulerMsg,
-    Runtime,
+    RerunCause, Runtime,
 };
 use futures_channel::mpsc::UnboundedReceiver;
 use generational_box::{GenerationalBox, SyncStorage};
@@ -30,6 +30,80 @@ impl Eq for ReactiveContext {}
 
 thread_local! {
     static CURRENT: RefCell<Vec<ReactiveContext>> = const { RefCell::new(vec![]) };
+    static BATCH: RefCell<Batch> = const { RefCell::new(Batch { depth: 0, pending: Vec::new() }) };
+}
+
+/// The reactive contexts that were marked dirty inside of [`batch`] on this thread
+struct Batch {
+    depth: usize,
+    pending: Vec<(
+        ReactiveContext,
+        Option<&'static std::panic::Location<'static>>,
+    )>,
+}
+
+/// Run a closure and defer marking reactive contexts as dirty until the closure finishes.
+///
+/// Writing to several signals normally marks the subscribers of each signal dirty as soon as it is written, so a memo
+/// that is read between the writes may recompute with half of the new state. Inside of a batch, every reactive context
+/// that would be marked dirty is queued instead. Each queued context is marked dirty once when the outermost batch
+/// ends. Memos read inside of the batch keep returning the value from before the batch, and they recompute with all
+/// of the writes once the batch ends.
+///
+/// Batches are tracked per thread. Signals with [`SyncStorage`] can be written in a batch on any thread, and their
+/// subscribers are notified from that thread when the batch ends.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// fn app() -> Element {
+///     let mut first = use_signal(|| "Jane".to_string());
+///     let mut last = use_signal(|| "Doe".to_string());
+///     let full_name = use_memo(move || format!("{first} {last}"));
+///
+///     rsx! {
+///         "{full_name}"
+///         button {
+///             // The memo never sees "John Doe"
+///             onclick: move |_| batch(|| {
+///                 first.set("John".to_string());
+///                 last.set("Smith".to_string());
+///             }),
+///             "Change name"
+///         }
+///     }
+/// }
+/// ```
+pub fn batch<O>(f: impl FnOnce() -> O) -> O {
+    /// Ends the batch even if the closure panics
+    struct EndBatch;
+
+    impl Drop for EndBatch {
+        fn drop(&mut self) {
+            let pending = BATCH.with_borrow_mut(|batch| {
+                batch.depth -= 1;
+                match batch.depth {
+                    0 => std::mem::take(&mut batch.pending),
+                    _ => Vec::new(),
+                }
+            });
+            // Don't run user code while the thread is unwinding
+            if std::thread::panicking() {
+                return;
+            }
+            // The key type is mutable, but the hash is stable through mutations because we hash by id
+            #[allow(clippy::mutable_key_type)]
+            let mut marked = HashSet::new();
+            for (context, source) in pending {
+                if marked.insert(context) {
+                    context.mark_dirty_with_source(source);
+                }
+            }
+        }
+    }
+
+    BATCH.with_borrow_mut(|batch| batch.depth += 1);
+    let _end = EndBatch;
+    f()
 }
 
 impl std::fmt::Display for ReactiveContext {
@@ -87,7 +161,6 @@ impl ReactiveContext {
             subscribers: Default::default(),
             #[cfg(debug_assertions)]
             origin,
-            #[cfg(debug_assertions)]
             scope: None,
         };
 
@@ -124,7 +197,6 @@ impl ReactiveContext {
             subscribers: Default::default(),
             #[cfg(debug_assertions)]
             origin: std::panic::Location::caller(),
-            #[cfg(debug_assertions)]
             scope: Some(id),
         };
 
@@ -224,7 +296,31 @@ impl ReactiveContext {
     ///
     /// Returns true if the context was marked as dirty, or false if the context has been dropped
     pub fn mark_dirty(&self) -> bool {
+        self.mark_dirty_with_source(None)
+    }
+
+    /// Marks this reactive context as dirty and record the location of the reactive value that changed.
+    ///
+    /// If profiling is enabled with [`VirtualDom::enable_profiling`](crate::VirtualDom::enable_profiling), the source
+    /// is recorded as the [`RerunCause`] of the next render of the scope this context belongs to.
+    ///
+    /// Returns true if the context was marked as dirty, or false if the context has been dropped
+    pub fn mark_dirty_with_source(
+        &self,
+        source: Option<&'static std::panic::Location<'static>>,
+    ) -> bool {
         if let Ok(mut self_write) = self.inner.try_write() {
+            // Inside of a batch, the context is marked dirty once the batch ends
+            let deferred = BATCH.with_borrow_mut(|batch| {
+                if batch.depth > 0 {
+                    batch.pending.push((*self, source));
+                }
+                batch.depth > 0
+            });
+            if deferred {
+                return true;
+            }
+
             #[cfg(debug_assertions)]
             {
                 tracing::trace!(
@@ -233,6 +329,10 @@ impl ReactiveContext {
                 );
             }
 
+            if let (Some(scope), Ok(runtime)) = (self_write.scope, Runtime::current()) {
+                runtime.record_rerun_cause(scope, RerunCause { source });
+            }
+
             (self_write.update)();
 
             true
@@ -254,6 +354,29 @@ impl ReactiveContext {
     pub fn origin_scope(&self) -> ScopeId {
         self.scope
     }
+
+    /// Get the id of the reactive context. The id is unique while the reactive context is alive
+    pub fn id(&self) -> generational_box::GenerationalBoxId {
+        self.inner.id()
+    }
+
+    /// Get the scope that reruns when this context is marked dirty. This is only set for the reactive contexts of
+    /// components
+    pub fn rerun_scope(&self) -> Option<ScopeId> {
+        self.inner.try_read().ok()?.scope
+    }
+
+    /// Get the location the reactive context was created at. This is only available in debug builds
+    pub fn created_at(&self) -> Option<&'static std::panic::Location<'static>> {
+        #[cfg(debug_assertions)]
+        {
+            self.inner.try_read().ok().map(|inner| inner.origin)
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            None
+        }
+    }
 }
 
 impl Hash for ReactiveContext {
@@ -299,7 +422,6 @@ struct Inner {
     #[cfg(debug_assertions)]
     origin: &'static std::panic::Location<'static>,
 
-    #[cfg(debug_assertions)]
-    // The scope that this reactive context is associated with
+    // The scope that this reactive context reruns when it is marked dirty
     scope: Option<ScopeId>,
 }
@@ -1,4 +1,4 @@
-use crate::innerlude::{DirtyTasks, Effect};
+use crate::innerlude::{DirtyTasks, Effect, ProfileEventKind, Profiler, RerunCause};
 use crate::scope_context::SuspenseLocation;
 use crate::{
     innerlude::{LocalTask, SchedulerMsg},
@@ -48,6 +48,12 @@ pub struct Runtime {
 
     // Tasks that are waiting to be polled
     pub(crate) dirty_tasks: RefCell<BTreeSet<DirtyTasks>>,
+
+    // The profiler if profiling is enabled
+    pub(crate) profiler: RefCell<Option<Profiler>>,
+
+    // The total number of mutations written while diffing scopes
+    pub(crate) mutations_written: Cell<usize>,
 }
 
 impl Runtime {
@@ -63,6 +69,8 @@ impl Runtime {
             suspended_tasks: Default::default(),
             pending_effects: Default::default(),
             dirty_tasks: Default::default(),
+            profiler: Default::default(),
+            mutations_written: Default::default(),
         })
     }
 
@@ -224,6 +232,41 @@ impl Runtime {
             .ok_or(RuntimeError::new())
     }
 
+    /// Check if the profiler is enabled
+    pub(crate) fn is_profiling(&self) -> bool {
+        matches!(self.profiler.try_borrow().as_deref(), Ok(Some(_)))
+    }
+
+    /// Start measuring a render or diff if profiling is enabled. Returns `true` if the measurement was started and
+    /// must be finished with [`Runtime::finish_profile`]
+    pub(crate) fn start_profile(&self) -> bool {
+        match self.profiler.try_borrow_mut().as_deref_mut() {
+            Ok(Some(profiler)) => {
+                profiler.start(self.mutations_written.get());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Finish measuring the last render or diff started with [`Runtime::start_profile`] and record it for a scope
+    pub(crate) fn finish_profile(&self, scope: ScopeId, kind: ProfileEventKind) {
+        let name = self
+            .get_state(scope)
+            .map(|scope| scope.name)
+            .unwrap_or_default();
+        if let Ok(Some(profiler)) = self.profiler.try_borrow_mut().as_deref_mut() {
+            profiler.finish(scope, name, kind, self.mutations_written.get());
+        }
+    }
+
+    /// Record why a scope was marked dirty if profiling is enabled
+    pub(crate) fn record_rerun_cause(&self, scope: ScopeId, cause: RerunCause) {
+        if let Ok(Some(profiler)) = self.profiler.try_borrow_mut().as_deref_mut() {
+            profiler.record_cause(scope, cause);
+        }
+    }
+
     /// Finish a render. This will mark all effects as ready to run and send the render signal.
     pub(crate) fn finish_render(&self) {
         // If there are new effects we can run, send a message to the scheduler to run them (after the renderer has applied the mutations)
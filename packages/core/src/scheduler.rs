@@ -75,6 +75,14 @@
 //! 3. Effects:
 //!    Description: Effects should always run after all changes to the DOM have been applied.
 //!    Priority: These are the lowest priority tasks in the scheduler. They are run after all other dirty scopes and futures have been resolved. Other tasks may cause components to rerun, which would update the DOM. These effects should only run after the DOM has been updated.
+//!
+//! ## Priority lanes
+//!
+//! When rendering with a deadline ([`VirtualDom::render_with_deadline`]), dirty scopes are split into two lanes:
+//! 1. Input: Scopes marked dirty while handling an event (or explicitly with [`RenderPriority::Input`]). These are rerun before any other work so the UI stays responsive to the user.
+//! 2. Background: Everything else, like signals written from tasks. These are rerun in the normal order described above once there is no input work left.
+//!
+//! Input scopes still respect the height ordering: if any ancestor of an input scope is dirty (in any lane), the ancestor is rerun first.
 
 use crate::innerlude::Effect;
 use crate::ScopeId;
@@ -85,6 +93,17 @@ use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::hash::Hash;
 
+/// The lane a dirty scope is queued in when rendering with [`VirtualDom::render_with_deadline`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RenderPriority {
+    /// Updates caused directly by user input like clicks or key presses. These are rerun before any background work.
+    Input,
+
+    /// Updates caused by anything else, like signals written from tasks or timers.
+    #[default]
+    Background,
+}
+
 #[derive(Debug, Clone, Copy, Eq)]
 pub struct ScopeOrder {
     pub(crate) height: u32,
@@ -140,11 +159,34 @@ impl VirtualDom {
         self.dirty_scopes.insert(order);
     }
 
+    /// Queue a scope to be rerendered in the given lane
+    pub(crate) fn queue_scope_with_priority(
+        &mut self,
+        order: ScopeOrder,
+        priority: RenderPriority,
+    ) {
+        if priority == RenderPriority::Input {
+            self.input_scopes.insert(order);
+        }
+        self.queue_scope(order);
+    }
+
     /// Check if there are any dirty scopes
     pub(crate) fn has_dirty_scopes(&self) -> bool {
         !self.dirty_scopes.is_empty()
     }
 
+    /// Check if there are any dirty scopes or tasks left to run
+    pub(crate) fn has_pending_work(&self) -> bool {
+        self.has_dirty_scopes()
+            || self
+                .runtime
+                .dirty_tasks
+                .borrow()
+                .iter()
+                .any(|tasks| !tasks.tasks_queued.borrow().is_empty())
+    }
+
     /// Take the top task from the highest scope
     pub(crate) fn pop_task(&mut self) -> Option<Task> {
         let mut dirty_tasks = self.runtime.dirty_tasks.borrow_mut();
@@ -178,6 +220,38 @@ impl VirtualDom {
         Some(effect)
     }
 
+    /// Take the highest input scope, or any of its dirty ancestors if they need to rerun first
+    pub(crate) fn pop_input_work(&mut self) -> Option<Work> {
+        let mut order = loop {
+            let order = *self.input_scopes.first()?;
+            if self.dirty_scopes.contains(&order) && self.scopes.contains(order.id.0) {
+                break order;
+            }
+            // The scope was already rerun or dropped
+            self.input_scopes.pop_first();
+        };
+
+        // Rerunning an ancestor may drop this scope or change its props, so the highest dirty ancestor always goes first
+        let mut parent = self
+            .runtime
+            .get_state(order.id)
+            .and_then(|scope| scope.parent_id());
+        while let Some(id) = parent {
+            let Some(scope) = self.runtime.get_state(id) else {
+                break;
+            };
+            let ancestor = ScopeOrder::new(scope.height(), id);
+            if self.dirty_scopes.contains(&ancestor) {
+                order = ancestor;
+            }
+            parent = scope.parent_id();
+        }
+
+        self.dirty_scopes.remove(&order);
+        self.input_scopes.remove(&order);
+        Some(Work::RerunScope(order))
+    }
+
     /// Take any work from the highest scope. This may include rerunning the scope and/or running tasks
     pub(crate) fn pop_work(&mut self) -> Option<Work> {
         let mut dirty_scope = self.dirty_scopes.first();
@@ -213,6 +287,7 @@ impl VirtualDom {
                 match scope.cmp(tasks_order) {
                     std::cmp::Ordering::Less => {
                         let scope = self.dirty_scopes.pop_first().unwrap();
+                        self.input_scopes.remove(&scope);
                         Some(Work::RerunScope(scope))
                     }
                     std::cmp::Ordering::Equal | std::cmp::Ordering::Greater => {
@@ -222,6 +297,7 @@ impl VirtualDom {
             }
             (Some(_), None) => {
                 let scope = self.dirty_scopes.pop_first().unwrap();
+                self.input_scopes.remove(&scope);
                 Some(Work::RerunScope(scope))
             }
             (None, Some(_)) => Some(Work::PollTask(self.pop_task().unwrap())),
@@ -1,4 +1,4 @@
-use crate::innerlude::{throw_error, RenderError, RenderReturn, ScopeOrder};
+use crate::innerlude::{throw_error, ProfileEventKind, RenderError, RenderReturn, ScopeOrder};
 use crate::prelude::ReactiveContext;
 use crate::scope_context::SuspenseLocation;
 use crate::{
@@ -66,8 +66,9 @@ impl VirtualDom {
 
                 let props: &dyn AnyProps = &*scope.props;
 
+                let profiling = self.runtime.start_profile();
                 let span = tracing::trace_span!("render", scope = %scope.state().name);
-                span.in_scope(|| {
+                let output = span.in_scope(|| {
                     scope.reactive_context.reset_and_run_in(|| {
                         let mut render_return = props.render();
                         self.handle_element_return(
@@ -77,7 +78,12 @@ impl VirtualDom {
                         );
                         render_return
                     })
-                })
+                });
+                if profiling {
+                    self.runtime
+                        .finish_profile(scope_id, ProfileEventKind::Render);
+                }
+                output
             };
 
             let scope_state = scope.state();
@@ -5,7 +5,7 @@ use crate::{
     prelude::SuspenseContext,
 };
 use generational_box::{AnyStorage, Owner};
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::{
     any::Any,
     cell::{Cell, RefCell},
@@ -55,6 +55,11 @@ pub(crate) struct Scope {
     pub(crate) hooks: RefCell<Vec<Box<dyn Any>>>,
     pub(crate) hook_index: Cell<usize>,
     pub(crate) shared_contexts: RefCell<Vec<Box<dyn Any>>>,
+    // The type names of the hooks and contexts in the same order as the hooks and contexts for devtools
+    pub(crate) hook_names: RefCell<Vec<&'static str>>,
+    pub(crate) context_names: RefCell<Vec<&'static str>>,
+    // Values set with `use_debug_value` keyed by the index of the hook they describe
+    pub(crate) debug_values: RefCell<FxHashMap<usize, String>>,
     pub(crate) spawned_tasks: RefCell<FxHashSet<Task>>,
     pub(crate) before_render: RefCell<Vec<Box<dyn FnMut()>>>,
     pub(crate) after_render: RefCell<Vec<Box<dyn FnMut()>>>,
@@ -83,6 +88,9 @@ impl Scope {
             spawned_tasks: RefCell::new(FxHashSet::default()),
             hooks: RefCell::new(vec![]),
             hook_index: Cell::new(0),
+            hook_names: RefCell::new(vec![]),
+            context_names: RefCell::new(vec![]),
+            debug_values: RefCell::new(FxHashMap::default()),
             before_render: RefCell::new(vec![]),
             after_render: RefCell::new(vec![]),
             status: RefCell::new(ScopeStatus::Unmounted {
@@ -178,11 +186,13 @@ impl Scope {
 
     /// Return any context of type T if it exists on this scope
     pub fn has_context<T: 'static + Clone>(&self) -> Option<T> {
-        self.shared_contexts
-            .borrow()
-            .iter()
-            .find_map(|any| any.downcast_ref::<T>())
-            .cloned()
+        let contexts = self.shared_contexts.borrow();
+        let index = contexts.iter().position(|any| any.is::<T>())?;
+        // Contexts inserted with `provide_any_context` don't know their type until they are first read
+        if let Some(name) = self.context_names.borrow_mut().get_mut(index) {
+            *name = std::any::type_name::<T>();
+        }
+        contexts[index].downcast_ref::<T>().cloned()
     }
 
     /// Try to retrieve a shared state with type `T` from any parent scope.
@@ -234,6 +244,8 @@ impl Scope {
     }
 
     /// Inject a Box<dyn Any> into the context of this scope
+    ///
+    /// The type of the context is erased, so devtools show it as `<unknown>` until a typed lookup finds it
     pub(crate) fn provide_any_context(&self, mut value: Box<dyn Any>) {
         let mut contexts = self.shared_contexts.borrow_mut();
 
@@ -248,6 +260,7 @@ impl Scope {
 
         // Else, just push it
         contexts.push(value);
+        self.context_names.borrow_mut().push("<unknown>");
     }
 
     /// Expose state to children further down the [`crate::VirtualDom`] Tree. Requires `Clone` on the context to allow getting values down the tree.
@@ -295,6 +308,9 @@ impl Scope {
 
         // Else, just push it
         contexts.push(Box::new(value.clone()));
+        self.context_names
+            .borrow_mut()
+            .push(std::any::type_name::<T>());
 
         value
     }
@@ -408,6 +424,9 @@ impl Scope {
 
         if cur_hook >= hooks.len() {
             hooks.push(Box::new(initializer()));
+            self.hook_names
+                .borrow_mut()
+                .push(std::any::type_name::<State>());
         }
 
         hooks
@@ -428,6 +447,12 @@ impl Scope {
             )
     }
 
+    /// Attach a debug value to the last hook that was used in this scope. Devtools display the value next to the hook.
+    pub fn set_debug_value(&self, value: String) {
+        let hook = self.hook_index.get().saturating_sub(1);
+        self.debug_values.borrow_mut().insert(hook, value);
+    }
+
     pub fn push_before_render(&self, f: impl FnMut() + 'static) {
         self.before_render.borrow_mut().push(Box::new(f));
     }
@@ -511,6 +536,16 @@ impl ScopeId {
             .flatten()
     }
 
+    /// Check if the scope is still mounted in the current virtual dom
+    pub fn is_alive(self) -> bool {
+        Runtime::with_scope(self, |_| ()).is_ok()
+    }
+
+    /// Get the name of the component that created the scope, if the scope is still mounted
+    pub fn name(self) -> Option<&'static str> {
+        Runtime::with_scope(self, |cx| cx.name).ok()
+    }
+
     /// Check if the current scope is a descendant of the given scope
     pub fn is_descendant_of(self, other: ScopeId) -> bool {
         let mut current = self;
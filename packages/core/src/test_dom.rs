@@ -0,0 +1,684 @@
+//! An in-memory DOM that can be used to test components without a real renderer.
+
+use std::{any::Any, fmt::Write, rc::Rc};
+
+use rustc_hash::FxHashMap;
+use slab::Slab;
+
+use crate::{
+    arena::ElementId, AttributeValue, Template, TemplateAttribute, TemplateNode, VirtualDom,
+    WriteMutations,
+};
+
+/// A handle to a node inside of a [`TestDom`].
+///
+/// Unlike an [`ElementId`], a handle is never reused for a different node, so it is safe to hold onto handles across
+/// renders. Once the node is removed, [`TestDom::contains`] returns false for the handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeHandle {
+    index: usize,
+    // The slot of a removed node is reused, so handles also store which node in the slot they point to
+    generation: u64,
+}
+
+/// The kind of a node inside of a [`TestDom`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestNodeKind {
+    /// The root node that the VirtualDom mounts its children to. This is the node with the id [`ElementId(0)`](ElementId)
+    Root,
+
+    /// An element like `div` or `button`
+    Element {
+        /// The tag of the element
+        tag: &'static str,
+
+        /// The namespace of the element
+        namespace: Option<&'static str>,
+
+        /// The attributes of the element in the order they were first set
+        attributes: Vec<TestAttribute>,
+    },
+
+    /// A text node
+    Text(String),
+
+    /// A placeholder node
+    Placeholder,
+}
+
+/// An attribute on an element inside of a [`TestDom`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestAttribute {
+    /// The name of the attribute
+    pub name: &'static str,
+
+    /// The namespace of the attribute
+    pub namespace: Option<&'static str>,
+
+    /// The value of the attribute, formatted the same way the web renderer formats it
+    pub value: String,
+}
+
+/// A node inside of a [`TestDom`]
+#[derive(Clone, Debug)]
+pub struct TestNode {
+    kind: TestNodeKind,
+    parent: Option<NodeHandle>,
+    children: Vec<NodeHandle>,
+    id: Option<ElementId>,
+    listeners: Vec<&'static str>,
+    generation: u64,
+}
+
+impl TestNode {
+    fn new(kind: TestNodeKind, generation: u64) -> Self {
+        Self {
+            kind,
+            generation,
+            parent: None,
+            children: Vec::new(),
+            id: None,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Get the kind of this node
+    pub fn kind(&self) -> &TestNodeKind {
+        &self.kind
+    }
+
+    /// Get the tag of this node if it is an element
+    pub fn tag(&self) -> Option<&'static str> {
+        match &self.kind {
+            TestNodeKind::Element { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// Get the value of an attribute on this node if it is an element
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes()
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Get all attributes on this node. Nodes that are not elements have no attributes
+    pub fn attributes(&self) -> &[TestAttribute] {
+        match &self.kind {
+            TestNodeKind::Element { attributes, .. } => attributes,
+            _ => &[],
+        }
+    }
+
+    /// Get the text of this node if it is a text node
+    pub fn text(&self) -> Option<&str> {
+        match &self.kind {
+            TestNodeKind::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Get the parent of this node. Nodes that are detached from the tree have no parent
+    pub fn parent(&self) -> Option<NodeHandle> {
+        self.parent
+    }
+
+    /// Get the children of this node
+    pub fn children(&self) -> &[NodeHandle] {
+        &self.children
+    }
+
+    /// Get the [`ElementId`] the VirtualDom assigned to this node, if any
+    pub fn id(&self) -> Option<ElementId> {
+        self.id
+    }
+
+    /// Get the names of the event listeners attached to this node
+    pub fn listeners(&self) -> &[&'static str] {
+        &self.listeners
+    }
+}
+
+/// A [`WriteMutations`] implementation that keeps an in-memory tree of nodes up to date with the VirtualDom.
+///
+/// The tree can be queried by tag, attribute or text, serialized to HTML for snapshots, and used to dispatch events
+/// back into the [`VirtualDom`] by node. Because it is just another [`WriteMutations`] implementation, you can also
+/// replay recorded [`BinaryMutations`](crate::BinaryMutations) frames onto it with a
+/// [`MutationDecoder`](crate::MutationDecoder).
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// # use dioxus_core::TestDom;
+/// fn app() -> Element {
+///     let mut count = use_signal(|| 0);
+///     rsx! {
+///         button { onclick: move |_| count += 1, "Count: {count}" }
+///     }
+/// }
+///
+/// set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+///
+/// let mut vdom = VirtualDom::new(app);
+/// let mut dom = TestDom::new();
+/// vdom.rebuild(&mut dom);
+/// assert_eq!(dom.to_html(), "<button>Count: 0</button>");
+///
+/// let button = dom.find_by_tag("button")[0];
+/// let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+/// dom.dispatch_event(&mut vdom, button, "click", std::rc::Rc::new(data), true);
+/// vdom.render_immediate(&mut dom);
+/// assert_eq!(dom.to_html(), "<button>Count: 1</button>");
+/// ```
+pub struct TestDom {
+    nodes: Slab<TestNode>,
+    next_generation: u64,
+    ids: FxHashMap<ElementId, NodeHandle>,
+    templates: FxHashMap<&'static str, Vec<TemplateNode>>,
+    stack: Vec<NodeHandle>,
+    root: NodeHandle,
+}
+
+impl Default for TestDom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestDom {
+    /// Create a new, empty test dom
+    pub fn new() -> Self {
+        let mut nodes = Slab::new();
+        let root = NodeHandle {
+            index: nodes.insert(TestNode::new(TestNodeKind::Root, 0)),
+            generation: 0,
+        };
+        let mut myself = Self {
+            nodes,
+            next_generation: 1,
+            ids: FxHashMap::default(),
+            templates: FxHashMap::default(),
+            stack: Vec::new(),
+            root,
+        };
+        myself.set_id(root, ElementId(0));
+        myself
+    }
+
+    /// Get the root node the VirtualDom mounts to
+    pub fn root(&self) -> NodeHandle {
+        self.root
+    }
+
+    /// Get a node by its handle
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle was not created by this test dom or the node was removed
+    pub fn node(&self, handle: NodeHandle) -> &TestNode {
+        self.nodes
+            .get(handle.index)
+            .filter(|node| node.generation == handle.generation)
+            .unwrap_or_else(|| panic!("the node {handle:?} is not in the test dom"))
+    }
+
+    /// Check if a node is still in the test dom. Nodes are dropped when the VirtualDom removes them or any of their
+    /// parents, so the test dom only grows with the size of the mounted tree.
+    pub fn contains(&self, handle: NodeHandle) -> bool {
+        self.nodes
+            .get(handle.index)
+            .is_some_and(|node| node.generation == handle.generation)
+    }
+
+    /// Get the node that is currently assigned the given [`ElementId`]
+    pub fn get(&self, id: ElementId) -> Option<NodeHandle> {
+        self.ids.get(&id).copied()
+    }
+
+    /// Find all nodes mounted under the root that match the predicate in document order
+    pub fn find(&self, mut predicate: impl FnMut(&TestNode) -> bool) -> Vec<NodeHandle> {
+        let mut found = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(handle) = stack.pop() {
+            let node = self.node(handle);
+            if predicate(node) {
+                found.push(handle);
+            }
+            stack.extend(node.children.iter().rev());
+        }
+        found
+    }
+
+    /// Find all elements with the given tag
+    pub fn find_by_tag(&self, tag: &str) -> Vec<NodeHandle> {
+        self.find(|node| node.tag() == Some(tag))
+    }
+
+    /// Find all elements with an attribute with the given name and value
+    pub fn find_by_attribute(&self, name: &str, value: &str) -> Vec<NodeHandle> {
+        self.find(|node| node.attribute(name) == Some(value))
+    }
+
+    /// Find all elements with a direct text child that contains the given text
+    pub fn find_by_text(&self, text: &str) -> Vec<NodeHandle> {
+        self.find(|node| {
+            node.tag().is_some()
+                && node.children.iter().any(|child| {
+                    self.node(*child)
+                        .text()
+                        .is_some_and(|child_text| child_text.contains(text))
+                })
+        })
+    }
+
+    /// Get the combined text of the node and all of its descendants
+    pub fn text_content(&self, handle: NodeHandle) -> String {
+        let node = self.node(handle);
+        match &node.kind {
+            TestNodeKind::Text(text) => text.clone(),
+            _ => node
+                .children
+                .iter()
+                .map(|child| self.text_content(*child))
+                .collect(),
+        }
+    }
+
+    /// Serialize everything mounted under the root to HTML. Like `dioxus_ssr::render`, placeholders are left out and
+    /// `dangerous_inner_html` is written as the contents of the element, but every attribute value is quoted.
+    pub fn to_html(&self) -> String {
+        self.html(self.root)
+    }
+
+    /// Serialize a node and its descendants to HTML. The root node serializes to just its children.
+    pub fn html(&self, handle: NodeHandle) -> String {
+        let mut html = String::new();
+        self.write_html(handle, &mut html).unwrap();
+        html
+    }
+
+    /// Dispatch an event to the VirtualDom as if it was triggered on the given node.
+    ///
+    /// Just like in the browser, bubbling events are sent to the closest node with a listener attached and non-bubbling
+    /// events are only sent if the node itself has a listener for the event. Returns `true` if the event was sent to
+    /// the VirtualDom.
+    ///
+    /// This does not rerender the VirtualDom. Call [`VirtualDom::render_immediate`] with this test dom afterwards to
+    /// apply any changes the event handlers made.
+    pub fn dispatch_event(
+        &self,
+        dom: &mut VirtualDom,
+        handle: NodeHandle,
+        name: &str,
+        data: Rc<dyn Any>,
+        bubbles: bool,
+    ) -> bool {
+        let mut current = Some(handle);
+        while let Some(handle) = current {
+            let node = self.node(handle);
+            let has_listener = match bubbles {
+                true => !node.listeners.is_empty(),
+                false => node.listeners.contains(&name),
+            };
+            if let (true, Some(id)) = (has_listener, node.id) {
+                dom.handle_event(name, data, id, bubbles);
+                return true;
+            }
+            if !bubbles {
+                return false;
+            }
+            current = node.parent;
+        }
+        false
+    }
+
+    fn write_html(&self, handle: NodeHandle, to: &mut String) -> std::fmt::Result {
+        let node = self.node(handle);
+        match &node.kind {
+            TestNodeKind::Root => {}
+            TestNodeKind::Text(text) => {
+                escape_into(text, to);
+                return Ok(());
+            }
+            // Placeholders are an implementation detail of the VirtualDom, so we leave them out of the html
+            TestNodeKind::Placeholder => return Ok(()),
+            TestNodeKind::Element {
+                tag, attributes, ..
+            } => {
+                write!(to, "<{tag}")?;
+                let mut style = String::new();
+                let mut inner_html = None;
+                for attr in attributes {
+                    if attr.name == "dangerous_inner_html" {
+                        inner_html = Some(&attr.value);
+                        continue;
+                    }
+                    if attr.namespace == Some("style") {
+                        write!(style, "{}:{};", attr.name, attr.value)?;
+                        continue;
+                    }
+                    write!(to, " {}=\"", attr.name)?;
+                    escape_into(&attr.value, to);
+                    to.push('"');
+                }
+                if !style.is_empty() {
+                    to.push_str(" style=\"");
+                    escape_into(&style, to);
+                    to.push('"');
+                }
+                to.push('>');
+                if let Some(inner_html) = inner_html {
+                    to.push_str(inner_html);
+                }
+            }
+        }
+
+        for child in &node.children {
+            self.write_html(*child, to)?;
+        }
+
+        if let TestNodeKind::Element { tag, .. } = &node.kind {
+            write!(to, "</{tag}>")?;
+        }
+
+        Ok(())
+    }
+
+    fn insert(&mut self, kind: TestNodeKind) -> NodeHandle {
+        let generation = self.next_generation;
+        self.next_generation += 1;
+        NodeHandle {
+            index: self.nodes.insert(TestNode::new(kind, generation)),
+            generation,
+        }
+    }
+
+    /// Drop a detached node and every node under it
+    fn free(&mut self, handle: NodeHandle) {
+        let node = self.nodes.remove(handle.index);
+        if let Some(id) = node.id {
+            if self.ids.get(&id) == Some(&handle) {
+                self.ids.remove(&id);
+            }
+        }
+        for child in node.children {
+            self.free(child);
+        }
+    }
+
+    fn set_id(&mut self, handle: NodeHandle, id: ElementId) {
+        self.nodes[handle.index].id = Some(id);
+        if let Some(old) = self.ids.insert(id, handle) {
+            if old != handle {
+                self.nodes[old.index].id = None;
+            }
+        }
+    }
+
+    fn by_id(&self, id: ElementId) -> NodeHandle {
+        self.get(id)
+            .unwrap_or_else(|| panic!("no node is assigned the id {id:?}"))
+    }
+
+    fn node_mut(&mut self, id: ElementId) -> &mut TestNode {
+        let handle = self.by_id(id);
+        &mut self.nodes[handle.index]
+    }
+
+    fn load_path(&self, path: &[u8]) -> NodeHandle {
+        let mut current = *self.stack.last().expect("the stack is empty");
+        for index in path {
+            current = self.node(current).children[*index as usize];
+        }
+        current
+    }
+
+    fn pop(&mut self, m: usize) -> Vec<NodeHandle> {
+        self.stack.split_off(self.stack.len() - m)
+    }
+
+    fn detach(&mut self, handle: NodeHandle) {
+        if let Some(parent) = self.nodes[handle.index].parent.take() {
+            self.nodes[parent.index]
+                .children
+                .retain(|child| *child != handle);
+        }
+    }
+
+    /// Insert the new nodes into the parent of the target at the offset from the target's position
+    fn insert_siblings(&mut self, target: NodeHandle, new: Vec<NodeHandle>, after: bool) {
+        for node in &new {
+            self.detach(*node);
+        }
+        let parent = self.nodes[target.index]
+            .parent
+            .expect("the target node is not mounted");
+        let mut index = self.nodes[parent.index]
+            .children
+            .iter()
+            .position(|child| *child == target)
+            .unwrap();
+        if after {
+            index += 1;
+        }
+        for node in &new {
+            self.nodes[node.index].parent = Some(parent);
+        }
+        self.nodes[parent.index].children.splice(index..index, new);
+    }
+
+    fn replace(&mut self, target: NodeHandle, new: Vec<NodeHandle>) {
+        self.insert_siblings(target, new, false);
+        self.detach(target);
+        self.free(target);
+    }
+
+    fn create_template_node(&mut self, node: &TemplateNode) -> NodeHandle {
+        match node {
+            TemplateNode::Element {
+                tag,
+                namespace,
+                attrs,
+                children,
+            } => {
+                let attributes = attrs
+                    .iter()
+                    .filter_map(|attr| match attr {
+                        TemplateAttribute::Static {
+                            name,
+                            value,
+                            namespace,
+                        } => Some(TestAttribute {
+                            name,
+                            namespace: *namespace,
+                            value: value.to_string(),
+                        }),
+                        TemplateAttribute::Dynamic { .. } => None,
+                    })
+                    .collect();
+                let handle = self.insert(TestNodeKind::Element {
+                    tag,
+                    namespace: *namespace,
+                    attributes,
+                });
+                for child in children.iter() {
+                    let child = self.create_template_node(child);
+                    self.nodes[child.index].parent = Some(handle);
+                    self.nodes[handle.index].children.push(child);
+                }
+                handle
+            }
+            TemplateNode::Text { text } => self.insert(TestNodeKind::Text(text.to_string())),
+            TemplateNode::Dynamic { .. } => self.insert(TestNodeKind::Placeholder),
+        }
+    }
+}
+
+impl WriteMutations for TestDom {
+    fn register_template(&mut self, template: Template) {
+        self.templates
+            .insert(template.name, template.roots.to_vec());
+    }
+
+    fn append_children(&mut self, id: ElementId, m: usize) {
+        let parent = self.by_id(id);
+        for child in self.pop(m) {
+            self.detach(child);
+            self.nodes[child.index].parent = Some(parent);
+            self.nodes[parent.index].children.push(child);
+        }
+    }
+
+    fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
+        let handle = self.load_path(path);
+        self.set_id(handle, id);
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) {
+        let handle = self.insert(TestNodeKind::Placeholder);
+        self.set_id(handle, id);
+        self.stack.push(handle);
+    }
+
+    fn create_text_node(&mut self, value: &str, id: ElementId) {
+        let handle = self.insert(TestNodeKind::Text(value.to_string()));
+        self.set_id(handle, id);
+        self.stack.push(handle);
+    }
+
+    fn hydrate_text_node(&mut self, path: &'static [u8], value: &str, id: ElementId) {
+        let mut handle = self.load_path(path);
+        match &mut self.nodes[handle.index].kind {
+            TestNodeKind::Text(text) => *text = value.to_string(),
+            _ => {
+                let text = self.insert(TestNodeKind::Text(value.to_string()));
+                self.replace(handle, vec![text]);
+                handle = text;
+            }
+        }
+        self.set_id(handle, id);
+    }
+
+    fn load_template(&mut self, name: &'static str, index: usize, id: ElementId) {
+        let root = self
+            .templates
+            .get(name)
+            .and_then(|roots| roots.get(index))
+            .copied()
+            .unwrap_or_else(|| panic!("template {name} was not registered"));
+        let handle = self.create_template_node(&root);
+        self.set_id(handle, id);
+        self.stack.push(handle);
+    }
+
+    fn replace_node_with(&mut self, id: ElementId, m: usize) {
+        let target = self.by_id(id);
+        let new = self.pop(m);
+        self.replace(target, new);
+    }
+
+    fn replace_placeholder_with_nodes(&mut self, path: &'static [u8], m: usize) {
+        let new = self.pop(m);
+        let target = self.load_path(path);
+        self.replace(target, new);
+    }
+
+    fn insert_nodes_after(&mut self, id: ElementId, m: usize) {
+        let target = self.by_id(id);
+        let new = self.pop(m);
+        self.insert_siblings(target, new, true);
+    }
+
+    fn insert_nodes_before(&mut self, id: ElementId, m: usize) {
+        let target = self.by_id(id);
+        let new = self.pop(m);
+        self.insert_siblings(target, new, false);
+    }
+
+    fn set_attribute(
+        &mut self,
+        name: &'static str,
+        ns: Option<&'static str>,
+        value: &AttributeValue,
+        id: ElementId,
+    ) {
+        let value = match value {
+            AttributeValue::Text(text) => Some(text.clone()),
+            AttributeValue::Float(float) => Some(float.to_string()),
+            AttributeValue::Int(int) => Some(int.to_string()),
+            AttributeValue::Bool(bool) => Some(bool.to_string()),
+            // Listeners and arbitrary values only mean something inside of the VirtualDom, so the test dom sees them
+            // as removed just like the binary encoder does
+            AttributeValue::None | AttributeValue::Listener(_) | AttributeValue::Any(_) => None,
+        };
+        let TestNodeKind::Element { attributes, .. } = &mut self.node_mut(id).kind else {
+            panic!("cannot set an attribute on a node that is not an element");
+        };
+        let existing = attributes
+            .iter()
+            .position(|attr| attr.name == name && attr.namespace == ns);
+        match (existing, value) {
+            (Some(index), Some(value)) => attributes[index].value = value,
+            (None, Some(value)) => attributes.push(TestAttribute {
+                name,
+                namespace: ns,
+                value,
+            }),
+            (Some(index), None) => {
+                attributes.remove(index);
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn set_node_text(&mut self, value: &str, id: ElementId) {
+        let handle = self.by_id(id);
+        match &mut self.nodes[handle.index].kind {
+            TestNodeKind::Text(text) => *text = value.to_string(),
+            _ => {
+                let text = self.insert(TestNodeKind::Text(value.to_string()));
+                for child in std::mem::take(&mut self.nodes[handle.index].children) {
+                    self.free(child);
+                }
+                self.nodes[text.index].parent = Some(handle);
+                self.nodes[handle.index].children.push(text);
+            }
+        }
+    }
+
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.node_mut(id).listeners.push(name);
+    }
+
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        let listeners = &mut self.node_mut(id).listeners;
+        if let Some(index) = listeners.iter().position(|listener| *listener == name) {
+            listeners.remove(index);
+        }
+    }
+
+    fn remove_node(&mut self, id: ElementId) {
+        if let Some(handle) = self.get(id) {
+            self.detach(handle);
+            self.free(handle);
+        }
+    }
+
+    fn push_root(&mut self, id: ElementId) {
+        let handle = self.by_id(id);
+        self.stack.push(handle);
+    }
+}
+
+fn escape_into(text: &str, to: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => to.push_str("&lt;"),
+            '>' => to.push_str("&gt;"),
+            '&' => to.push_str("&amp;"),
+            '"' => to.push_str("&quot;"),
+            '\'' => to.push_str("&#x27;"),
+            c => to.push(c),
+        }
+    }
+}
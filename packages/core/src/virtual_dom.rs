@@ -8,8 +8,8 @@ use crate::root_wrapper::RootScopeWrapper;
 use crate::{
     arena::ElementId,
     innerlude::{
-        ElementRef, NoOpMutations, SchedulerMsg, ScopeOrder, ScopeState, VNodeMount, VProps,
-        WriteMutations,
+        CountingMutations, ElementRef, NoOpMutations, Profile, ProfileEventKind, Profiler,
+        RenderPriority, SchedulerMsg, ScopeOrder, ScopeState, VNodeMount, VProps, WriteMutations,
     },
     nodes::{Template, TemplateId},
     runtime::{Runtime, RuntimeGuard},
@@ -21,7 +21,7 @@ use futures_util::StreamExt;
 use rustc_hash::FxHashSet;
 use slab::Slab;
 use std::collections::BTreeSet;
-use std::{any::Any, rc::Rc};
+use std::{any::Any, rc::Rc, time::Duration};
 use tracing::instrument;
 
 /// A virtual node system that progresses user events and diffs UI trees.
@@ -208,6 +208,9 @@ pub struct VirtualDom {
 
     pub(crate) dirty_scopes: BTreeSet<ScopeOrder>,
 
+    // The subset of dirty scopes that were marked dirty by user input
+    pub(crate) input_scopes: BTreeSet<ScopeOrder>,
+
     // A map of templates we have sent to the renderer
     pub(crate) templates: FxHashSet<TemplateId>,
 
@@ -311,7 +314,7 @@ impl VirtualDom {
         root_props: P,
     ) -> Self {
         let render_fn = root.id();
-        let props = VProps::new(root, |_, _| true, root_props, "Root");
+        let props = VProps::new(root, |_, _| true, |_| None, root_props, "Root");
         Self::new_with_component(VComponent {
             name: "root",
             render_fn,
@@ -336,6 +339,7 @@ impl VirtualDom {
             runtime: Runtime::new(tx),
             scopes: Default::default(),
             dirty_scopes: Default::default(),
+            input_scopes: Default::default(),
             templates: Default::default(),
             queued_templates: Default::default(),
             elements: Default::default(),
@@ -346,6 +350,7 @@ impl VirtualDom {
         let root = VProps::new(
             RootScopeWrapper,
             |_, _| true,
+            |_| None,
             RootProps(root),
             "RootWrapper",
         );
@@ -402,14 +407,26 @@ impl VirtualDom {
     ///
     /// Whenever the Runtime "works", it will re-render this scope
     pub fn mark_dirty(&mut self, id: ScopeId) {
+        self.mark_dirty_with_priority(id, RenderPriority::Background);
+    }
+
+    /// Manually mark a scope as requiring a re-render in a specific [`RenderPriority`] lane
+    ///
+    /// Scopes in the [`RenderPriority::Input`] lane are rerun first by [`VirtualDom::render_with_deadline`]
+    pub fn mark_dirty_with_priority(&mut self, id: ScopeId, priority: RenderPriority) {
         let Some(scope) = self.runtime.get_state(id) else {
             return;
         };
 
-        tracing::event!(tracing::Level::TRACE, "Marking scope {:?} as dirty", id);
+        tracing::event!(
+            tracing::Level::TRACE,
+            "Marking scope {:?} as dirty with {:?} priority",
+            id,
+            priority
+        );
         let order = ScopeOrder::new(scope.height(), id);
         drop(scope);
-        self.queue_scope(order);
+        self.queue_scope_with_priority(order, priority);
     }
 
     /// Mark a task as dirty
@@ -441,6 +458,8 @@ impl VirtualDom {
     ///
     /// It is up to the listeners themselves to mark nodes as dirty.
     ///
+    /// Any scopes the listeners mark dirty are queued in the [`RenderPriority::Input`] lane.
+    ///
     /// If you have multiple events, you can call this method multiple times before calling "render_with_deadline"
     #[instrument(skip(self), level = "trace", name = "VirtualDom::handle_event")]
     pub fn handle_event(
@@ -450,6 +469,9 @@ impl VirtualDom {
         element: ElementId,
         bubbles: bool,
     ) {
+        // Queue any updates from before the event in the background lane so they are not attributed to this event
+        self.queue_events();
+
         let _runtime = RuntimeGuard::new(self.runtime.clone());
 
         if let Some(Some(parent_path)) = self.elements.get(element.0).copied() {
@@ -459,6 +481,8 @@ impl VirtualDom {
                 self.handle_non_bubbling_event(parent_path, name, Event::new(data, bubbles));
             }
         }
+
+        self.queue_events_with_priority(RenderPriority::Input);
     }
 
     /// Wait for the scheduler to have any work.
@@ -514,10 +538,15 @@ impl VirtualDom {
 
     /// Queue any pending events
     fn queue_events(&mut self) {
+        self.queue_events_with_priority(RenderPriority::Background)
+    }
+
+    /// Queue any pending events, marking any scopes dirty in the given lane
+    fn queue_events_with_priority(&mut self, priority: RenderPriority) {
         // Prevent a task from deadlocking the runtime by repeatedly queueing itself
         while let Ok(Some(msg)) = self.rx.try_next() {
             match msg {
-                SchedulerMsg::Immediate(id) => self.mark_dirty(id),
+                SchedulerMsg::Immediate(id) => self.mark_dirty_with_priority(id, priority),
                 SchedulerMsg::TaskNotified(task) => self.mark_task_dirty(Task::from_id(task)),
                 SchedulerMsg::EffectQueued => {}
             }
@@ -614,14 +643,60 @@ impl VirtualDom {
     pub fn rebuild(&mut self, to: &mut impl WriteMutations) {
         self.flush_templates(to);
         let _runtime = RuntimeGuard::new(self.runtime.clone());
+        // Mutations are only counted while profiling so rendering without the profiler doesn't pay for it
+        if self.runtime.is_profiling() {
+            self.rebuild_root(&mut CountingMutations::new(to, self.runtime.clone()));
+        } else {
+            self.rebuild_root(to);
+        }
+    }
+
+    /// Write the mutations that create the current DOM from scratch, without rerunning any components.
+    ///
+    /// Unlike [`VirtualDom::rebuild`], the state of every component is kept and the nodes keep the ids they were
+    /// mounted with, so later mutations apply to the replayed DOM. This lets a renderer that lost its DOM (like a
+    /// client that missed some edits) catch up with the VirtualDom.
+    ///
+    /// Every template is registered again when it is first used after the replay, so the mutations can be applied to a
+    /// renderer that starts out empty.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_core::*;
+    /// fn app() -> Element {
+    ///     rsx! { "hello world" }
+    /// }
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.rebuild(&mut NoOpMutations);
+    ///
+    /// // The renderer lost its DOM, so write it again
+    /// let mut mutations = Mutations::default();
+    /// dom.replay(&mut mutations);
+    /// ```
+    pub fn replay(&mut self, to: &mut impl WriteMutations) {
+        self.templates.clear();
+        self.flush_templates(to);
+        let _runtime = RuntimeGuard::new(self.runtime.clone());
+        let m = self.replay_scope(ScopeId::ROOT, to);
+        to.append_children(ElementId(0), m);
+    }
+
+    fn rebuild_root(&mut self, to: &mut impl WriteMutations) {
         let new_nodes = self.run_scope(ScopeId::ROOT);
 
         self.scopes[ScopeId::ROOT.0].last_rendered_node = Some(new_nodes.clone());
 
         // Rebuilding implies we append the created elements to the root
+        let profiling = self.runtime.start_profile();
         let m = self.create_scope(Some(to), ScopeId::ROOT, new_nodes, None);
 
         to.append_children(ElementId(0), m);
+        if profiling {
+            self.runtime
+                .finish_profile(ScopeId::ROOT, ProfileEventKind::Diff);
+        }
     }
 
     /// Render whatever the VirtualDom has ready as fast as possible without requiring an executor to progress
@@ -638,6 +713,16 @@ impl VirtualDom {
         // Next, diff any dirty scopes
         // We choose not to poll the deadline since we complete pretty quickly anyways
         let _runtime = RuntimeGuard::new(self.runtime.clone());
+        if self.runtime.is_profiling() {
+            self.render_dirty_scopes(&mut CountingMutations::new(to, self.runtime.clone()));
+        } else {
+            self.render_dirty_scopes(to);
+        }
+
+        self.runtime.finish_render();
+    }
+
+    fn render_dirty_scopes(&mut self, to: &mut impl WriteMutations) {
         while let Some(work) = self.pop_work() {
             match work {
                 Work::PollTask(task) => {
@@ -651,8 +736,86 @@ impl VirtualDom {
                 }
             }
         }
+    }
+
+    /// Render dirty scopes until the deadline passes, returning `true` if there is still work left to do.
+    ///
+    /// The deadline is checked after every scope rerun or task poll and should return `true` once it has passed. This
+    /// lets renderers split large updates across multiple frames instead of blocking the main thread until every dirty
+    /// scope has been rerun. Scopes marked dirty by user input (see [`RenderPriority`]) are rerun before any
+    /// background work.
+    ///
+    /// The mutations written so far are always valid to apply, even if work remains.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_core::*;
+    /// # use std::time::{Duration, Instant};
+    /// # fn app() -> Element { rsx! { div {} } }
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.rebuild(&mut NoOpMutations);
+    ///
+    /// // Render in 16ms slices, applying the mutations after each slice
+    /// loop {
+    ///     let mut mutations = Mutations::default();
+    ///     let deadline = Instant::now() + Duration::from_millis(16);
+    ///     let work_remaining = dom.render_with_deadline(&mut mutations, || Instant::now() >= deadline);
+    ///     // apply the mutations to the renderer here
+    ///     if !work_remaining {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    #[instrument(
+        skip(self, to, deadline),
+        level = "trace",
+        name = "VirtualDom::render_with_deadline"
+    )]
+    pub fn render_with_deadline(
+        &mut self,
+        to: &mut impl WriteMutations,
+        deadline: impl FnMut() -> bool,
+    ) -> bool {
+        self.flush_templates(to);
+
+        // Process any events that might be pending in the queue
+        self.process_events();
+
+        let _runtime = RuntimeGuard::new(self.runtime.clone());
+        if self.runtime.is_profiling() {
+            let mut to = CountingMutations::new(to, self.runtime.clone());
+            self.render_dirty_scopes_until(&mut to, deadline);
+        } else {
+            self.render_dirty_scopes_until(to, deadline);
+        }
 
         self.runtime.finish_render();
+
+        self.has_pending_work()
+    }
+
+    fn render_dirty_scopes_until(
+        &mut self,
+        to: &mut impl WriteMutations,
+        mut deadline: impl FnMut() -> bool,
+    ) {
+        while let Some(work) = self.pop_input_work().or_else(|| self.pop_work()) {
+            match work {
+                Work::PollTask(task) => {
+                    _ = self.runtime.handle_task_wakeup(task);
+                }
+                Work::RerunScope(scope) => {
+                    self.run_and_diff_scope(Some(to), scope.id);
+                }
+            }
+            // Make sure we process any new events
+            self.queue_events();
+
+            if deadline() {
+                break;
+            }
+        }
     }
 
     /// [`Self::render_immediate`] to a vector of mutations for testing purposes
@@ -785,6 +948,58 @@ impl VirtualDom {
         self.runtime.clone()
     }
 
+    /// Start recording how long each scope takes to render and diff, how many mutations it writes and which reactive
+    /// values caused it to rerun. Any previously recorded profile is discarded.
+    ///
+    /// This uses [`std::time::Instant`] to measure time which is not available on `wasm32-unknown-unknown`. Use
+    /// [`Self::enable_profiling_with_clock`] to provide your own clock on the web.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use dioxus::prelude::*;
+    /// # use dioxus_core::*;
+    /// fn app() -> Element {
+    ///     rsx! { div { "hello world" } }
+    /// }
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.enable_profiling();
+    /// dom.rebuild(&mut NoOpMutations);
+    ///
+    /// let profile = dom.disable_profiling().unwrap();
+    /// assert_eq!(profile.scope(ScopeId::APP).unwrap().render_count, 1);
+    /// std::fs::write("trace.json", profile.to_chrome_trace()).unwrap();
+    /// # std::fs::remove_file("trace.json").unwrap();
+    /// ```
+    pub fn enable_profiling(&mut self) {
+        let started = std::time::Instant::now();
+        self.enable_profiling_with_clock(move || started.elapsed());
+    }
+
+    /// Start profiling with a custom clock. The clock should return the time elapsed since some fixed point, like
+    /// `performance.now()` on the web.
+    pub fn enable_profiling_with_clock(&mut self, clock: impl Fn() -> Duration + 'static) {
+        *self.runtime.profiler.borrow_mut() = Some(Profiler::new(Box::new(clock)));
+    }
+
+    /// Get a snapshot of everything recorded since profiling was enabled. Returns `None` if profiling is not enabled.
+    pub fn profile(&self) -> Option<Profile> {
+        self.runtime
+            .profiler
+            .borrow()
+            .as_ref()
+            .map(Profiler::snapshot)
+    }
+
+    /// Stop profiling and return everything that was recorded. Returns `None` if profiling was not enabled.
+    pub fn disable_profiling(&mut self) -> Option<Profile> {
+        self.runtime
+            .profiler
+            .borrow_mut()
+            .take()
+            .map(|profiler| profiler.snapshot())
+    }
+
     /// Flush any queued template changes
     #[instrument(skip(self, to), level = "trace", name = "VirtualDom::flush_templates")]
     fn flush_templates(&mut self, to: &mut impl WriteMutations) {
@@ -0,0 +1,226 @@
+//! Make sure the binary mutation encoding round trips every mutation the VirtualDom emits
+
+use dioxus::dioxus_core::{
+    AttributeValue, BinaryMutations, ElementId, Mutation, MutationDecodeError, MutationDecoder,
+    Mutations, Template, TemplateNode, WriteMutations, MAX_TEMPLATE_DEPTH,
+    MUTATION_ENCODING_VERSION,
+};
+use dioxus::prelude::*;
+
+fn app() -> Element {
+    let (order, show): (&[usize], bool) = match generation() % 2 {
+        0 => (&[0, 1, 2, 3], true),
+        _ => (&[3, 1, 0, 4, 5], false),
+    };
+
+    rsx! {
+        div { class: "list", "data-generation": "{generation()}",
+            for i in order {
+                div { key: "{i}", onclick: move |_| {}, "{i}" }
+            }
+        }
+        if show {
+            svg { circle { r: 10 } }
+        }
+        "{generation()}"
+    }
+}
+
+fn round_trip(encoded: &mut BinaryMutations, decoder: &mut MutationDecoder) -> Mutations {
+    let mut decoded = Mutations::default();
+    decoder.decode(&encoded.take(), &mut decoded).unwrap();
+    decoded
+}
+
+#[test]
+fn rebuild_and_diff_round_trip() {
+    let mut expected_dom = VirtualDom::new(app);
+    let mut encoded_dom = VirtualDom::new(app);
+    let mut encoded = BinaryMutations::new();
+    let mut decoder = MutationDecoder::new();
+
+    encoded_dom.rebuild(&mut encoded);
+    assert_eq!(
+        round_trip(&mut encoded, &mut decoder),
+        expected_dom.rebuild_to_vec()
+    );
+
+    for _ in 0..3 {
+        expected_dom.mark_dirty(ScopeId::APP);
+        encoded_dom.mark_dirty(ScopeId::APP);
+        encoded_dom.render_immediate(&mut encoded);
+        assert_eq!(
+            round_trip(&mut encoded, &mut decoder),
+            expected_dom.render_immediate_to_vec()
+        );
+    }
+}
+
+#[test]
+fn attribute_values_round_trip() {
+    let values = [
+        AttributeValue::Text("hello".to_string()),
+        AttributeValue::Float(-1.5),
+        AttributeValue::Int(i64::MIN),
+        AttributeValue::Int(-1),
+        AttributeValue::Int(i64::MAX),
+        AttributeValue::Bool(true),
+        AttributeValue::Bool(false),
+        AttributeValue::None,
+    ];
+
+    let mut encoded = BinaryMutations::new();
+    for value in &values {
+        encoded.set_attribute("value", Some("ns"), value, ElementId(usize::MAX));
+    }
+
+    let mut decoded = Mutations::default();
+    MutationDecoder::new()
+        .decode(encoded.bytes(), &mut decoded)
+        .unwrap();
+
+    let expected: Vec<_> = values
+        .into_iter()
+        .map(|value| Mutation::SetAttribute {
+            name: "value",
+            ns: Some("ns"),
+            value,
+            id: ElementId(usize::MAX),
+        })
+        .collect();
+    assert_eq!(decoded.edits, expected);
+}
+
+#[test]
+fn unencodable_attribute_values_are_written_as_none() {
+    let mut encoded = BinaryMutations::new();
+    encoded.set_attribute("value", None, &AttributeValue::any_value(1), ElementId(1));
+
+    let mut decoded = Mutations::default();
+    MutationDecoder::new()
+        .decode(encoded.bytes(), &mut decoded)
+        .unwrap();
+    assert_eq!(
+        decoded.edits,
+        [Mutation::SetAttribute {
+            name: "value",
+            ns: None,
+            value: AttributeValue::None,
+            id: ElementId(1),
+        }]
+    );
+}
+
+#[test]
+fn templates_are_decoded_once() {
+    let mut decoder = MutationDecoder::new();
+    let mut templates = Vec::new();
+    // Each dom registers the same templates in a separate frame
+    for _ in 0..2 {
+        let mut encoded = BinaryMutations::new();
+        VirtualDom::new(app).rebuild(&mut encoded);
+        templates.push(round_trip(&mut encoded, &mut decoder).templates);
+    }
+
+    assert!(!templates[0].is_empty());
+    assert_eq!(templates[0], templates[1]);
+    for (first, second) in templates[0].iter().zip(&templates[1]) {
+        assert!(std::ptr::eq(first.roots, second.roots));
+    }
+}
+
+#[test]
+fn changed_templates_replace_the_cached_template() {
+    fn template(text: &'static str) -> Template {
+        Template {
+            name: "hot-reloaded",
+            roots: Box::leak(Box::new([TemplateNode::Text { text }])),
+            node_paths: &[],
+            attr_paths: &[],
+        }
+    }
+
+    let mut decoder = MutationDecoder::new();
+    let mut registered = Vec::new();
+    for text in ["old", "new", "new"] {
+        let mut encoded = BinaryMutations::new();
+        encoded.register_template(template(text));
+        registered.extend(round_trip(&mut encoded, &mut decoder).templates);
+    }
+
+    assert_eq!(registered[0], template("old"));
+    assert_eq!(registered[1], template("new"));
+    // The new contents are cached in place of the old ones
+    assert!(std::ptr::eq(registered[1].roots, registered[2].roots));
+}
+
+#[test]
+fn static_strings_are_interned() {
+    let mut encoded = BinaryMutations::new();
+    encoded.create_event_listener("click", ElementId(1));
+    let single = encoded.bytes().len();
+    encoded.create_event_listener("click", ElementId(1));
+
+    // The second listener only needs the opcode, a reference to the name and the id
+    assert_eq!(encoded.bytes().len() - single, 3);
+}
+
+#[test]
+fn malformed_frames_are_rejected() {
+    let mut decoder = MutationDecoder::new();
+    let mut mutations = Mutations::default();
+
+    assert_eq!(
+        decoder.decode(b"nope", &mut mutations),
+        Err(MutationDecodeError::InvalidHeader)
+    );
+    assert_eq!(
+        decoder.decode(
+            &[b'D', b'X', b'M', MUTATION_ENCODING_VERSION + 1],
+            &mut mutations
+        ),
+        Err(MutationDecodeError::UnsupportedVersion(
+            MUTATION_ENCODING_VERSION + 1
+        ))
+    );
+
+    let mut encoded = BinaryMutations::new();
+    encoded.create_text_node("hello world", ElementId(1));
+    let bytes = encoded.take();
+    assert_eq!(
+        decoder.decode(&bytes[..bytes.len() - 2], &mut mutations),
+        Err(MutationDecodeError::UnexpectedEof)
+    );
+
+    let mut bytes = BinaryMutations::new().take();
+    bytes.push(u8::MAX);
+    assert_eq!(
+        decoder.decode(&bytes, &mut mutations),
+        Err(MutationDecodeError::InvalidOpcode(u8::MAX))
+    );
+}
+
+#[test]
+fn deeply_nested_templates_are_rejected() {
+    let mut node = TemplateNode::Dynamic { id: 0 };
+    for _ in 0..=MAX_TEMPLATE_DEPTH {
+        node = TemplateNode::Element {
+            tag: "div",
+            namespace: None,
+            attrs: &[],
+            children: Box::leak(Box::new([node])),
+        };
+    }
+    let mut encoded = BinaryMutations::new();
+    encoded.register_template(Template {
+        name: "deep",
+        roots: Box::leak(Box::new([node])),
+        node_paths: &[],
+        attr_paths: &[],
+    });
+
+    assert_eq!(
+        MutationDecoder::new().decode(encoded.bytes(), &mut Mutations::default()),
+        Err(MutationDecodeError::TemplateTooDeep)
+    );
+}
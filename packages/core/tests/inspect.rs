@@ -0,0 +1,155 @@
+//! Make sure component tree snapshots reflect the live tree
+
+use dioxus::dioxus_core::TestDom;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct Theme;
+
+fn app() -> Element {
+    let count = use_signal(|| 1);
+    use_debug_value(count);
+    use_context_provider(|| Theme);
+
+    rsx! {
+        div { "app" }
+        for i in 0..count() {
+            Child { key: "{i}", i }
+        }
+    }
+}
+
+#[component]
+fn Child(i: usize) -> Element {
+    rsx! {
+        span { "child {i}" }
+        "text"
+    }
+}
+
+#[test]
+fn snapshots_component_tree() {
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+
+    let tree = vdom.component_tree();
+    assert_eq!(tree.root, ScopeId::ROOT);
+    assert_eq!(tree.scopes[0].id, ScopeId::ROOT);
+
+    let app = tree.get(ScopeId::APP).unwrap();
+    assert_eq!(app.name, "root");
+    assert_eq!(app.render_count, 1);
+    assert!(app.hooks[0].type_name.ends_with("Signal<usize>"));
+    assert_eq!(app.hooks[0].debug_value.as_deref(), Some("1"));
+    assert_eq!(app.hooks[1].debug_value, None);
+    assert!(app.contexts.iter().any(|name| name.ends_with("Theme")));
+    assert_eq!(app.children.len(), 1);
+
+    // Scopes are listed in depth first order
+    let position = |id| tree.scopes.iter().position(|scope| scope.id == id);
+    assert!(position(ScopeId::APP) < position(app.children[0]));
+
+    let child = tree.get(app.children[0]).unwrap();
+    assert!(child.name.ends_with("Child"));
+    assert!(child.props.ends_with("ChildProps"));
+    assert_eq!(child.parent, Some(ScopeId::APP));
+    assert_eq!(child.height, app.height + 1);
+
+    // The elements are the top level nodes of the component
+    assert_eq!(child.elements.len(), 2);
+    let span = dom.find_by_tag("span")[0];
+    assert_eq!(dom.node(span).id(), Some(child.elements[0]));
+
+    // The app's elements include the elements of its children
+    assert_eq!(app.elements.len(), 3);
+}
+
+#[test]
+fn snapshots_follow_updates() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        let mut count = use_signal(|| 0);
+        use_debug_value(count);
+
+        rsx! {
+            button { onclick: move |_| count += 1, "add" }
+            for i in 0..count() {
+                Child { key: "{i}", i }
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+
+    let button = dom.find_by_tag("button")[0];
+    for _ in 0..2 {
+        let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+        dom.dispatch_event(&mut vdom, button, "click", Rc::new(data), true);
+        vdom.render_immediate(&mut dom);
+    }
+
+    let app = vdom.inspect_scope(ScopeId::APP).unwrap();
+    assert_eq!(app.render_count, 3);
+    assert_eq!(app.children.len(), 2);
+    assert_eq!(app.hooks[0].debug_value.as_deref(), Some("2"));
+
+    assert!(vdom.inspect_scope(ScopeId(usize::MAX - 1)).is_none());
+}
+
+#[test]
+fn snapshots_debug_props() {
+    #[derive(Props, PartialEq, Clone, Debug)]
+    struct LabelProps {
+        text: String,
+    }
+
+    #[allow(non_snake_case)]
+    fn Label(props: LabelProps) -> Element {
+        rsx! { "{props.text}" }
+    }
+
+    fn app() -> Element {
+        rsx! {
+            Label { text: "hello" }
+            Child { i: 0 }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.rebuild_in_place();
+
+    let tree = vdom.component_tree();
+    let app = tree.get(ScopeId::APP).unwrap();
+    let label = tree.get(app.children[0]).unwrap();
+    assert_eq!(
+        label.props_debug.as_deref(),
+        Some(r#"LabelProps { text: "hello" }"#)
+    );
+
+    // Props that don't implement Debug only have a type name
+    let child = tree.get(app.children[1]).unwrap();
+    assert_eq!(child.props_debug, None);
+}
+
+#[test]
+fn untyped_contexts_are_named_when_read() {
+    fn app() -> Element {
+        consume_context::<Theme>();
+        rsx! {}
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.insert_any_root_context(Box::new(Theme));
+    let root = vdom.inspect_scope(ScopeId::ROOT).unwrap();
+    assert!(root.contexts.iter().any(|name| name == "<unknown>"));
+
+    vdom.rebuild_in_place();
+    let root = vdom.inspect_scope(ScopeId::ROOT).unwrap();
+    assert!(!root.contexts.iter().any(|name| name == "<unknown>"));
+    assert!(root.contexts.iter().any(|name| name.ends_with("Theme")));
+}
@@ -0,0 +1,173 @@
+//! Make sure the profiler attributes renders, diffs and mutations to the right scopes
+
+use dioxus::dioxus_core::{NoOpMutations, ProfileEventKind, TestDom};
+use dioxus::prelude::*;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+#[test]
+fn records_renders_and_mutations() {
+    fn app() -> Element {
+        rsx! {
+            div { "{generation()}" }
+            Child {}
+        }
+    }
+
+    #[component]
+    fn Child() -> Element {
+        rsx! { "child" }
+    }
+
+    // A fake clock that ticks once every time it is read
+    let ticks = Rc::new(Cell::new(0));
+    let clock = {
+        let ticks = ticks.clone();
+        move || {
+            ticks.set(ticks.get() + 1);
+            Duration::from_micros(ticks.get())
+        }
+    };
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.enable_profiling_with_clock(clock);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+
+    let child = ScopeId(ScopeId::APP.0 + 1);
+    let profile = vdom.profile().unwrap();
+    assert_eq!(profile.scope(ScopeId::APP).unwrap().render_count, 1);
+    assert!(profile.scope(child).unwrap().name.ends_with("Child"));
+    assert_eq!(profile.scope(child).unwrap().render_count, 1);
+    // The initial rebuild is a single diff of the root scope
+    let root = profile.scope(ScopeId::ROOT).unwrap();
+    assert!(root.mutations > 0);
+    assert!(root.diff_time > Duration::ZERO);
+
+    vdom.mark_dirty(ScopeId::APP);
+    let mut mutations = dioxus_core::Mutations::default();
+    vdom.render_immediate(&mut mutations);
+
+    let profile = vdom.disable_profiling().unwrap();
+    let app = profile.scope(ScopeId::APP).unwrap();
+    assert_eq!(app.render_count, 2);
+    // Only the text node changed and the child was memoized
+    assert_eq!(app.mutations, mutations.edits.len());
+    assert_eq!(profile.scope(child).unwrap().render_count, 1);
+
+    let last = profile.events.last().unwrap();
+    assert_eq!(last.kind, ProfileEventKind::Diff);
+    assert_eq!(last.scope, ScopeId::APP);
+
+    // Nothing is recorded once profiling is disabled
+    assert!(vdom.profile().is_none());
+    vdom.mark_dirty(ScopeId::APP);
+    vdom.render_immediate(&mut NoOpMutations);
+    assert!(vdom.profile().is_none());
+}
+
+#[test]
+fn nested_diffs_are_not_counted_twice() {
+    fn app() -> Element {
+        rsx! {
+            div { "{generation()}" }
+            Child { generation: generation() }
+        }
+    }
+
+    #[component]
+    fn Child(generation: usize) -> Element {
+        rsx! { "{generation}" }
+    }
+
+    let ticks = Rc::new(Cell::new(0));
+    let clock = {
+        let ticks = ticks.clone();
+        move || {
+            ticks.set(ticks.get() + 1);
+            Duration::from_micros(ticks.get())
+        }
+    };
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.rebuild(&mut NoOpMutations);
+    vdom.enable_profiling_with_clock(clock);
+    vdom.mark_dirty(ScopeId::APP);
+    let mut mutations = dioxus_core::Mutations::default();
+    vdom.render_immediate(&mut mutations);
+
+    // The child is diffed while the app is diffed, but each mutation belongs to exactly one of them
+    let child = ScopeId(ScopeId::APP.0 + 1);
+    let profile = vdom.disable_profiling().unwrap();
+    let app = profile.scope(ScopeId::APP).unwrap();
+    let child_profile = profile.scope(child).unwrap();
+    assert_eq!(app.mutations, 1);
+    assert_eq!(child_profile.mutations, 1);
+    assert_eq!(mutations.edits.len(), 2);
+
+    // The time spent rendering and diffing the child is only part of the app's diff duration
+    let app_diff = profile
+        .events
+        .iter()
+        .find(|event| event.scope == ScopeId::APP && event.kind == ProfileEventKind::Diff)
+        .unwrap();
+    let child_time: Duration = profile
+        .events
+        .iter()
+        .filter(|event| event.scope == child)
+        .map(|event| event.duration)
+        .sum();
+    assert_eq!(app_diff.self_duration, app_diff.duration - child_time);
+    assert_eq!(app.diff_time, app_diff.self_duration);
+}
+
+#[test]
+fn records_rerun_causes() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        let mut count = use_signal(|| 0);
+        rsx! {
+            button { onclick: move |_| count += 1, "{count}" }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    vdom.enable_profiling();
+
+    let button = dom.find_by_tag("button")[0];
+    let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+    assert!(dom.dispatch_event(&mut vdom, button, "click", Rc::new(data), true));
+    vdom.render_immediate(&mut dom);
+
+    let profile = vdom.disable_profiling().unwrap();
+    let render = profile
+        .events
+        .iter()
+        .find(|event| event.kind == ProfileEventKind::Render)
+        .unwrap();
+    assert_eq!(render.scope, ScopeId::APP);
+    assert_eq!(render.causes.len(), 1);
+    if cfg!(debug_assertions) {
+        let source = render.causes[0].source.unwrap();
+        assert_eq!(source.file(), file!());
+    }
+}
+
+#[test]
+fn chrome_trace_export() {
+    fn app() -> Element {
+        rsx! { "hello" }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.enable_profiling();
+    vdom.rebuild(&mut NoOpMutations);
+
+    let trace = vdom.profile().unwrap().to_chrome_trace();
+    assert!(trace.starts_with(r#"{"traceEvents":[{"name":"#));
+    assert!(trace.contains(r#""cat":"render","ph":"X""#));
+    assert!(trace.contains(r#""cat":"diff","ph":"X""#));
+    assert!(trace.ends_with("]}"));
+}
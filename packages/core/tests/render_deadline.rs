@@ -0,0 +1,132 @@
+//! Tests for time-sliced rendering with [`VirtualDom::render_with_deadline`]
+
+use dioxus::dioxus_core::{RenderPriority, TestDom};
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+thread_local! {
+    static RENDERS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn take_renders() -> Vec<String> {
+    RENDERS.with(|renders| std::mem::take(&mut *renders.borrow_mut()))
+}
+
+/// A deadline that passes after the given number of units of work
+fn after(mut units: usize) -> impl FnMut() -> bool {
+    move || {
+        units = units.saturating_sub(1);
+        units == 0
+    }
+}
+
+#[test]
+fn slices_work_across_calls() {
+    fn app() -> Element {
+        rsx! {
+            for i in 0..10 {
+                Child { key: "{i}", i }
+            }
+        }
+    }
+
+    #[component]
+    fn Child(i: usize) -> Element {
+        RENDERS.with(|renders| renders.borrow_mut().push(format!("child {i}")));
+        rsx! { "{i}: {generation()}" }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    take_renders();
+
+    for i in 1..=10 {
+        vdom.mark_dirty(ScopeId(ScopeId::APP.0 + i));
+    }
+
+    // Each slice only has time for three scopes
+    let mut slices = 0;
+    while vdom.render_with_deadline(&mut dom, after(3)) {
+        slices += 1;
+        assert_eq!(take_renders().len(), 3);
+    }
+    assert_eq!(slices, 3);
+    assert_eq!(take_renders().len(), 1);
+    assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+
+    // Without any work, we finish immediately
+    assert!(!vdom.render_with_deadline(&mut dom, || true));
+}
+
+#[test]
+fn input_runs_before_background_work() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        rsx! {
+            Background {}
+            Input {}
+        }
+    }
+
+    #[component]
+    fn Background() -> Element {
+        RENDERS.with(|renders| renders.borrow_mut().push("background".to_string()));
+        rsx! { "background" }
+    }
+
+    #[component]
+    fn Input() -> Element {
+        let mut count = use_signal(|| 0);
+        RENDERS.with(|renders| renders.borrow_mut().push("input".to_string()));
+        rsx! {
+            button { onclick: move |_| count += 1, "clicked {count}" }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    take_renders();
+
+    // The background scope is queued before the click, but the click should still be handled first
+    let background = ScopeId(ScopeId::APP.0 + 1);
+    vdom.mark_dirty(background);
+    let button = dom.find_by_tag("button")[0];
+    let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+    assert!(dom.dispatch_event(&mut vdom, button, "click", Rc::new(data), true));
+
+    assert!(vdom.render_with_deadline(&mut dom, after(1)));
+    assert_eq!(take_renders(), ["input"]);
+    assert_eq!(dom.text_content(button), "clicked 1");
+
+    assert!(!vdom.render_with_deadline(&mut dom, after(1)));
+    assert_eq!(take_renders(), ["background"]);
+}
+
+#[test]
+fn dirty_ancestors_run_before_input_scopes() {
+    fn app() -> Element {
+        RENDERS.with(|renders| renders.borrow_mut().push("parent".to_string()));
+        rsx! { Child {} }
+    }
+
+    #[component]
+    fn Child() -> Element {
+        RENDERS.with(|renders| renders.borrow_mut().push("child".to_string()));
+        rsx! { "child" }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    vdom.rebuild_in_place();
+    take_renders();
+
+    let child = ScopeId(ScopeId::APP.0 + 1);
+    vdom.mark_dirty(ScopeId::APP);
+    vdom.mark_dirty_with_priority(child, RenderPriority::Input);
+
+    assert!(!vdom.render_with_deadline(&mut dioxus_core::NoOpMutations, || false));
+    // The parent runs first even though only the child is in the input lane
+    assert_eq!(take_renders(), ["parent", "child"]);
+}
@@ -0,0 +1,219 @@
+//! Make sure the in-memory test dom stays in sync with the VirtualDom
+
+use dioxus::dioxus_core::{AttributeValue, BinaryMutations, MutationDecoder, TestDom};
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+fn click(vdom: &mut VirtualDom, dom: &mut TestDom, text: &str) {
+    let target = dom.find_by_text(text)[0];
+    let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+    assert!(dom.dispatch_event(vdom, target, "click", Rc::new(data), true));
+    vdom.render_immediate(dom);
+}
+
+#[test]
+fn matches_ssr_across_diffs() {
+    fn app() -> Element {
+        let order: &[usize] = match generation() % 3 {
+            0 => &[0, 1, 2, 3],
+            1 => &[3, 1, 0, 4],
+            _ => &[],
+        };
+        let even = matches!(generation() % 2, 0);
+
+        rsx! {
+            ul { class: "list",
+                for i in order {
+                    li { key: "{i}", "data-index": "{i}", "item {i}" }
+                }
+            }
+            if even {
+                p { color: "red", "even" }
+            }
+            "{generation()}"
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+
+    for _ in 0..6 {
+        vdom.mark_dirty(ScopeId::APP);
+        vdom.render_immediate(&mut dom);
+        assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+    }
+}
+
+#[test]
+fn inner_html_and_custom_attributes() {
+    fn app() -> Element {
+        rsx! {
+            div {
+                dangerous_inner_html: "<b>bold</b>",
+                "data-custom": AttributeValue::any_value(generation()),
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    assert_eq!(dom.to_html(), "<div><b>bold</b></div>");
+    assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+
+    vdom.mark_dirty(ScopeId::APP);
+    vdom.render_immediate(&mut dom);
+    assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+}
+
+#[test]
+fn removed_nodes_are_dropped() {
+    fn app() -> Element {
+        let show = generation() % 2 == 0;
+        rsx! {
+            if show {
+                ul {
+                    li { "item" }
+                }
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    let item = dom.find_by_tag("li")[0];
+
+    // Removing the list drops every node inside of it
+    vdom.mark_dirty(ScopeId::APP);
+    vdom.render_immediate(&mut dom);
+    assert!(!dom.contains(item));
+
+    // The slots of the removed nodes are reused, but the old handles never point to the new nodes
+    vdom.mark_dirty(ScopeId::APP);
+    vdom.render_immediate(&mut dom);
+    let new_item = dom.find_by_tag("li")[0];
+    assert_ne!(new_item, item);
+    assert!(dom.contains(new_item));
+    assert!(!dom.contains(item));
+}
+
+#[test]
+fn queries_and_events() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        let mut items = use_signal(Vec::<usize>::new);
+        let mut next = use_signal(|| 0);
+
+        rsx! {
+            div {
+                onclick: move |_| {
+                    items.write().push(next());
+                    next += 1;
+                },
+                button { id: "add", "add item" }
+            }
+            for (i, item) in items.iter().enumerate() {
+                span { key: "{item}", class: "item", onclick: move |_| _ = items.write().remove(i), "remove {item}" }
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+
+    // The button has no listener, so the click bubbles to the div
+    click(&mut vdom, &mut dom, "add item");
+    click(&mut vdom, &mut dom, "add item");
+    assert_eq!(dom.find_by_attribute("class", "item").len(), 2);
+    assert_eq!(dom.text_content(dom.root()), "add itemremove 0remove 1");
+
+    click(&mut vdom, &mut dom, "remove 0");
+    let items = dom.find_by_tag("span");
+    assert_eq!(items.len(), 1);
+    assert_eq!(dom.text_content(items[0]), "remove 1");
+
+    let button = dom.find_by_tag("button")[0];
+    assert_eq!(dom.node(button).attribute("id"), Some("add"));
+    assert_eq!(dom.html(button), r#"<button id="add">add item</button>"#);
+
+    // Non-bubbling events are only sent to the node itself
+    let data = PlatformEventData::new(Box::<SerializedMouseData>::default());
+    assert!(!dom.dispatch_event(&mut vdom, button, "click", Rc::new(data), false));
+}
+
+#[test]
+fn replay_recorded_mutations() {
+    fn app() -> Element {
+        rsx! {
+            div { "hello" }
+            for i in 0..generation() {
+                "{i}"
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut recorder = BinaryMutations::new();
+    let mut frames = Vec::new();
+    vdom.rebuild(&mut recorder);
+    frames.push(recorder.take());
+    for _ in 0..3 {
+        vdom.mark_dirty(ScopeId::APP);
+        vdom.render_immediate(&mut recorder);
+        frames.push(recorder.take());
+    }
+
+    let mut dom = TestDom::new();
+    let mut decoder = MutationDecoder::new();
+    for frame in frames {
+        decoder.decode(&frame, &mut dom).unwrap();
+    }
+    assert_eq!(dom.to_html(), dioxus_ssr::render(&vdom));
+}
+
+#[test]
+fn replay_the_mounted_dom() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        let mut count = use_signal(|| 0);
+        rsx! {
+            button { onclick: move |_| count += 1, "count {count}" }
+            if count() % 2 == 1 {
+                p { class: "odd", "odd" }
+            }
+            for i in 0..count() {
+                Item { i }
+            }
+        }
+    }
+
+    #[component]
+    fn Item(i: i32) -> Element {
+        rsx! { li { "data-index": "{i}", "item {i}" } }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let mut dom = TestDom::new();
+    vdom.rebuild(&mut dom);
+    click(&mut vdom, &mut dom, "count 0");
+    click(&mut vdom, &mut dom, "count 1");
+
+    // A renderer that starts out empty gets the same DOM without rerunning any components
+    let mut replayed = TestDom::new();
+    vdom.replay(&mut replayed);
+    assert_eq!(replayed.to_html(), dom.to_html());
+    assert_eq!(replayed.to_html(), dioxus_ssr::render(&vdom));
+
+    // Later mutations and events apply to the replayed DOM, including templates that were not mounted during the
+    // replay
+    click(&mut vdom, &mut replayed, "count 2");
+    assert_eq!(replayed.to_html(), dioxus_ssr::render(&vdom));
+    assert_eq!(replayed.find_by_attribute("class", "odd").len(), 1);
+    assert_eq!(replayed.find_by_tag("li").len(), 3);
+}
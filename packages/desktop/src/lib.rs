@@ -20,6 +20,8 @@ mod menubar;
 mod protocol;
 mod query;
 mod shortcut;
+#[cfg(feature = "persistent")]
+mod storage;
 mod waker;
 mod webview;
 
@@ -0,0 +1,43 @@
+use dioxus_core::ScopeId;
+use dioxus_signals::{FileStorage, PersistentStorage};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+thread_local! {
+    // Every window that uses the same data directory shares one storage so they don't overwrite each other's values
+    static STORAGE: RefCell<HashMap<PathBuf, Rc<FileStorage>>> = RefCell::new(HashMap::new());
+}
+
+/// Provides the storage for persistent signals if no storage was provided. Values are saved in a JSON file inside
+/// the data directory from [`Config::with_data_directory`](crate::Config::with_data_directory), or the local data
+/// directory of the user if none was set.
+pub(crate) fn init_storage(data_dir: Option<&Path>) {
+    if ScopeId::ROOT
+        .has_context::<Rc<dyn PersistentStorage>>()
+        .is_some()
+    {
+        return;
+    }
+    let Some(dir) = data_dir.map(Path::to_path_buf).or_else(default_data_dir) else {
+        tracing::warn!("No data directory found. Persistent signals will not be saved.");
+        return;
+    };
+    let path = dir.join("persistent_signals.json");
+    let storage = STORAGE.with_borrow_mut(|storage| {
+        storage
+            .entry(path.clone())
+            .or_insert_with(|| Rc::new(FileStorage::new(path)))
+            .clone()
+    });
+    let provider: Rc<dyn PersistentStorage> = storage;
+    ScopeId::ROOT.provide_context(provider);
+}
+
+fn default_data_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(dirs::data_local_dir()?.join(exe.file_stem()?))
+}
@@ -226,6 +226,8 @@ impl WebviewInstance {
         dom.in_runtime(|| {
             ScopeId::ROOT.provide_context(desktop_context.clone());
             ScopeId::ROOT.provide_context(provider);
+            #[cfg(feature = "persistent")]
+            crate::storage::init_storage(cfg.data_dir.as_deref());
         });
 
         WebviewInstance {
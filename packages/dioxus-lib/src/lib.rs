@@ -33,7 +33,7 @@ pub mod prelude {
 
     #[cfg(feature = "macro")]
     #[allow(deprecated)]
-    pub use dioxus_core_macro::{component, rsx, Props};
+    pub use dioxus_core_macro::{component, rsx, Props, Store};
 
     #[cfg(feature = "macro")]
     pub use dioxus_config_macro::*;
@@ -370,6 +370,9 @@ pub async fn render_handler(
     let build_virtual_dom = state.build_virtual_dom.clone();
 
     let (parts, _) = request.into_parts();
+    // Only GET and HEAD requests can be answered with 304 Not Modified
+    let conditional_headers = matches!(parts.method, http::Method::GET | http::Method::HEAD)
+        .then(|| parts.headers.clone());
     let url = parts
         .uri
         .path_and_query()
@@ -384,7 +387,14 @@ pub async fn render_handler(
         .await
     {
         Ok((freshness, rx)) => {
-            let mut response = axum::response::Html::from(Body::from_stream(rx)).into_response();
+            // If the client already has this render cached, skip sending the body
+            let not_modified = conditional_headers
+                .is_some_and(|request_headers| freshness.is_not_modified(&request_headers));
+            let mut response = if not_modified {
+                StatusCode::NOT_MODIFIED.into_response()
+            } else {
+                axum::response::Html::from(Body::from_stream(rx)).into_response()
+            };
             freshness.write(response.headers_mut());
             let headers = server_context.response_parts().headers.clone();
             apply_request_parts_to_response(headers, &mut response);
@@ -496,3 +506,74 @@ async fn handle_server_fns_inner(
         future().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serve_config::load_index_html;
+    use dioxus_lib::prelude::VNode;
+    use dioxus_ssr::incremental::IncrementalRenderer;
+    use tower::ServiceExt;
+
+    fn app() -> Element {
+        VNode::empty()
+    }
+
+    fn router() -> Router {
+        let incremental = IncrementalRenderer::builder()
+            .static_dir(std::env::temp_dir().join("dioxus-fullstack-axum-not-modified"))
+            .clear_cache(true);
+        let config = ServeConfig {
+            index: load_index_html(
+                r#"<html><head></head><body><div id="main"></div></body></html>"#.to_string(),
+                "main",
+            ),
+            incremental: Some(incremental),
+        };
+        let ssr_state = SSRState::new(&config);
+        let state = RenderHandleState {
+            config,
+            build_virtual_dom: Arc::new(|| VirtualDom::new(app)),
+            ssr_state: Default::default(),
+        }
+        .with_ssr_state(ssr_state);
+        Router::new().fallback(get(render_handler).with_state(state))
+    }
+
+    fn request(if_none_match: Option<&str>) -> Request<Body> {
+        let mut request = Request::get("/").header(ACCEPT, "text/html");
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_renders_answer_conditional_requests() {
+        let router = router();
+
+        // Fresh renders are streamed before the html is known, so only the cached render has an ETag
+        let response = router.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(ETAG));
+        body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        let response = router.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+
+        // The client already has the cached render, so the body is skipped
+        let response = router.clone().oneshot(request(Some(&etag))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag);
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+
+        let response = router.oneshot(request(Some("\"other\""))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
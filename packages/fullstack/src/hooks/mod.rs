@@ -1,2 +1,3 @@
 pub mod server_cached;
 pub mod server_future;
+pub mod server_query;
@@ -0,0 +1,90 @@
+use dioxus_lib::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+
+/// Like [`use_query`], but the query is fetched on the server while the page renders and the data is sent to the
+/// client with the html.
+///
+/// On the server, this suspends until the query has data. The data is serialized into the page, and hydrated into
+/// the [`QueryClient`] cache on the client without fetching it again. After hydration, the query behaves like any
+/// other query: it is shared with every component that uses the same key, and it is refetched when it becomes stale.
+///
+/// When you run this function on the client, you need to be careful to insure the order you run it initially is the
+/// same order you run it on the server.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn fetch_article(id: u32) -> String { todo!() }
+/// #[component]
+/// fn Article(id: u32) -> Element {
+///     // Since we bubble up the suspense with `?`, the server will wait for the query to resolve before rendering
+///     let article = use_server_query(("article", id), move || fetch_article(id))?;
+///
+///     rsx! {
+///         "{article.data().unwrap_or_default()}"
+///     }
+/// }
+/// ```
+#[must_use = "Consider using `spawn` to run a future without reading its value"]
+#[track_caller]
+pub fn use_server_query<T, F>(
+    key: impl Into<QueryKey>,
+    fetcher: impl Fn() -> F + 'static,
+) -> Result<Query<T>, RenderError>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+    F: Future<Output = T> + 'static,
+{
+    use_server_query_with(QueryOptions::default(), key, fetcher)
+}
+
+/// Like [`use_server_query`], but with control over when the data becomes stale and is refetched on the client.
+#[must_use = "Consider using `spawn` to run a future without reading its value"]
+#[track_caller]
+pub fn use_server_query_with<T, F>(
+    options: QueryOptions,
+    key: impl Into<QueryKey>,
+    fetcher: impl Fn() -> F + 'static,
+) -> Result<Query<T>, RenderError>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+    F: Future<Output = T> + 'static,
+{
+    let key = key.into();
+
+    #[cfg(feature = "server")]
+    let serialize_context = crate::html_storage::use_serialize_context();
+    // We always create a storage entry, even if the data isn't ready yet to keep the order of the data the same on the client
+    #[cfg(feature = "server")]
+    let server_storage_entry = use_hook(|| serialize_context.create_entry());
+
+    // If this is the first run and we are on the web client, seed the cache with the data from the server
+    #[cfg(feature = "web")]
+    {
+        let client = use_query_client();
+        use_hook(|| {
+            if let Ok(Some(data)) = dioxus_web::take_server_data::<T>() {
+                client.hydrate(key.clone(), data);
+            }
+        });
+    }
+
+    let query = use_query_with(options, key, fetcher);
+
+    // Once the data is ready on the server, serialize it into the slot we reserved for it
+    #[cfg(feature = "server")]
+    {
+        let serialized = use_hook(|| std::rc::Rc::new(std::cell::Cell::new(false)));
+        if !serialized.get() {
+            if let Some(data) = query.data() {
+                serialize_context.insert(server_storage_entry, &data);
+                serialized.set(true);
+            }
+        }
+    }
+
+    // Suspend if the data isn't ready
+    query.suspend().map(|_| query)
+}
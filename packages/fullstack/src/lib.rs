@@ -35,7 +35,11 @@ mod server_context;
 /// A prelude of commonly used items in dioxus-fullstack.
 pub mod prelude {
     use crate::hooks;
-    pub use hooks::{server_cached::use_server_cached, server_future::use_server_future};
+    pub use hooks::{
+        server_cached::use_server_cached,
+        server_future::use_server_future,
+        server_query::{use_server_query, use_server_query_with},
+    };
 
     #[cfg(feature = "axum")]
     #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
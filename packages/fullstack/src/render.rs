@@ -2,7 +2,7 @@
 use crate::streaming::{Mount, StreamingRenderer};
 use dioxus_interpreter_js::INITIALIZE_STREAMING_JS;
 use dioxus_ssr::{
-    incremental::{CachedRender, RenderFreshness},
+    incremental::{CachedRender, RegenerationGuard, RenderFreshness},
     Renderer,
 };
 use futures_channel::mpsc::Sender;
@@ -99,6 +99,16 @@ impl SsrRendererPool {
         None
     }
 
+    /// Mark a stale route as regenerating. Returns `None` if incremental rendering is disabled or the route is already
+    /// being regenerated by another request
+    fn start_regeneration(&self, route: &str) -> Option<RegenerationGuard> {
+        self.incremental_cache
+            .as_ref()?
+            .read()
+            .ok()?
+            .start_regeneration(route)
+    }
+
     /// Render a virtual dom into a stream. This method will return immediately and continue streaming the result in the background
     /// The streaming is canceled when the stream the function returns is dropped
     async fn render_to(
@@ -141,19 +151,37 @@ impl SsrRendererPool {
             }
         }
 
-        let (mut into, rx) = futures_channel::mpsc::channel::<
+        let (mut into, mut rx) = futures_channel::mpsc::channel::<
             Result<String, dioxus_ssr::incremental::IncrementalRendererError>,
         >(1000);
 
         // before we even spawn anything, we can check synchronously if we have the route cached
+        let mut stale_render = None;
+        let mut regeneration = None;
         if let Some(freshness) = self.check_cached_route(&route, &mut into) {
-            return Ok((
-                freshness,
-                ReceiverWithDrop {
-                    receiver: rx,
-                    cancel_task: None,
-                },
-            ));
+            regeneration = freshness
+                .is_stale()
+                .then(|| self.start_regeneration(&route))
+                .flatten();
+            if regeneration.is_none() {
+                return Ok((
+                    freshness,
+                    ReceiverWithDrop {
+                        receiver: rx,
+                        cancel_task: None,
+                    },
+                ));
+            }
+
+            // The cached render is stale. Serve it immediately and render the route again in the background
+            // The new render is only written to the cache, so it is sent into a channel nobody reads
+            let (regenerate_into, regenerate_rx) = futures_channel::mpsc::channel(1000);
+            into = regenerate_into;
+            let stale = ReceiverWithDrop {
+                receiver: std::mem::replace(&mut rx, regenerate_rx),
+                cancel_task: None,
+            };
+            stale_render = Some((freshness, stale));
         }
 
         let wrapper = FullstackHTMLTemplate { cfg: cfg.clone() };
@@ -347,6 +375,8 @@ impl SsrRendererPool {
                     let _ = incremental.cache(route, cached_render);
                 }
             }
+            // The new render is cached, so the route can be regenerated again once it becomes stale
+            drop(regeneration);
 
             stream.render(post_streaming);
 
@@ -354,6 +384,11 @@ impl SsrRendererPool {
             myself.renderers.write().unwrap().push(renderer);
         });
 
+        // If we are regenerating a stale render, serve the stale render and let the new render finish in the background
+        if let Some(stale_render) = stale_render {
+            return Ok(stale_render);
+        }
+
         Ok((
             RenderFreshness::now(None),
             ReceiverWithDrop {
@@ -93,7 +93,7 @@ fn load_index_path(path: PathBuf) -> String {
     contents
 }
 
-fn load_index_html(contents: String, root_id: &'static str) -> IndexHtml {
+pub(crate) fn load_index_html(contents: String, root_id: &'static str) -> IndexHtml {
     let (pre_main, post_main) = contents.split_once(&format!("id=\"{root_id}\"")).unwrap_or_else(|| panic!("Failed to find id=\"{root_id}\" in index.html. The id is used to inject the application into the page."));
 
     let post_main = post_main.split_once('>').unwrap_or_else(|| {
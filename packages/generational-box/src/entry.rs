@@ -7,6 +7,9 @@ pub(crate) struct StorageEntry<T> {
     generation: u64,
 
     pub data: Option<T>,
+
+    // If the value was leaked instead of inserted into an owner
+    pub leaked: bool,
 }
 
 impl<T> Default for StorageEntry<T> {
@@ -14,6 +17,7 @@ impl<T> Default for StorageEntry<T> {
         Self {
             generation: 0,
             data: None,
+            leaked: false,
         }
     }
 }
@@ -46,10 +50,14 @@ impl MemoryLocationBorrowInfo {
             if let Some(borrowed_mut_at) = borrow.borrowed_mut_at.as_ref() {
                 BorrowMutError::AlreadyBorrowedMut(crate::error::AlreadyBorrowedMutError {
                     borrowed_mut_at,
+                    #[cfg(feature = "debug_borrow_threads")]
+                    borrowed_mut_on: borrow.borrowed_mut_on.unwrap(),
                 })
             } else {
                 BorrowMutError::AlreadyBorrowed(crate::error::AlreadyBorrowedError {
                     borrowed_at: borrow.borrowed_at.clone(),
+                    #[cfg(feature = "debug_borrow_threads")]
+                    borrowed_on: borrow.borrowed_on.clone(),
                 })
             }
         }
@@ -60,12 +68,30 @@ impl MemoryLocationBorrowInfo {
     }
 
     pub(crate) fn borrow_error(&self) -> BorrowError {
+        #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+        let borrow = self.0.read();
         BorrowError::AlreadyBorrowedMut(crate::error::AlreadyBorrowedMutError {
-            #[cfg(any(debug_assertions, feature = "debug_ownership"))]
-            borrowed_mut_at: self.0.read().borrowed_mut_at.unwrap(),
+            #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+            borrowed_mut_at: borrow.borrowed_mut_at.unwrap(),
+            #[cfg(feature = "debug_borrow_threads")]
+            borrowed_mut_on: borrow.borrowed_mut_on.unwrap(),
         })
     }
 
+    /// Check if the current thread holds a mutable borrow. Locking the value again on this thread would deadlock.
+    #[cfg(feature = "debug_borrow_threads")]
+    pub(crate) fn borrowed_mut_on_current_thread(&self) -> bool {
+        self.0.read().borrowed_mut_on == Some(std::thread::current().id())
+    }
+
+    /// Check if the current thread holds any borrow. Locking the value mutably on this thread would deadlock.
+    #[cfg(feature = "debug_borrow_threads")]
+    pub(crate) fn borrowed_on_current_thread(&self) -> bool {
+        let current = std::thread::current().id();
+        let borrow = self.0.read();
+        borrow.borrowed_mut_on == Some(current) || borrow.borrowed_on.contains(&current)
+    }
+
     /// Start a new borrow
     #[track_caller]
     pub(crate) fn borrow_guard(&'static self) -> GenerationalRefBorrowGuard {
@@ -75,6 +101,8 @@ impl MemoryLocationBorrowInfo {
         {
             let mut borrow = self.0.write();
             borrow.borrowed_at.push(borrowed_at);
+            #[cfg(feature = "debug_borrow_threads")]
+            borrow.borrowed_on.push(std::thread::current().id());
         }
 
         GenerationalRefBorrowGuard {
@@ -94,6 +122,10 @@ impl MemoryLocationBorrowInfo {
         {
             let mut borrow = self.0.write();
             borrow.borrowed_mut_at = Some(borrowed_mut_at);
+            #[cfg(feature = "debug_borrow_threads")]
+            {
+                borrow.borrowed_mut_on = Some(std::thread::current().id());
+            }
         }
 
         GenerationalRefBorrowMutGuard {
@@ -109,9 +141,23 @@ impl MemoryLocationBorrowInfo {
         #[cfg(any(debug_assertions, feature = "debug_borrows"))]
         {
             let mut borrow = self.0.write();
+            #[cfg(not(feature = "debug_borrow_threads"))]
             borrow
                 .borrowed_at
                 .retain(|location| *location != borrowed_at);
+            // Only remove this borrow so the borrows of other threads from the same location are still tracked
+            #[cfg(feature = "debug_borrow_threads")]
+            {
+                let current = std::thread::current().id();
+                let index =
+                    borrow.borrowed_at.iter().zip(&borrow.borrowed_on).position(
+                        |(location, thread)| *location == borrowed_at && *thread == current,
+                    );
+                if let Some(index) = index {
+                    borrow.borrowed_at.remove(index);
+                    borrow.borrowed_on.remove(index);
+                }
+            }
         }
     }
 
@@ -122,9 +168,24 @@ impl MemoryLocationBorrowInfo {
             let mut borrow = self.0.write();
             if borrow.borrowed_mut_at == Some(borrowed_mut_at) {
                 borrow.borrowed_mut_at = None;
+                #[cfg(feature = "debug_borrow_threads")]
+                {
+                    borrow.borrowed_mut_on = None;
+                }
             }
         }
     }
+
+    /// Release the memory used to track borrows
+    pub(crate) fn shrink(&self) {
+        #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+        {
+            let mut borrow = self.0.write();
+            borrow.borrowed_at.shrink_to_fit();
+            #[cfg(feature = "debug_borrow_threads")]
+            borrow.borrowed_on.shrink_to_fit();
+        }
+    }
 }
 
 #[cfg(any(debug_assertions, feature = "debug_borrows"))]
@@ -132,4 +193,9 @@ impl MemoryLocationBorrowInfo {
 struct MemoryLocationBorrowInfoInner {
     borrowed_at: Vec<&'static std::panic::Location<'static>>,
     borrowed_mut_at: Option<&'static std::panic::Location<'static>>,
+    // The threads that hold each borrow in borrowed_at
+    #[cfg(feature = "debug_borrow_threads")]
+    borrowed_on: Vec<std::thread::ThreadId>,
+    #[cfg(feature = "debug_borrow_threads")]
+    borrowed_mut_on: Option<std::thread::ThreadId>,
 }
@@ -71,7 +71,7 @@ impl ValueDroppedError {
     #[allow(unused)]
     pub(crate) fn new_for_location(location: GenerationalLocation) -> Self {
         Self {
-            #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+            #[cfg(any(debug_assertions, feature = "debug_ownership"))]
             created_at: location.created_at,
         }
     }
@@ -93,15 +93,19 @@ impl std::error::Error for ValueDroppedError {}
 pub struct AlreadyBorrowedMutError {
     #[cfg(any(debug_assertions, feature = "debug_borrows"))]
     pub(crate) borrowed_mut_at: &'static std::panic::Location<'static>,
+    #[cfg(feature = "debug_borrow_threads")]
+    pub(crate) borrowed_mut_on: std::thread::ThreadId,
 }
 
 impl AlreadyBorrowedMutError {
-    /// Create a new `AlreadyBorrowedMutError`.
+    /// Create a new `AlreadyBorrowedMutError` for a mutable borrow held by the current thread.
     #[allow(unused)]
     pub fn new(borrowed_mut_at: &'static std::panic::Location<'static>) -> Self {
         Self {
             #[cfg(any(debug_assertions, feature = "debug_borrows"))]
             borrowed_mut_at,
+            #[cfg(feature = "debug_borrow_threads")]
+            borrowed_mut_on: std::thread::current().id(),
         }
     }
 }
@@ -111,6 +115,8 @@ impl Display for AlreadyBorrowedMutError {
         f.write_str("Failed to borrow because the value was already borrowed mutably.")?;
         #[cfg(any(debug_assertions, feature = "debug_borrows"))]
         f.write_fmt(format_args!("borrowed_mut_at: {}", self.borrowed_mut_at))?;
+        #[cfg(feature = "debug_borrow_threads")]
+        f.write_fmt(format_args!(" on {:?}", self.borrowed_mut_on))?;
         Ok(())
     }
 }
@@ -122,13 +128,17 @@ impl std::error::Error for AlreadyBorrowedMutError {}
 pub struct AlreadyBorrowedError {
     #[cfg(any(debug_assertions, feature = "debug_borrows"))]
     pub(crate) borrowed_at: Vec<&'static std::panic::Location<'static>>,
+    #[cfg(feature = "debug_borrow_threads")]
+    pub(crate) borrowed_on: Vec<std::thread::ThreadId>,
 }
 
 impl AlreadyBorrowedError {
-    /// Create a new `AlreadyBorrowedError`.
+    /// Create a new `AlreadyBorrowedError` for borrows held by the current thread.
     #[allow(unused)]
     pub fn new(borrowed_at: Vec<&'static std::panic::Location<'static>>) -> Self {
         Self {
+            #[cfg(feature = "debug_borrow_threads")]
+            borrowed_on: vec![std::thread::current().id(); borrowed_at.len()],
             #[cfg(any(debug_assertions, feature = "debug_borrows"))]
             borrowed_at,
         }
@@ -140,10 +150,17 @@ impl Display for AlreadyBorrowedError {
         f.write_str("Failed to borrow mutably because the value was already borrowed immutably.")?;
         #[cfg(any(debug_assertions, feature = "debug_borrows"))]
         f.write_str("borrowed_at:")?;
-        #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+        #[cfg(all(
+            any(debug_assertions, feature = "debug_borrows"),
+            not(feature = "debug_borrow_threads")
+        ))]
         for location in self.borrowed_at.iter() {
             f.write_fmt(format_args!("\t{}", location))?;
         }
+        #[cfg(feature = "debug_borrow_threads")]
+        for (location, thread) in self.borrowed_at.iter().zip(&self.borrowed_on) {
+            f.write_fmt(format_args!("\t{} on {:?}", location, thread))?;
+        }
         Ok(())
     }
 }
@@ -11,12 +11,14 @@ use std::{
 
 pub use error::*;
 pub use references::*;
+pub use stats::StorageStats;
 pub use sync::SyncStorage;
 pub use unsync::UnsyncStorage;
 
 mod entry;
 mod error;
 mod references;
+mod stats;
 mod sync;
 mod unsync;
 
@@ -55,7 +57,7 @@ impl<T, S: Storage<T>> GenerationalBox<T, S> {
     /// a box that needs to be manually dropped with no owners.
     #[track_caller]
     pub fn leak(value: T) -> Self {
-        let location = S::claim(std::panic::Location::caller());
+        let location = S::claim_leaked(std::panic::Location::caller());
         location.set(value);
         Self {
             raw: location,
@@ -211,6 +213,38 @@ pub trait AnyStorage: Default + 'static {
     /// Claim a new memory location. This will either create a new memory location or recycle an old one.
     fn claim(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self>;
 
+    /// Claim a new memory location that is not owned by any [`Owner`]. The location is counted in
+    /// [`StorageStats::leaked`] until it is recycled.
+    ///
+    /// By default this claims a normal memory location, so the location is not counted as leaked.
+    fn claim_leaked(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self> {
+        Self::claim(caller)
+    }
+
+    /// Get statistics about the memory locations of this storage type. [`UnsyncStorage`] keeps a separate pool of
+    /// memory locations for each thread, so its statistics only include the current thread.
+    ///
+    /// Storage types that don't track their memory locations return empty statistics.
+    fn stats() -> StorageStats {
+        StorageStats::default()
+    }
+
+    /// Free recycled memory locations until at most `keep` are left in the pool, and release the spare capacity of the
+    /// pool and the memory the remaining locations use to track borrows. This is useful after a large number of values
+    /// are dropped at once, like when a long list or a whole app is torn down.
+    ///
+    /// Does nothing by default.
+    ///
+    /// # Safety
+    ///
+    /// Generational boxes that point to a dropped value still read the generation of their memory location, so a box
+    /// that points to a freed location would read freed memory. The caller must make sure that no generational box,
+    /// [`GenerationalPointer`] or reference created for a value that was dropped before this call is used afterwards,
+    /// for example by only calling this once every box that was created for the dropped values is gone.
+    unsafe fn shrink_recycled(keep: usize) {
+        let _ = keep;
+    }
+
     /// Create a new owner. The owner will be responsible for dropping all of the generational boxes that it creates.
     fn owner() -> Owner<Self> {
         Owner(Arc::new(Mutex::new(OwnerInner {
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Statistics about the memory locations of a storage type. See [`AnyStorage::stats`](crate::AnyStorage::stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageStats {
+    /// The number of memory locations that hold a value that has not been dropped yet, including leaked values.
+    pub live: usize,
+    /// The number of memory locations in the recycled pool that are waiting to be reused.
+    pub recycled: usize,
+    /// The highest number of live memory locations at any point.
+    pub peak: usize,
+    /// The number of live values that were created with [`GenerationalBox::leak`](crate::GenerationalBox::leak) and
+    /// not dropped yet.
+    pub leaked: usize,
+    /// The number of bytes used by the memory locations and the recycled pool. This does not include any heap memory
+    /// owned by the values themselves.
+    pub bytes: usize,
+}
+
+/// Counters for the memory locations of a storage type
+pub(crate) struct Counters {
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    leaked: AtomicUsize,
+}
+
+impl Counters {
+    pub(crate) const fn new() -> Self {
+        Self {
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            leaked: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn claim(&self, leaked: bool) {
+        let live = self.live.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak.fetch_max(live, Ordering::Relaxed);
+        if leaked {
+            self.leaked.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn recycle(&self, leaked: bool) {
+        self.live.fetch_sub(1, Ordering::Relaxed);
+        if leaked {
+            self.leaked.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Get the stats for a storage type with a recycled pool of `recycled` memory locations and `capacity` entries
+    pub(crate) fn stats<S>(&self, recycled: usize, capacity: usize) -> StorageStats {
+        let live = self.live.load(Ordering::Relaxed);
+        StorageStats {
+            live,
+            recycled,
+            peak: self.peak.load(Ordering::Relaxed),
+            leaked: self.leaked.load(Ordering::Relaxed),
+            bytes: (live + recycled) * std::mem::size_of::<S>()
+                + capacity * std::mem::size_of::<&S>(),
+        }
+    }
+}
@@ -7,7 +7,8 @@ use crate::{
     entry::{MemoryLocationBorrowInfo, StorageEntry},
     error::{self, ValueDroppedError},
     references::{GenerationalRef, GenerationalRefMut},
-    AnyStorage, GenerationalLocation, GenerationalPointer, Storage,
+    stats::Counters,
+    AnyStorage, GenerationalLocation, GenerationalPointer, Storage, StorageStats,
 };
 
 /// A thread safe storage. This is slower than the unsync storage, but allows you to share the value between threads.
@@ -19,6 +20,8 @@ pub struct SyncStorage {
 
 static SYNC_RUNTIME: OnceLock<Arc<Mutex<Vec<&'static SyncStorage>>>> = OnceLock::new();
 
+static SYNC_COUNTERS: Counters = Counters::new();
+
 fn sync_runtime() -> &'static Arc<Mutex<Vec<&'static SyncStorage>>> {
     SYNC_RUNTIME.get_or_init(|| Arc::new(Mutex::new(Vec::new())))
 }
@@ -72,13 +75,61 @@ impl AnyStorage for SyncStorage {
     }
 
     #[track_caller]
-    #[allow(unused)]
     fn claim(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self> {
-        match sync_runtime().lock().pop() {
+        SyncStorage::claim_with(caller, false)
+    }
+
+    #[track_caller]
+    fn claim_leaked(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self> {
+        SyncStorage::claim_with(caller, true)
+    }
+
+    fn recycle(pointer: GenerationalPointer<Self>) -> Option<Box<dyn std::any::Any>> {
+        let mut borrow_mut = pointer.storage.data.write();
+        // First check if the generation is still valid
+        if !borrow_mut.valid(&pointer.location) {
+            return None;
+        }
+        borrow_mut.increment_generation();
+        let old_data = borrow_mut.data.take();
+        SYNC_COUNTERS.recycle(std::mem::take(&mut borrow_mut.leaked));
+        sync_runtime().lock().push(pointer.storage);
+        old_data.map(|data| data as Box<dyn std::any::Any>)
+    }
+
+    fn stats() -> StorageStats {
+        let runtime = sync_runtime().lock();
+        SYNC_COUNTERS.stats::<Self>(runtime.len(), runtime.capacity())
+    }
+
+    unsafe fn shrink_recycled(keep: usize) {
+        let mut runtime = sync_runtime().lock();
+        // Free the locations that were recycled first. The most recently recycled ones are reused first
+        let freed = runtime.len().saturating_sub(keep);
+        for storage in runtime.drain(..freed) {
+            // SAFETY: Every location is leaked from a box when it is claimed, and the caller guarantees nothing points
+            // to the recycled locations anymore
+            drop(unsafe { Box::from_raw(storage as *const Self as *mut Self) });
+        }
+        runtime.shrink_to_fit();
+        for storage in runtime.iter() {
+            storage.borrow_info.shrink();
+        }
+    }
+}
+
+impl SyncStorage {
+    #[allow(unused)]
+    fn claim_with(
+        caller: &'static std::panic::Location<'static>,
+        leaked: bool,
+    ) -> GenerationalPointer<Self> {
+        SYNC_COUNTERS.claim(leaked);
+        let pointer = match sync_runtime().lock().pop() {
             Some(mut storage) => {
                 let location = GenerationalLocation {
                     generation: storage.data.read().generation(),
-                    #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+                    #[cfg(any(debug_assertions, feature = "debug_ownership"))]
                     created_at: caller,
                 };
                 GenerationalPointer { storage, location }
@@ -88,25 +139,51 @@ impl AnyStorage for SyncStorage {
 
                 let location = GenerationalLocation {
                     generation: 0,
-                    #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+                    #[cfg(any(debug_assertions, feature = "debug_ownership"))]
                     created_at: caller,
                 };
 
                 GenerationalPointer { storage, location }
             }
+        };
+        if leaked {
+            pointer.storage.data.write().leaked = true;
         }
+        pointer
     }
 
-    fn recycle(pointer: GenerationalPointer<Self>) -> Option<Box<dyn std::any::Any>> {
-        let mut borrow_mut = pointer.storage.data.write();
-        // First check if the generation is still valid
-        if !borrow_mut.valid(&pointer.location) {
-            return None;
+    /// Lock the value for reading. If the debug_borrow_threads feature is enabled and this thread already holds the
+    /// write lock, this returns an error instead of deadlocking.
+    fn read_lock(
+        &self,
+    ) -> Result<
+        RwLockReadGuard<'_, StorageEntry<Box<dyn std::any::Any + Send + Sync>>>,
+        error::BorrowError,
+    > {
+        #[cfg(feature = "debug_borrow_threads")]
+        if let Some(read) = self.data.try_read() {
+            return Ok(read);
+        } else if self.borrow_info.borrowed_mut_on_current_thread() {
+            return Err(self.borrow_info.borrow_error());
         }
-        borrow_mut.increment_generation();
-        let old_data = borrow_mut.data.take();
-        sync_runtime().lock().push(pointer.storage);
-        old_data.map(|data| data as Box<dyn std::any::Any>)
+        Ok(self.data.read())
+    }
+
+    /// Lock the value for writing. If the debug_borrow_threads feature is enabled and this thread already holds a
+    /// lock, this returns an error instead of deadlocking.
+    fn write_lock(
+        &self,
+    ) -> Result<
+        RwLockWriteGuard<'_, StorageEntry<Box<dyn std::any::Any + Send + Sync>>>,
+        error::BorrowMutError,
+    > {
+        #[cfg(feature = "debug_borrow_threads")]
+        if let Some(write) = self.data.try_write() {
+            return Ok(write);
+        } else if self.borrow_info.borrowed_on_current_thread() {
+            return Err(self.borrow_info.borrow_mut_error());
+        }
+        Ok(self.data.write())
     }
 }
 
@@ -115,7 +192,7 @@ impl<T: Sync + Send + 'static> Storage<T> for SyncStorage {
     fn try_read(
         pointer: GenerationalPointer<Self>,
     ) -> Result<Self::Ref<'static, T>, error::BorrowError> {
-        let read = pointer.storage.data.read();
+        let read = pointer.storage.read_lock()?;
 
         let read = RwLockReadGuard::try_map(read, |any| {
             // Verify the generation is still correct
@@ -140,7 +217,7 @@ impl<T: Sync + Send + 'static> Storage<T> for SyncStorage {
     fn try_write(
         pointer: GenerationalPointer<Self>,
     ) -> Result<Self::Mut<'static, T>, error::BorrowMutError> {
-        let write = pointer.storage.data.write();
+        let write = pointer.storage.write_lock()?;
 
         let write = RwLockWriteGuard::try_map(write, |any| {
             // Verify the generation is still correct
@@ -2,12 +2,15 @@ use crate::{
     entry::{MemoryLocationBorrowInfo, StorageEntry},
     error,
     references::{GenerationalRef, GenerationalRefMut},
+    stats::Counters,
     AnyStorage, BorrowError, BorrowMutError, GenerationalLocation, GenerationalPointer, Storage,
+    StorageStats,
 };
 use std::cell::{Ref, RefCell, RefMut};
 
 thread_local! {
     static UNSYNC_RUNTIME: RefCell<Vec<&'static UnsyncStorage>> = const { RefCell::new(Vec::new()) };
+    static UNSYNC_COUNTERS: Counters = const { Counters::new() };
 }
 
 /// A unsync storage. This is the default storage type.
@@ -81,13 +84,68 @@ impl AnyStorage for UnsyncStorage {
         self.data.as_ptr() as *const ()
     }
 
-    #[allow(unused)]
     fn claim(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self> {
+        UnsyncStorage::claim_with(caller, false)
+    }
+
+    fn claim_leaked(caller: &'static std::panic::Location<'static>) -> GenerationalPointer<Self> {
+        UnsyncStorage::claim_with(caller, true)
+    }
+
+    fn recycle(pointer: GenerationalPointer<Self>) -> Option<Box<dyn std::any::Any>> {
+        let mut borrow_mut = pointer.storage.data.borrow_mut();
+
+        // First check if the generation is still valid
+        if !borrow_mut.valid(&pointer.location) {
+            return None;
+        }
+
+        borrow_mut.increment_generation();
+        let old_data = borrow_mut.data.take();
+        UNSYNC_COUNTERS.with(|counters| counters.recycle(std::mem::take(&mut borrow_mut.leaked)));
+        UNSYNC_RUNTIME.with(|runtime| runtime.borrow_mut().push(pointer.storage));
+
+        old_data
+    }
+
+    fn stats() -> StorageStats {
+        UNSYNC_RUNTIME.with(|runtime| {
+            let runtime = runtime.borrow();
+            UNSYNC_COUNTERS
+                .with(|counters| counters.stats::<Self>(runtime.len(), runtime.capacity()))
+        })
+    }
+
+    unsafe fn shrink_recycled(keep: usize) {
         UNSYNC_RUNTIME.with(|runtime| {
+            let mut runtime = runtime.borrow_mut();
+            // Free the locations that were recycled first. The most recently recycled ones are reused first
+            let freed = runtime.len().saturating_sub(keep);
+            for storage in runtime.drain(..freed) {
+                // SAFETY: Every location is leaked from a box when it is claimed, and the caller guarantees nothing
+                // points to the recycled locations anymore
+                drop(unsafe { Box::from_raw(storage as *const Self as *mut Self) });
+            }
+            runtime.shrink_to_fit();
+            for storage in runtime.iter() {
+                storage.borrow_info.shrink();
+            }
+        })
+    }
+}
+
+impl UnsyncStorage {
+    #[allow(unused)]
+    fn claim_with(
+        caller: &'static std::panic::Location<'static>,
+        leaked: bool,
+    ) -> GenerationalPointer<Self> {
+        UNSYNC_COUNTERS.with(|counters| counters.claim(leaked));
+        let pointer = UNSYNC_RUNTIME.with(|runtime| {
             if let Some(storage) = runtime.borrow_mut().pop() {
                 let location = GenerationalLocation {
                     generation: storage.data.borrow().generation(),
-                    #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+                    #[cfg(any(debug_assertions, feature = "debug_ownership"))]
                     created_at: caller,
                 };
                 GenerationalPointer { storage, location }
@@ -95,7 +153,7 @@ impl AnyStorage for UnsyncStorage {
                 let data: &'static Self = &*Box::leak(Box::default());
                 let location = GenerationalLocation {
                     generation: 0,
-                    #[cfg(any(debug_assertions, feature = "debug_borrows"))]
+                    #[cfg(any(debug_assertions, feature = "debug_ownership"))]
                     created_at: caller,
                 };
                 GenerationalPointer {
@@ -103,22 +161,11 @@ impl AnyStorage for UnsyncStorage {
                     location,
                 }
             }
-        })
-    }
-
-    fn recycle(pointer: GenerationalPointer<Self>) -> Option<Box<dyn std::any::Any>> {
-        let mut borrow_mut = pointer.storage.data.borrow_mut();
-
-        // First check if the generation is still valid
-        if !borrow_mut.valid(&pointer.location) {
-            return None;
+        });
+        if leaked {
+            pointer.storage.data.borrow_mut().leaked = true;
         }
-
-        borrow_mut.increment_generation();
-        let old_data = borrow_mut.data.take();
-        UNSYNC_RUNTIME.with(|runtime| runtime.borrow_mut().push(pointer.storage));
-
-        old_data
+        pointer
     }
 }
 
@@ -0,0 +1,77 @@
+use generational_box::{AnyStorage, GenerationalBox, UnsyncStorage};
+
+#[test]
+fn stats_track_live_recycled_and_leaked_boxes() {
+    // Unsync storage keeps a separate pool for each thread, so other tests don't affect these stats
+    let before = UnsyncStorage::stats();
+
+    let owner = UnsyncStorage::owner();
+    for i in 0..100 {
+        owner.insert(i);
+    }
+    let stats = UnsyncStorage::stats();
+    assert_eq!(stats.live, before.live + 100);
+    assert!(stats.peak >= stats.live);
+    assert!(stats.bytes > before.bytes);
+
+    drop(owner);
+    let stats = UnsyncStorage::stats();
+    assert_eq!(stats.live, before.live);
+    assert!(stats.recycled >= 100);
+    assert!(stats.peak >= before.live + 100);
+
+    // Leaked boxes are tracked until they are dropped manually
+    let leaked = GenerationalBox::<_, UnsyncStorage>::leak(String::from("leaked"));
+    assert_eq!(UnsyncStorage::stats().leaked, before.leaked + 1);
+    assert_eq!(leaked.manually_drop().as_deref(), Some("leaked"));
+    assert_eq!(UnsyncStorage::stats().leaked, before.leaked);
+
+    // Shrinking frees the recycled locations beyond the number we keep
+    let stats = UnsyncStorage::stats();
+    // SAFETY: Every box created in this test was dropped and is never used again
+    unsafe { UnsyncStorage::shrink_recycled(10) };
+    let shrunk = UnsyncStorage::stats();
+    assert_eq!(shrunk.recycled, 10);
+    assert_eq!(shrunk.live, stats.live);
+    assert!(shrunk.bytes < stats.bytes);
+
+    // New values still get a location after the pool was shrunk
+    let owner = UnsyncStorage::owner();
+    let values: Vec<_> = (0..20).map(|i| owner.insert(i)).collect();
+    assert!(values
+        .iter()
+        .enumerate()
+        .all(|(i, value)| *value.read() == i));
+    assert_eq!(UnsyncStorage::stats().recycled, 0);
+}
+
+#[cfg(feature = "debug_borrow_threads")]
+#[test]
+fn sync_storage_reports_borrows_held_by_the_current_thread() {
+    use generational_box::{BorrowError, BorrowMutError, SyncStorage};
+
+    let owner = SyncStorage::owner();
+    let value = owner.insert(1);
+
+    // Locking a value this thread already holds returns an error instead of deadlocking
+    let write = value.write();
+    let error = value.try_read().unwrap_err();
+    assert!(matches!(error, BorrowError::AlreadyBorrowedMut(_)));
+    assert!(error
+        .to_string()
+        .contains(&format!("{:?}", std::thread::current().id())));
+    drop(write);
+
+    let read = value.read();
+    assert!(matches!(
+        value.try_write().err(),
+        Some(BorrowMutError::AlreadyBorrowed(_))
+    ));
+
+    // Other threads wait for the lock instead
+    let handle = std::thread::spawn(move || *value.write() += 1);
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    drop(read);
+    handle.join().unwrap();
+    assert_eq!(*value.read(), 2);
+}
@@ -78,6 +78,12 @@ pub use use_reactive::*;
 mod use_resource;
 pub use use_resource::*;
 
+mod use_resource_with;
+pub use use_resource_with::*;
+
+mod use_query;
+pub use use_query::*;
+
 mod use_effect;
 pub use use_effect::*;
 
@@ -93,5 +99,19 @@ pub use use_hook_did_run::*;
 mod use_signal;
 pub use use_signal::*;
 
+mod use_stream_signal;
+pub use use_stream_signal::*;
+
+mod use_collection;
+pub use use_collection::*;
+
+mod use_store;
+pub use use_store::*;
+
+#[cfg(feature = "persistent")]
+mod use_persistent;
+#[cfg(feature = "persistent")]
+pub use use_persistent::*;
+
 mod use_set_compare;
 pub use use_set_compare::*;
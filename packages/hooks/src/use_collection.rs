@@ -1,76 +1,66 @@
-/*
-a form of use_signal explicitly for map-style collections (BTreeMap, HashMap, etc).
+use dioxus_core::prelude::*;
+use dioxus_signals::{SignalMap, SignalVec};
+use std::hash::Hash;
 
-Why?
----
-Traditionally, it's possible to use the "use_state" hook for collections in the React world.
-Adding a new entry would look something similar to:
-
-```js
-let (map, set_map) = useState({});
-set_map({ ...map, [key]: value });
-```
-The new value then causes the appropriate update when passed into children.
-
-This is moderately efficient because the fields of the map are moved, but the data itself is not cloned.
-However, if you used similar approach with Dioxus:
-
-```rust
-let (map, set_map) = use_signal(|| HashMap::new());
-set_map({
-    let mut newmap = map.clone();
-    newmap.set(key, value);
-    newmap
-})
-```
-Unfortunately, you'd be cloning the entire state every time a value is changed. The obvious solution is to
-wrap every element in the HashMap with an Rc. That way, cloning the HashMap is on par with its JS equivalent.
-
-Fortunately, we can make this operation even more efficient in Dioxus, leveraging the borrow rules of Rust.
-
-This hook provides a memoized collection, memoized setters, and memoized getters. This particular hook is
-extremely powerful for implementing lists and supporting core state management needs for small apps.
-
-If you need something even more powerful, check out the dedicated atomic state management Dioxus Dataflow, which
-uses the same memoization on top of the use_context API.
-
-Here's a fully-functional todo app using the use_map API:
-```rust
-static TodoList: Component = |cx| {
-    let todos = use_map(|| HashMap::new());
-    let input = use_signal(|| None);
-
-    rsx!{
-        div {
-            button {
-                "Add todo"
-                onclick: move |_| {
-                    let new_todo = TodoItem::new(input.contents());
-                    todos.insert(new_todo.id.clone(), new_todo);
-                    input.clear();
-                }
-            }
-            button {
-                "Clear todos"
-                onclick: move |_| todos.clear()
-            }
-            input {
-                placeholder: "What needs to be done?"
-                ref: input
-            }
-            ul {
-                {todos.iter().map(|todo| rsx!(
-                    li {
-                        key: todo.id
-                        span { "{todo.content}" }
-                        button {"x", onclick: move |_| todos.remove(todo.key.clone())}
-                    }
-                ))}
-            }
-        }
-    })
+/// Creates a new [`SignalVec`]: a list where every entry is its own signal.
+///
+/// Reading an index only subscribes to that entry and iterating only subscribes to the structure of the list, so
+/// editing one entry does not rerun every component that renders the list.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// fn App() -> Element {
+///     let mut todos = use_signal_vec(|| vec!["Buy milk".to_string(), "Walk the dog".to_string()]);
+///
+///     rsx! {
+///         button { onclick: move |_| todos.push("New todo".to_string()), "Add todo" }
+///         button { onclick: move |_| todos.clear(), "Clear todos" }
+///         ul {
+///             for todo in todos.iter() {
+///                 li { key: "{todo.id():?}", "{todo}" }
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[track_caller]
+#[must_use]
+pub fn use_signal_vec<T: 'static, I: IntoIterator<Item = T>>(
+    f: impl FnOnce() -> I,
+) -> SignalVec<T> {
+    use_hook(|| SignalVec::new(f()))
 }
 
-```
-
-*/
+/// Creates a new [`SignalMap`]: a map where every value is its own signal.
+///
+/// Reading a key only subscribes to that key and iterating only subscribes to the structure of the map, so editing
+/// one value does not rerun every component that renders the map.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// fn App() -> Element {
+///     let mut scores = use_signal_map(|| [("Alice".to_string(), 0), ("Bob".to_string(), 0)]);
+///     let alice = scores.get(&"Alice".to_string());
+///
+///     rsx! {
+///         if let Some(mut alice) = alice {
+///             button { onclick: move |_| alice += 1, "Alice: {alice}" }
+///         }
+///         button { onclick: move |_| { scores.remove(&"Alice".to_string()); }, "Remove Alice" }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[track_caller]
+#[must_use]
+pub fn use_signal_map<K, V, I>(f: impl FnOnce() -> I) -> SignalMap<K, V>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: 'static,
+    I: IntoIterator<Item = (K, V)>,
+{
+    use_hook(|| SignalMap::new(f()))
+}
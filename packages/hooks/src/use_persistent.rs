@@ -0,0 +1,34 @@
+use dioxus_core::prelude::*;
+use dioxus_signals::PersistentSignal;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Creates a new [`PersistentSignal`]: a signal that saves its value to the platform's storage and restores it the
+/// next time the app starts.
+///
+/// On the web, values are saved in `localStorage` and stay in sync between tabs. On desktop, values are saved in a
+/// JSON file in the app's data directory. When rendering on the server, the signal starts with the value from `init`
+/// and the saved value is loaded after the client hydrates, so the html always matches.
+///
+/// ```rust, no_run
+/// use dioxus::prelude::*;
+///
+/// fn App() -> Element {
+///     let mut name = use_persistent("name", String::new);
+///
+///     rsx! {
+///         input {
+///             value: "{name}",
+///             oninput: move |event| name.set(event.value()),
+///         }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[track_caller]
+#[must_use]
+pub fn use_persistent<T: Serialize + DeserializeOwned + 'static>(
+    key: impl Into<String>,
+    init: impl FnOnce() -> T,
+) -> PersistentSignal<T> {
+    use_hook(|| PersistentSignal::new(key, init))
+}
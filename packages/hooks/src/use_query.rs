@@ -0,0 +1,950 @@
+use crate::{use_callback, use_root_context, use_signal, UseCallback};
+use dioxus_core::prelude::*;
+use dioxus_signals::*;
+use futures_timer::Delay;
+use futures_util::FutureExt;
+use instant::Instant;
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+    time::Duration,
+};
+
+/// A key that identifies a query in the [`QueryClient`] cache.
+///
+/// A key is a list of segments. Each segment is stored as its [`Display`] representation, so any value that can be
+/// formatted can be part of a key. Keys can be created from strings, arrays, and tuples:
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// let all_todos = QueryKey::from("todos");
+/// let todo = QueryKey::from(("todos", 5));
+/// assert!(todo.starts_with(&all_todos));
+/// assert_eq!(todo, QueryKey::new().segment("todos").segment(5));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct QueryKey(Vec<String>);
+
+impl QueryKey {
+    /// Create an empty key. The empty key is a prefix of every key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a segment to the end of the key.
+    pub fn segment(mut self, segment: impl Display) -> Self {
+        self.0.push(segment.to_string());
+        self
+    }
+
+    /// Get the segments of the key.
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Check if the key starts with every segment of `prefix`.
+    pub fn starts_with(&self, prefix: &QueryKey) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+impl Display for QueryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", self.0.join(", "))
+    }
+}
+
+impl From<&str> for QueryKey {
+    fn from(segment: &str) -> Self {
+        Self(vec![segment.to_string()])
+    }
+}
+
+impl From<String> for QueryKey {
+    fn from(segment: String) -> Self {
+        Self(vec![segment])
+    }
+}
+
+impl From<&QueryKey> for QueryKey {
+    fn from(key: &QueryKey) -> Self {
+        key.clone()
+    }
+}
+
+impl<T: Display, const N: usize> From<[T; N]> for QueryKey {
+    fn from(segments: [T; N]) -> Self {
+        Self(segments.iter().map(ToString::to_string).collect())
+    }
+}
+
+impl<T: Display> From<&[T]> for QueryKey {
+    fn from(segments: &[T]) -> Self {
+        Self(segments.iter().map(ToString::to_string).collect())
+    }
+}
+
+impl<T: Display> From<Vec<T>> for QueryKey {
+    fn from(segments: Vec<T>) -> Self {
+        Self(segments.iter().map(ToString::to_string).collect())
+    }
+}
+
+macro_rules! impl_query_key_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Display),+> From<($($name,)+)> for QueryKey {
+            #[allow(non_snake_case)]
+            fn from(($($name,)+): ($($name,)+)) -> Self {
+                Self(vec![$($name.to_string()),+])
+            }
+        }
+    };
+}
+
+impl_query_key_for_tuple!(A);
+impl_query_key_for_tuple!(A, B);
+impl_query_key_for_tuple!(A, B, C);
+impl_query_key_for_tuple!(A, B, C, D);
+impl_query_key_for_tuple!(A, B, C, D, E);
+
+/// Options for [`use_query_with`].
+///
+/// When several components use the same query with different options, the options are combined so every component
+/// gets at least what it asked for: the shortest stale time and the longest cache time are used, and stale data is
+/// refetched on focus or reconnect if any component asks for it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct QueryOptions {
+    stale_time: Duration,
+    cache_time: Duration,
+    refetch_on_focus: bool,
+    refetch_on_reconnect: bool,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            cache_time: Duration::from_secs(5 * 60),
+            refetch_on_focus: true,
+            refetch_on_reconnect: true,
+        }
+    }
+}
+
+impl QueryOptions {
+    /// Create options that treat data as stale as soon as it is fetched, refetch stale data when the window is
+    /// focused or the network reconnects, and keep unused data for five minutes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how long data stays fresh after it is fetched. Stale data is refetched the next time a component that
+    /// uses the query is created, the window is focused, or the network reconnects. Defaults to zero.
+    pub fn stale_time(mut self, stale_time: Duration) -> Self {
+        self.stale_time = stale_time;
+        self
+    }
+
+    /// Set how long data stays in the cache once no component uses the query. If a component uses the query again
+    /// before then, the cached data is shown right away. Defaults to five minutes.
+    pub fn cache_time(mut self, cache_time: Duration) -> Self {
+        self.cache_time = cache_time;
+        self
+    }
+
+    /// Refetch stale data when the window is focused. Defaults to true.
+    pub fn refetch_on_focus(mut self, refetch: bool) -> Self {
+        self.refetch_on_focus = refetch;
+        self
+    }
+
+    /// Refetch stale data when the network reconnects. Defaults to true.
+    pub fn refetch_on_reconnect(mut self, refetch: bool) -> Self {
+        self.refetch_on_reconnect = refetch;
+        self
+    }
+
+    /// Combine the options of two components that use the same query
+    fn merge(self, other: Self) -> Self {
+        Self {
+            stale_time: self.stale_time.min(other.stale_time),
+            cache_time: self.cache_time.max(other.cache_time),
+            refetch_on_focus: self.refetch_on_focus || other.refetch_on_focus,
+            refetch_on_reconnect: self.refetch_on_reconnect || other.refetch_on_reconnect,
+        }
+    }
+}
+
+type Fetcher = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = Rc<dyn Any>>>>>;
+
+/// A cache of queries shared by every component in the app. Get the client with [`use_query_client`].
+///
+/// The client can read and write the cache directly, which is useful to update queries after a mutation:
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn add_todo(todo: String) -> Result<(), ()> { Ok(()) }
+/// fn AddTodo() -> Element {
+///     let client = use_query_client();
+///     let add = use_mutation(move |todo: String| async move {
+///         // Show the new todo right away
+///         let optimistic = client.optimistic_update("todos", |todos: &mut Vec<String>| {
+///             todos.push(todo.clone());
+///         });
+///         match add_todo(todo).await {
+///             // Then fetch the todos from the server again
+///             Ok(_) => client.invalidate("todos"),
+///             // Or undo the change if adding the todo failed
+///             Err(_) => optimistic.rollback(),
+///         }
+///     });
+///
+///     rsx! {
+///         button { onclick: move |_| add.mutate("New todo".to_string()), "Add todo" }
+///     }
+/// }
+/// ```
+pub struct QueryClient {
+    cache: CopyValue<HashMap<QueryKey, Entry>>,
+    next_observer: CopyValue<usize>,
+}
+
+struct Entry {
+    state: Signal<EntryState>,
+    // The type of the data, so a key that is used with different types fails loudly instead of returning no data
+    data_type: (TypeId, &'static str),
+    fetcher: Option<Fetcher>,
+    task: Option<Task>,
+    // The options of each component that currently uses the query
+    observers: HashMap<usize, QueryOptions>,
+    // The options of the observers combined. This keeps the last options once every observer is gone
+    options: QueryOptions,
+    // Removes the entry once its cache time passes without any observers
+    eviction: Option<Task>,
+    // The data was sent from the server, and should not be refetched when the first component uses the query
+    hydrated: bool,
+}
+
+impl Entry {
+    fn check_type<T: 'static>(&self, key: &QueryKey) {
+        let (type_id, name) = self.data_type;
+        if type_id != TypeId::of::<T>() {
+            panic!(
+                "The query {key} holds {name}, but it was used as {}. Every use of a query key must use the same type",
+                type_name::<T>()
+            );
+        }
+    }
+
+    fn is_observed(&self) -> bool {
+        !self.observers.is_empty()
+    }
+
+    fn update_options(&mut self) {
+        if let Some(options) = self.observers.values().copied().reduce(QueryOptions::merge) {
+            self.options = options;
+        }
+    }
+
+    /// Cancel any running work and drop the state of the entry
+    fn drop_state(mut self) {
+        for task in [self.task.take(), self.eviction.take()]
+            .into_iter()
+            .flatten()
+        {
+            task.cancel();
+        }
+        self.state.manually_drop();
+    }
+}
+
+#[derive(Clone, Default)]
+struct EntryState {
+    data: Option<Rc<dyn Any>>,
+    updated_at: Option<Instant>,
+    fetching: bool,
+    invalidated: bool,
+}
+
+impl EntryState {
+    fn is_stale(&self, options: &QueryOptions) -> bool {
+        match self.updated_at {
+            Some(updated_at) => self.invalidated || updated_at.elapsed() >= options.stale_time,
+            None => true,
+        }
+    }
+}
+
+impl QueryClient {
+    /// Create a new, empty query client that lives as long as the app. Most apps should use [`use_query_client`]
+    /// instead, which shares one client across the whole app.
+    pub fn new() -> Self {
+        Self {
+            cache: CopyValue::new_in_scope(HashMap::new(), ScopeId::ROOT),
+            next_observer: CopyValue::new_in_scope(0, ScopeId::ROOT),
+        }
+    }
+
+    /// Get the cached data for a query without subscribing to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query holds data of a different type.
+    pub fn get_query_data<T: Clone + 'static>(&self, key: impl Into<QueryKey>) -> Option<T> {
+        let state = self.state::<T>(&key.into())?;
+        let state = state.peek();
+        state.data.as_ref()?.downcast_ref::<T>().cloned()
+    }
+
+    /// Replace the cached data for a query. Any fetch that is running for the query is cancelled so it doesn't
+    /// overwrite the new data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query holds data of a different type.
+    pub fn set_query_data<T: 'static>(&self, key: impl Into<QueryKey>, data: T) {
+        let key = key.into();
+        self.entry::<T>(&key);
+        self.set_data(key, Some(Rc::new(data)));
+    }
+
+    /// Update the cached data for a query right away, before the change is confirmed by the server. The returned
+    /// [`OptimisticUpdate`] can roll back the change if it fails. If the query has no data, nothing is changed.
+    pub fn optimistic_update<T: Clone + 'static>(
+        &self,
+        key: impl Into<QueryKey>,
+        update: impl FnOnce(&mut T),
+    ) -> OptimisticUpdate {
+        let key = key.into();
+        let previous = self
+            .state::<T>(&key)
+            .and_then(|state| state.peek().data.clone());
+        if let Some(mut data) = previous
+            .as_ref()
+            .and_then(|data| data.downcast_ref::<T>())
+            .cloned()
+        {
+            update(&mut data);
+            self.set_data(key.clone(), Some(Rc::new(data)));
+        }
+        OptimisticUpdate {
+            client: *self,
+            key,
+            previous,
+        }
+    }
+
+    /// Mark every query that starts with `prefix` as stale. Queries that are used by a component are refetched
+    /// right away, and the rest are refetched the next time they are used.
+    ///
+    /// A fetch that is already running when the query is invalidated may have read the old data, so it is cancelled
+    /// and started again.
+    pub fn invalidate(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let matching: Vec<_> = self
+            .cache
+            .write_unchecked()
+            .iter_mut()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, entry)| {
+                if let Some(task) = entry.task.take() {
+                    task.cancel();
+                }
+                (key.clone(), entry.state, entry.is_observed())
+            })
+            .collect();
+        for (key, state, observed) in matching {
+            write_state(state, |state| {
+                state.invalidated = true;
+                state.fetching = false;
+            });
+            if observed {
+                self.fetch(&key);
+            }
+        }
+    }
+
+    /// Refetch a query, even if the data is fresh. If the query is already fetching, the running fetch is reused.
+    pub fn refetch(&self, key: impl Into<QueryKey>) {
+        self.fetch(&key.into());
+    }
+
+    /// Remove every query that starts with `prefix` and isn't used by any component from the cache.
+    pub fn remove(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let removed: Vec<_> = self
+            .cache
+            .peek()
+            .iter()
+            .filter(|(key, entry)| key.starts_with(&prefix) && !entry.is_observed())
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in removed {
+            self.evict(&key);
+        }
+    }
+
+    /// Refetch the stale queries that are used by a component and refetch when the window is focused. Renderers call
+    /// this when the window regains focus.
+    pub fn window_focused(&self) {
+        self.refetch_stale(|options| options.refetch_on_focus);
+    }
+
+    /// Refetch the stale queries that are used by a component and refetch when the network reconnects. Renderers
+    /// call this when the network comes back online.
+    pub fn reconnected(&self) {
+        self.refetch_stale(|options| options.refetch_on_reconnect);
+    }
+
+    /// Seed the cache with data that was fetched on the server. The data isn't refetched when the first component
+    /// uses the query.
+    pub fn hydrate<T: 'static>(&self, key: impl Into<QueryKey>, data: T) {
+        let key = key.into();
+        // Every component that uses the query hydrates it. Only the first one needs to set the data
+        let state = self.entry::<T>(&key);
+        if state.peek().updated_at.is_none() {
+            self.set_data(key.clone(), Some(Rc::new(data)));
+        }
+        if let Some(entry) = self.cache.write_unchecked().get_mut(&key) {
+            entry.hydrated = true;
+        }
+    }
+
+    fn refetch_stale(&self, filter: impl Fn(&QueryOptions) -> bool) {
+        let stale: Vec<_> = self
+            .cache
+            .peek()
+            .iter()
+            .filter(|(_, entry)| {
+                entry.is_observed()
+                    && filter(&entry.options)
+                    && entry.state.peek().is_stale(&entry.options)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            self.fetch(&key);
+        }
+    }
+
+    fn state<T: 'static>(&self, key: &QueryKey) -> Option<Signal<EntryState>> {
+        let cache = self.cache.peek();
+        let entry = cache.get(key)?;
+        entry.check_type::<T>(key);
+        Some(entry.state)
+    }
+
+    /// Get the state of an entry, creating the entry if it doesn't exist. New entries are evicted after the default
+    /// cache time unless a component starts using them
+    fn entry<T: 'static>(&self, key: &QueryKey) -> Signal<EntryState> {
+        if let Some(state) = self.state::<T>(key) {
+            return state;
+        }
+        let state = Signal::new_in_scope(EntryState::default(), ScopeId::ROOT);
+        let options = QueryOptions::default();
+        self.cache.write_unchecked().insert(
+            key.clone(),
+            Entry {
+                state,
+                data_type: (TypeId::of::<T>(), type_name::<T>()),
+                fetcher: None,
+                task: None,
+                observers: HashMap::new(),
+                options,
+                eviction: self.schedule_eviction(key, options.cache_time),
+                hydrated: false,
+            },
+        );
+        state
+    }
+
+    /// Remove an entry once the cache time passes, unless a component starts using it before then
+    fn schedule_eviction(&self, key: &QueryKey, cache_time: Duration) -> Option<Task> {
+        let client = *self;
+        let key = key.clone();
+        spawn_forever(async move {
+            Delay::new(cache_time).await;
+            let observed = client.cache.peek().get(&key).map(Entry::is_observed);
+            if observed == Some(false) {
+                if let Some(entry) = client.cache.write_unchecked().get_mut(&key) {
+                    // This task is the eviction, so it finishes on its own
+                    entry.eviction = None;
+                }
+                client.evict(&key);
+            }
+        })
+    }
+
+    fn evict(&self, key: &QueryKey) {
+        let removed = self.cache.write_unchecked().remove(key);
+        if let Some(entry) = removed {
+            entry.drop_state();
+        }
+    }
+
+    fn set_data(&self, key: QueryKey, data: Option<Rc<dyn Any>>) {
+        let Some(state) = self.cache.peek().get(&key).map(|entry| entry.state) else {
+            return;
+        };
+        if let Some(task) = self
+            .cache
+            .write_unchecked()
+            .get_mut(&key)
+            .and_then(|entry| entry.task.take())
+        {
+            task.cancel();
+        }
+        write_state(state, |state| {
+            *state = EntryState {
+                data,
+                updated_at: Some(Instant::now()),
+                fetching: false,
+                invalidated: false,
+            }
+        });
+    }
+
+    /// Get a new id for a component that uses queries
+    fn next_observer(&self) -> usize {
+        let mut next = self.next_observer.write_unchecked();
+        *next += 1;
+        *next
+    }
+
+    /// Start using a query in a component. Fetches the query if the data is stale
+    fn observe<T: 'static>(
+        &self,
+        key: &QueryKey,
+        observer: usize,
+        options: QueryOptions,
+        fetcher: Fetcher,
+    ) {
+        let state = self.entry::<T>(key);
+        let (hydrated, options) = {
+            let mut cache = self.cache.write_unchecked();
+            let entry = cache.get_mut(key).unwrap();
+            if let Some(eviction) = entry.eviction.take() {
+                eviction.cancel();
+            }
+            entry.observers.insert(observer, options);
+            entry.update_options();
+            entry.fetcher = Some(fetcher);
+            (std::mem::take(&mut entry.hydrated), entry.options)
+        };
+        if !hydrated && state.peek().is_stale(&options) {
+            self.fetch(key);
+        }
+    }
+
+    /// Update the options and fetcher of a component that uses a query
+    fn update(&self, key: &QueryKey, observer: usize, options: QueryOptions, fetcher: Fetcher) {
+        if let Some(entry) = self.cache.write_unchecked().get_mut(key) {
+            if entry.observers.get(&observer) != Some(&options) {
+                entry.observers.insert(observer, options);
+                entry.update_options();
+            }
+            entry.fetcher = Some(fetcher);
+        }
+    }
+
+    /// Stop using a query in a component. Once no component uses the query, it is evicted after its cache time
+    fn unobserve(&self, key: &QueryKey, observer: usize) {
+        let Ok(mut cache) = self.cache.try_write_unchecked() else {
+            return;
+        };
+        let Some(entry) = cache.get_mut(key) else {
+            return;
+        };
+        entry.observers.remove(&observer);
+        if entry.is_observed() {
+            entry.update_options();
+        } else if entry.eviction.is_none() {
+            let cache_time = entry.options.cache_time;
+            drop(cache);
+            let eviction = self.schedule_eviction(key, cache_time);
+            if let Some(entry) = self.cache.write_unchecked().get_mut(key) {
+                entry.eviction = eviction;
+            }
+        }
+    }
+
+    /// Fetch a query unless it is already fetching
+    fn fetch(&self, key: &QueryKey) {
+        let (mut state, fetcher) = {
+            let cache = self.cache.peek();
+            let Some(entry) = cache.get(key) else {
+                return;
+            };
+            if entry.task.is_some() {
+                return;
+            }
+            let Some(fetcher) = entry.fetcher.clone() else {
+                return;
+            };
+            (entry.state, fetcher)
+        };
+
+        let future = fetcher();
+        let client = *self;
+        let finished_key = key.clone();
+        let task = spawn_forever(async move {
+            let data = future.await;
+            if let Some(entry) = client.cache.write_unchecked().get_mut(&finished_key) {
+                entry.task = None;
+            }
+            state.set(EntryState {
+                data: Some(data),
+                updated_at: Some(Instant::now()),
+                fetching: false,
+                invalidated: false,
+            });
+        });
+        if let Some(entry) = self.cache.write_unchecked().get_mut(key) {
+            entry.task = task;
+        }
+        if !state.peek().fetching {
+            write_state(state, |state| state.fetching = true);
+        }
+    }
+}
+
+/// Write to the state of a query. Queries are fetched and hydrated when a component that uses them renders, so this
+/// may happen during a render.
+fn write_state(mut state: Signal<EntryState>, f: impl FnOnce(&mut EntryState)) {
+    use ::warnings::Warning;
+    dioxus_signals::warnings::signal_read_and_write_in_reactive_scope::allow(|| {
+        dioxus_signals::warnings::signal_write_in_component_body::allow(|| f(&mut state.write()))
+    });
+}
+
+impl Default for QueryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for QueryClient {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for QueryClient {}
+
+impl PartialEq for QueryClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.cache == other.cache
+    }
+}
+
+/// An update made with [`QueryClient::optimistic_update`] that can be rolled back.
+pub struct OptimisticUpdate {
+    client: QueryClient,
+    key: QueryKey,
+    previous: Option<Rc<dyn Any>>,
+}
+
+impl OptimisticUpdate {
+    /// Restore the data the query had before the update.
+    pub fn rollback(self) {
+        self.client.set_data(self.key, self.previous);
+    }
+}
+
+/// Get the [`QueryClient`] that is shared by every component in the app. If no client was provided with
+/// [`provide_root_context`], a new client is created at the root of the app.
+pub fn use_query_client() -> QueryClient {
+    use_root_context(QueryClient::new)
+}
+
+/// Fetch data and cache it under a key that is shared by every component in the app.
+///
+/// - Components that use the same key share the same data, and only one fetch runs at a time for each key.
+/// - Cached data is shown right away when a component is created. If the data is stale, it is refetched in the
+///   background.
+/// - When the key changes, the query switches to the data for the new key.
+/// - [`QueryClient::invalidate`] refetches every query that starts with a key prefix.
+/// - Once no component uses a query, its data is kept for the [cache time](QueryOptions::cache_time) and then removed.
+///
+/// Every use of a key must have the same data type. Using a key with a different type panics.
+///
+/// Unlike [`use_resource`](crate::use_resource), the fetcher is not reactive. Build the key from any signals the fetch
+/// depends on instead, so the query switches to a new key when they change.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn fetch_user(id: u32) -> String { todo!() }
+/// #[component]
+/// fn User(id: u32) -> Element {
+///     let user = use_query(("user", id), move || fetch_user(id));
+///
+///     match user.data() {
+///         Some(name) => rsx! { "{name}" },
+///         None => rsx! { "Loading..." },
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_query<T, F>(key: impl Into<QueryKey>, fetcher: impl Fn() -> F + 'static) -> Query<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    use_query_with(QueryOptions::default(), key, fetcher)
+}
+
+/// Like [`use_query`], but with control over when the data becomes stale and is refetched.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use std::time::Duration;
+/// # async fn fetch_todos() -> Vec<String> { todo!() }
+/// fn Todos() -> Element {
+///     let todos = use_query_with(
+///         QueryOptions::new().stale_time(Duration::from_secs(60)),
+///         "todos",
+///         fetch_todos,
+///     );
+///
+///     rsx! {
+///         for todo in todos.data().unwrap_or_default() {
+///             p { "{todo}" }
+///         }
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_query_with<T, F>(
+    options: QueryOptions,
+    key: impl Into<QueryKey>,
+    fetcher: impl Fn() -> F + 'static,
+) -> Query<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let client = use_query_client();
+    let key = key.into();
+    let fetcher: Fetcher = Rc::new(move || {
+        fetcher()
+            .map(|data| Rc::new(data) as Rc<dyn Any>)
+            .boxed_local()
+    });
+
+    let observer = use_hook(|| client.next_observer());
+    let observed = use_hook(|| Rc::new(RefCell::new(None::<QueryKey>)));
+    let mut current = use_hook(|| CopyValue::new(key.clone()));
+    let changed = observed.borrow().as_ref() != Some(&key);
+    if changed {
+        if let Some(old) = observed.borrow_mut().replace(key.clone()) {
+            client.unobserve(&old, observer);
+        }
+        current.set(key.clone());
+        client.observe::<T>(&key, observer, options, fetcher);
+    } else {
+        client.update(&key, observer, options, fetcher);
+    }
+
+    use_drop(move || {
+        if let Some(key) = observed.borrow_mut().take() {
+            client.unobserve(&key, observer);
+        }
+    });
+
+    Query {
+        client,
+        key: current,
+        phantom: PhantomData,
+    }
+}
+
+/// A handle to a query created with [`use_query`].
+pub struct Query<T: 'static> {
+    client: QueryClient,
+    key: CopyValue<QueryKey>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Query<T> {
+    /// Get the key of the query.
+    pub fn key(&self) -> QueryKey {
+        self.key.cloned()
+    }
+
+    /// Get the cached data for the query. Returns None if the data hasn't been fetched yet.
+    ///
+    /// Reading this subscribes to the query.
+    #[track_caller]
+    pub fn data(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let state = self.state()?;
+        let state = state.read();
+        state.data.as_ref()?.downcast_ref::<T>().cloned()
+    }
+
+    /// Check if the query is fetching. This is true while a fetch runs, even if there is cached data.
+    ///
+    /// Reading this subscribes to the query.
+    #[track_caller]
+    pub fn is_fetching(&self) -> bool {
+        self.state().is_some_and(|state| state.read().fetching)
+    }
+
+    /// Check if the data is stale. Data becomes stale once its stale time has passed or it is invalidated.
+    ///
+    /// Reading this subscribes to the query, but the query doesn't update when the stale time passes.
+    #[track_caller]
+    pub fn is_stale(&self) -> bool {
+        let key = self.key.read();
+        let cache = self.client.cache.peek();
+        let Some(entry) = cache.get(&*key) else {
+            return true;
+        };
+        let stale = entry.state.read().is_stale(&entry.options);
+        stale
+    }
+
+    /// Refetch the query, even if the data is fresh.
+    pub fn refetch(&self) {
+        self.client.fetch(&self.key.peek());
+    }
+
+    /// Suspend the component until the query has data, and return the data.
+    pub fn suspend(&self) -> Result<T, RenderError>
+    where
+        T: Clone,
+    {
+        if let Some(data) = self.data() {
+            return Ok(data);
+        }
+        let task = self
+            .client
+            .cache
+            .peek()
+            .get(&*self.key.peek())
+            .and_then(|entry| entry.task);
+        match task {
+            Some(task) => Err(RenderError::Suspended(SuspendedFuture::new(task))),
+            None => Err(RenderError::Aborted(
+                dioxus_core::CapturedError::from_display(format!(
+                    "The query {} has no data and is not fetching",
+                    self.key.peek()
+                )),
+            )),
+        }
+    }
+
+    fn state(&self) -> Option<Signal<EntryState>> {
+        self.client.state::<T>(&self.key.read())
+    }
+}
+
+impl<T> Clone for Query<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Query<T> {}
+
+impl<T> PartialEq for Query<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.client == other.client && self.key == other.key
+    }
+}
+
+/// Run an async function that changes data on the server, like adding or deleting an item. Use the
+/// [`QueryClient`] inside the function to update or invalidate the queries that changed.
+///
+/// The mutation is cancelled if the component that created it is dropped.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn delete_todo(id: u32) {}
+/// #[component]
+/// fn DeleteTodo(id: u32) -> Element {
+///     let client = use_query_client();
+///     let delete = use_mutation(move |id: u32| async move {
+///         delete_todo(id).await;
+///         client.invalidate("todos");
+///     });
+///
+///     rsx! {
+///         button { disabled: delete.is_pending(), onclick: move |_| delete.mutate(id), "Delete" }
+///     }
+/// }
+/// ```
+pub fn use_mutation<A, T, F>(mut mutate: impl FnMut(A) -> F + 'static) -> UseMutation<A, T>
+where
+    A: 'static,
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let mut pending = use_signal(|| 0);
+    let mut value = use_signal(|| None);
+    let callback = use_callback(move |arg: A| {
+        let future = mutate(arg);
+        pending += 1;
+        spawn(async move {
+            let output = future.await;
+            value.set(Some(output));
+            pending -= 1;
+        });
+    });
+
+    UseMutation {
+        callback,
+        pending,
+        value,
+    }
+}
+
+/// A handle to a mutation created with [`use_mutation`].
+pub struct UseMutation<A: 'static, T: 'static> {
+    callback: UseCallback<A, ()>,
+    pending: Signal<usize>,
+    value: Signal<Option<T>>,
+}
+
+impl<A, T> UseMutation<A, T> {
+    /// Start the mutation with an argument.
+    pub fn mutate(&self, arg: A) {
+        self.callback.call(arg);
+    }
+
+    /// Check if any call to [`UseMutation::mutate`] is still running. This subscribes to the mutation.
+    #[track_caller]
+    pub fn is_pending(&self) -> bool {
+        *self.pending.read() > 0
+    }
+
+    /// Get the output of the last mutation that finished. This method returns a [`ReadOnlySignal`] which can be read
+    /// to get the output or passed to other hooks and components.
+    pub fn value(&self) -> ReadOnlySignal<Option<T>> {
+        self.value.into()
+    }
+}
+
+impl<A, T> Clone for UseMutation<A, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, T> Copy for UseMutation<A, T> {}
+
+impl<A, T> PartialEq for UseMutation<A, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.callback == other.callback
+            && self.pending == other.pending
+            && self.value == other.value
+    }
+}
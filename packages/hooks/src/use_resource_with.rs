@@ -0,0 +1,352 @@
+use crate::{use_callback, use_signal, UseCallback};
+use dioxus_core::prelude::*;
+use dioxus_signals::*;
+use futures_timer::Delay;
+use futures_util::{
+    future::{self, Either},
+    pin_mut, FutureExt, StreamExt,
+};
+use instant::Instant;
+use std::{cell::Cell, future::Future, ops::Deref, rc::Rc, time::Duration};
+
+/// What an [`AsyncMemo`] does with a future that is still running when its dependencies change.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum RerunPolicy {
+    /// Cancel the running future and start a new one.
+    #[default]
+    Cancel,
+
+    /// Let the running future finish while the new one starts. Results from older runs are ignored once a newer run
+    /// has finished, so the value never goes back in time.
+    Race,
+}
+
+/// Options for [`use_resource_with`].
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// # use std::time::Duration;
+/// let options = ResourceOptions::new()
+///     .debounce(Duration::from_millis(300))
+///     .keep_previous(false)
+///     .rerun_policy(RerunPolicy::Race);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ResourceOptions {
+    debounce: Option<Duration>,
+    throttle: Option<Duration>,
+    keep_previous: bool,
+    rerun_policy: RerunPolicy,
+}
+
+impl Default for ResourceOptions {
+    fn default() -> Self {
+        Self {
+            debounce: None,
+            throttle: None,
+            keep_previous: true,
+            rerun_policy: RerunPolicy::Cancel,
+        }
+    }
+}
+
+impl ResourceOptions {
+    /// Create options that rerun the future as soon as the dependencies change, keep the previous value while the
+    /// future reruns, and cancel the running future when the dependencies change.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until the dependencies stop changing for `duration` before the future reruns.
+    pub fn debounce(mut self, duration: Duration) -> Self {
+        self.debounce = Some(duration);
+        self
+    }
+
+    /// Start the future at most once every `duration`. Changes to the dependencies while waiting are handled by one
+    /// rerun at the end of the wait.
+    pub fn throttle(mut self, duration: Duration) -> Self {
+        self.throttle = Some(duration);
+        self
+    }
+
+    /// Keep the previous value while the future reruns. If this is false, the value is cleared every time the future
+    /// reruns. Defaults to true.
+    pub fn keep_previous(mut self, keep_previous: bool) -> Self {
+        self.keep_previous = keep_previous;
+        self
+    }
+
+    /// Set what happens to a future that is still running when the dependencies change. Defaults to
+    /// [`RerunPolicy::Cancel`].
+    pub fn rerun_policy(mut self, policy: RerunPolicy) -> Self {
+        self.rerun_policy = policy;
+        self
+    }
+}
+
+/// Like [`use_resource`](crate::use_resource), but with control over when the future reruns and what happens to the
+/// value while it does.
+///
+/// The future reruns when any signal it reads changes. [`ResourceOptions`] controls:
+/// - Debouncing or throttling reruns
+/// - Keeping the previous value while the future reruns
+/// - Cancelling or racing the running future when the dependencies change
+///
+/// [`AsyncMemo::is_stale`] is true from the moment the dependencies change until the value is up to date again.
+///
+/// The options are read when the hook is created. Changing the options in later renders has no effect.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use std::time::Duration;
+/// # async fn search(query: &str) -> Vec<String> { vec![query.to_string()] }
+/// fn Search() -> Element {
+///     let mut query = use_signal(String::new);
+///     let results = use_resource_with(
+///         ResourceOptions::new().debounce(Duration::from_millis(300)),
+///         move || async move { search(&query.read()).await },
+///     );
+///
+///     rsx! {
+///         input { value: "{query}", oninput: move |event| query.set(event.value()) }
+///         div {
+///             // Dim the previous results while the new ones load
+///             opacity: if results.is_stale() { "0.5" } else { "1" },
+///             for result in results.cloned().unwrap_or_default() {
+///                 p { "{result}" }
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[must_use = "Consider using `spawn` to run a future without reading its value"]
+#[track_caller]
+pub fn use_resource_with<T, F>(
+    options: ResourceOptions,
+    mut future: impl FnMut() -> F + 'static,
+) -> AsyncMemo<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let location = std::panic::Location::caller();
+
+    let mut value = use_signal(|| None);
+    let mut stale = use_signal(|| true);
+    let options = use_hook(|| options);
+    let runs = use_hook(|| CopyValue::new(Runs::default()));
+    let (rc, changed) = use_hook(|| {
+        let (rc, changed) = ReactiveContext::new_with_origin(location);
+        (rc, Rc::new(Cell::new(Some(changed))))
+    });
+
+    let run = use_callback(move |_| {
+        let generation = {
+            let mut runs = runs.write_unchecked();
+            runs.generation += 1;
+            runs.last_start = Some(Instant::now());
+            if options.rerun_policy == RerunPolicy::Cancel {
+                for (_, task) in runs.tasks.drain(..) {
+                    task.cancel();
+                }
+            }
+            runs.generation
+        };
+        if !options.keep_previous && value.peek().is_some() {
+            value.set(None);
+        }
+        if !*stale.peek() {
+            stale.set(true);
+        }
+
+        // Create the user's task
+        let fut = rc.reset_and_run_in(&mut future);
+
+        // Spawn a wrapper task that polls the inner future and watch its dependencies
+        let task = spawn(async move {
+            let fut = fut;
+            pin_mut!(fut);
+
+            // Run each poll in the context of the reactive scope
+            let res = future::poll_fn(|cx| {
+                rc.run_in(|| {
+                    tracing::trace_span!("polling async memo", location = %location)
+                        .in_scope(|| fut.poll_unpin(cx))
+                })
+            })
+            .await;
+
+            let mut runs = runs.write_unchecked();
+            runs.tasks.retain(|(run, _)| *run != generation);
+            // A newer run already finished
+            if generation <= runs.finished {
+                return;
+            }
+            runs.finished = generation;
+            let latest = runs.generation == generation;
+            drop(runs);
+
+            value.set(Some(res));
+            if latest {
+                stale.set(false);
+            }
+        });
+        runs.write_unchecked().tasks.push((generation, task));
+    });
+
+    use_hook(|| run(()));
+
+    use_hook(|| {
+        let mut changed = changed.take().unwrap();
+        spawn(async move {
+            // Wait for the dependencies to change
+            while changed.next().await.is_some() {
+                if !*stale.peek() {
+                    stale.set(true);
+                }
+
+                // Wait until the dependencies stop changing
+                if let Some(debounce) = options.debounce {
+                    loop {
+                        match future::select(changed.next(), Delay::new(debounce)).await {
+                            Either::Left((Some(_), _)) => continue,
+                            Either::Left((None, _)) => return,
+                            Either::Right(_) => break,
+                        }
+                    }
+                }
+
+                // Wait until enough time has passed since the last run
+                if let Some(throttle) = options.throttle {
+                    let last_start = runs.peek().last_start;
+                    if let Some(wait) =
+                        last_start.and_then(|last| throttle.checked_sub(last.elapsed()))
+                    {
+                        Delay::new(wait).await;
+                    }
+                    // Any changes while waiting are handled by this run
+                    while let Ok(Some(_)) = changed.try_next() {}
+                }
+
+                run(());
+            }
+        })
+    });
+
+    AsyncMemo {
+        value,
+        stale,
+        runs,
+        run,
+    }
+}
+
+/// The runs of the future behind an [`AsyncMemo`]
+#[derive(Default)]
+struct Runs {
+    // The last run that was started
+    generation: u64,
+    // The last run that finished
+    finished: u64,
+    last_start: Option<Instant>,
+    tasks: Vec<(u64, Task)>,
+}
+
+/// A handle to a reactive future spawned with [`use_resource_with`] that can be used to read the latest value of the
+/// future.
+pub struct AsyncMemo<T: 'static> {
+    value: Signal<Option<T>>,
+    stale: Signal<bool>,
+    runs: CopyValue<Runs>,
+    run: UseCallback<(), ()>,
+}
+
+impl<T> AsyncMemo<T> {
+    /// Check if the value is out of date. This is true from the moment the dependencies change, including while
+    /// waiting for the debounce or throttle, until a run with the latest dependencies finishes. It is also true before
+    /// the first run finishes.
+    ///
+    /// Reading this subscribes to the staleness of the memo.
+    #[track_caller]
+    pub fn is_stale(&self) -> bool {
+        *self.stale.read()
+    }
+
+    /// Rerun the future immediately, skipping any debounce or throttle.
+    pub fn restart(&mut self) {
+        self.run.call(());
+    }
+
+    /// Cancel every running future. The current value is kept.
+    pub fn cancel(&mut self) {
+        for (_, task) in self.runs.write_unchecked().tasks.drain(..) {
+            task.cancel();
+        }
+    }
+
+    /// Get the latest value of the future. This method returns a [`ReadOnlySignal`] which can be read to get the
+    /// value or passed to other hooks and components.
+    pub fn value(&self) -> ReadOnlySignal<Option<T>> {
+        self.value.into()
+    }
+}
+
+impl<T> PartialEq for AsyncMemo<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+            && self.stale == other.stale
+            && self.runs == other.runs
+            && self.run == other.run
+    }
+}
+
+impl<T> Clone for AsyncMemo<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AsyncMemo<T> {}
+
+impl<T> From<AsyncMemo<T>> for ReadOnlySignal<Option<T>> {
+    fn from(val: AsyncMemo<T>) -> Self {
+        val.value.into()
+    }
+}
+
+impl<T> Readable for AsyncMemo<T> {
+    type Target = Option<T>;
+    type Storage = UnsyncStorage;
+
+    #[track_caller]
+    fn try_read_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.value.try_read_unchecked()
+    }
+
+    #[track_caller]
+    fn try_peek_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.value.try_peek_unchecked()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for AsyncMemo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Allow calling an async memo with memo() syntax
+///
+/// Currently only limited to copy types, though could probably specialize for string/arc/rc
+impl<T: Clone> Deref for AsyncMemo<T> {
+    type Target = dyn Fn() -> Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        Readable::deref_impl(self)
+    }
+}
@@ -0,0 +1,35 @@
+use dioxus_core::prelude::*;
+use dioxus_signals::Store;
+
+/// Creates a new [`Store`]: state that tracks reads of each part of the value separately.
+///
+/// Use `#[derive(Store)]` on your state to read and write each field on its own. A component
+/// that reads one field only reruns when that field is written.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// #[derive(Store)]
+/// struct Counter {
+///     count: i32,
+///     step: i32,
+/// }
+///
+/// fn App() -> Element {
+///     let counter = use_store(|| Counter { count: 0, step: 1 });
+///
+///     rsx! {
+///         button {
+///             onclick: move |_| *counter.count().write() += counter.step().cloned(),
+///             "Count: {counter.count()}"
+///         }
+///         button { onclick: move |_| *counter.step().write() += 1, "Step: {counter.step()}" }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[track_caller]
+#[must_use]
+pub fn use_store<T: 'static>(f: impl FnOnce() -> T) -> Store<T> {
+    use_hook(|| Store::new(f()))
+}
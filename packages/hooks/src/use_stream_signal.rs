@@ -0,0 +1,58 @@
+use dioxus_core::prelude::*;
+use dioxus_signals::Signal;
+use futures_util::Stream;
+
+/// Drive a stream into a signal that holds the latest item of the stream. The signal is `None` until the stream yields
+/// its first item. If the stream yields several items between polls, only the newest item is kept.
+///
+/// The stream is created once when the component is created and stops when the component is dropped.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use futures_util::Stream;
+/// # fn connect(url: &str) -> impl Stream<Item = String> { futures_util::stream::empty() }
+/// fn Status() -> Element {
+///     let status = use_stream_signal(|| connect("wss://example.com/status"));
+///
+///     rsx! {
+///         "Status: {status.cloned().unwrap_or_default()}"
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_stream_signal<T, S>(stream: impl FnOnce() -> S) -> Signal<Option<T>>
+where
+    T: 'static,
+    S: Stream<Item = T> + 'static,
+{
+    use_hook(|| Signal::from_stream(stream()))
+}
+
+/// Drive a stream into a signal that collects every item of the stream. The items are kept until you remove them
+/// from the signal.
+///
+/// The stream is created once when the component is created and stops when the component is dropped.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use futures_util::Stream;
+/// # fn log_lines() -> impl Stream<Item = String> { futures_util::stream::empty() }
+/// fn Logs() -> Element {
+///     let mut lines = use_stream_signal_accumulate(log_lines);
+///
+///     rsx! {
+///         for line in lines.iter() {
+///             pre { "{line}" }
+///         }
+///         button { onclick: move |_| lines.write().clear(), "Clear" }
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_stream_signal_accumulate<T, S>(stream: impl FnOnce() -> S) -> Signal<Vec<T>>
+where
+    T: 'static,
+    S: Stream<Item = T> + 'static,
+{
+    use_hook(|| Signal::from_stream_accumulate(stream()))
+}
@@ -0,0 +1,218 @@
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+#[derive(Default)]
+struct State {
+    fetches: Cell<usize>,
+    client: Cell<Option<QueryClient>>,
+    children: Cell<Option<Signal<usize>>>,
+    seen: RefCell<Vec<Option<usize>>>,
+}
+
+#[derive(Clone)]
+struct Shared(Rc<State>);
+
+impl PartialEq for Shared {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A component that queries the number of fetches so far
+#[component]
+fn Todos(state: Shared, options: QueryOptions) -> Element {
+    let state = state.0;
+    let query = use_query_with(options, "todos", {
+        let state = state.clone();
+        move || {
+            state.fetches.set(state.fetches.get() + 1);
+            let fetches = state.fetches.get();
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                fetches
+            }
+        }
+    });
+    state.seen.borrow_mut().push(query.data());
+    rsx! {}
+}
+
+fn dom(children: usize, stale_time: u64) -> (VirtualDom, Rc<State>) {
+    let options = QueryOptions::new().stale_time(Duration::from_secs(stale_time));
+    dom_with(children, vec![options])
+}
+
+/// Create a dom where each child uses the next options in the list
+fn dom_with(children: usize, options: Vec<QueryOptions>) -> (VirtualDom, Rc<State>) {
+    let state = Rc::new(State::default());
+    let dom = VirtualDom::new_with_props(
+        |(children, options, state): (usize, Vec<QueryOptions>, Shared)| {
+            let children = use_signal(|| children);
+            state.0.children.set(Some(children));
+            state.0.client.set(Some(use_query_client()));
+            rsx! {
+                for i in 0..children() {
+                    Todos { state: state.clone(), options: options[i % options.len()] }
+                }
+            }
+        },
+        (children, options, Shared(state.clone())),
+    );
+    (dom, state)
+}
+
+/// Process work in the dom for some time
+async fn run_for(dom: &mut VirtualDom, duration: Duration) {
+    let _ = tokio::time::timeout(duration, async {
+        loop {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn queries_are_shared_and_deduplicated() {
+    let (mut dom, state) = dom(2, 60);
+    dom.rebuild_in_place();
+    let mut children = state.children.get().unwrap();
+    run_for(&mut dom, Duration::from_millis(50)).await;
+
+    // Both components share one fetch
+    assert_eq!(state.fetches.get(), 1);
+    assert_eq!(state.seen.borrow().last(), Some(&Some(1)));
+
+    // Fresh data is shown right away in new components without refetching
+    state.seen.borrow_mut().clear();
+    dom.in_runtime(|| children.set(3));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 1);
+    assert!(state.seen.borrow().iter().all(|seen| *seen == Some(1)));
+
+    // Invalidating the query refetches it
+    let client = state.client.get().unwrap();
+    dom.in_runtime(|| client.invalidate(QueryKey::new()));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+    assert_eq!(state.seen.borrow().last(), Some(&Some(2)));
+
+    // Stale queries are refetched when the window is focused
+    dom.in_runtime(|| client.window_focused());
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+}
+
+#[tokio::test]
+async fn queries_support_optimistic_updates() {
+    let (mut dom, state) = dom(1, 0);
+    dom.rebuild_in_place();
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    let client = state.client.get().unwrap();
+    assert_eq!(
+        dom.in_runtime(|| client.get_query_data("todos")),
+        Some(1usize)
+    );
+
+    let update =
+        dom.in_runtime(|| client.optimistic_update("todos", |fetches: &mut usize| *fetches += 10));
+    run_for(&mut dom, Duration::from_millis(5)).await;
+    assert_eq!(state.seen.borrow().last(), Some(&Some(11)));
+
+    dom.in_runtime(|| update.rollback());
+    run_for(&mut dom, Duration::from_millis(5)).await;
+    assert_eq!(state.seen.borrow().last(), Some(&Some(1)));
+
+    // Stale queries are refetched when the window is focused
+    dom.in_runtime(|| client.window_focused());
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+    assert_eq!(state.seen.borrow().last(), Some(&Some(2)));
+}
+
+#[tokio::test]
+async fn invalidating_a_running_fetch_restarts_it() {
+    let (mut dom, state) = dom(1, 60);
+    // The fetch starts while rendering, so it is still running when the query is invalidated
+    dom.rebuild_in_place();
+    assert_eq!(state.fetches.get(), 1);
+
+    // The first fetch may have read data from before the invalidation, so its result is thrown away
+    let client = state.client.get().unwrap();
+    dom.in_runtime(|| client.invalidate("todos"));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+    assert!(!state.seen.borrow().contains(&Some(1)));
+    assert_eq!(state.seen.borrow().last(), Some(&Some(2)));
+}
+
+#[tokio::test]
+async fn options_are_merged_across_components() {
+    let fresh = QueryOptions::new().stale_time(Duration::from_secs(60));
+    let (mut dom, state) = dom_with(2, vec![fresh, QueryOptions::new()]);
+    dom.rebuild_in_place();
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 1);
+
+    // The second component treats the data as stale right away, so focusing the window refetches it
+    let client = state.client.get().unwrap();
+    dom.in_runtime(|| client.window_focused());
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+
+    // Once it is gone, the data stays fresh for the first component
+    let mut children = state.children.get().unwrap();
+    dom.in_runtime(|| children.set(1));
+    run_for(&mut dom, Duration::from_millis(5)).await;
+    dom.in_runtime(|| client.window_focused());
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+}
+
+#[tokio::test]
+async fn unused_queries_are_evicted_after_the_cache_time() {
+    let options = QueryOptions::new()
+        .stale_time(Duration::from_secs(60))
+        .cache_time(Duration::from_millis(20));
+    let (mut dom, state) = dom_with(1, vec![options]);
+    dom.rebuild_in_place();
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    let client = state.client.get().unwrap();
+    assert_eq!(
+        dom.in_runtime(|| client.get_query_data("todos")),
+        Some(1usize)
+    );
+
+    let mut children = state.children.get().unwrap();
+    dom.in_runtime(|| children.set(0));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(
+        dom.in_runtime(|| client.get_query_data::<usize>("todos")),
+        None
+    );
+
+    // The query is fetched again the next time it is used
+    dom.in_runtime(|| children.set(1));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.fetches.get(), 2);
+    assert_eq!(state.seen.borrow().last(), Some(&Some(2)));
+}
+
+#[test]
+#[should_panic(
+    expected = "The query [todos] holds usize, but it was used as alloc::string::String"
+)]
+fn keys_used_with_different_types_panic() {
+    let (mut dom, state) = dom(0, 60);
+    dom.rebuild_in_place();
+    let client = state.client.get().unwrap();
+    dom.in_runtime(|| {
+        client.set_query_data("todos", 1usize);
+        client.get_query_data::<String>("todos")
+    });
+}
@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+#[derive(Default)]
+struct State {
+    handles: Cell<Option<(Signal<i32>, AsyncMemo<i32>)>>,
+    runs: Cell<usize>,
+    finished: RefCell<Vec<i32>>,
+}
+
+/// Create a dom with an async memo that echoes a query. Querying 1 takes 100ms, every other query finishes immediately.
+fn dom(options: ResourceOptions) -> (VirtualDom, Rc<State>) {
+    let state = Rc::new(State::default());
+    let dom = VirtualDom::new_with_props(
+        |(options, state): (ResourceOptions, Rc<State>)| {
+            let query = use_signal(|| 0);
+            let memo = use_resource_with(options, {
+                let state = state.clone();
+                move || {
+                    let query = query();
+                    state.runs.set(state.runs.get() + 1);
+                    let state = state.clone();
+                    async move {
+                        if query == 1 {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                        }
+                        state.finished.borrow_mut().push(query);
+                        query
+                    }
+                }
+            });
+            state.handles.set(Some((query, memo)));
+            rsx! {}
+        },
+        (options, state.clone()),
+    );
+    (dom, state)
+}
+
+/// Process work in the dom for some time
+async fn run_for(dom: &mut VirtualDom, duration: Duration) {
+    let _ = tokio::time::timeout(duration, async {
+        loop {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn async_memos_debounce_changes() {
+    let (mut dom, state) = dom(ResourceOptions::new().debounce(Duration::from_millis(50)));
+    dom.rebuild_in_place();
+    let (mut query, memo) = state.handles.get().unwrap();
+    assert!(dom.in_runtime(|| memo.is_stale()));
+
+    run_for(&mut dom, Duration::from_millis(20)).await;
+    assert_eq!(dom.in_runtime(|| memo.cloned()), Some(0));
+    assert!(!dom.in_runtime(|| memo.is_stale()));
+
+    // The previous value is kept while the dependencies keep changing
+    dom.in_runtime(|| query.set(10));
+    run_for(&mut dom, Duration::from_millis(10)).await;
+    dom.in_runtime(|| query.set(11));
+    run_for(&mut dom, Duration::from_millis(10)).await;
+    assert!(dom.in_runtime(|| memo.is_stale()));
+    assert_eq!(dom.in_runtime(|| memo.cloned()), Some(0));
+    assert_eq!(state.runs.get(), 1);
+
+    // Once the dependencies stop changing, the future reruns once
+    run_for(&mut dom, Duration::from_millis(100)).await;
+    assert_eq!(dom.in_runtime(|| memo.cloned()), Some(11));
+    assert!(!dom.in_runtime(|| memo.is_stale()));
+    assert_eq!(state.runs.get(), 2);
+}
+
+#[tokio::test]
+async fn async_memos_cancel_or_race_running_futures() {
+    for policy in [RerunPolicy::Cancel, RerunPolicy::Race] {
+        let (mut dom, state) = dom(ResourceOptions::new()
+            .keep_previous(false)
+            .rerun_policy(policy));
+        dom.rebuild_in_place();
+        let (mut query, memo) = state.handles.get().unwrap();
+        run_for(&mut dom, Duration::from_millis(20)).await;
+        assert_eq!(dom.in_runtime(|| memo.cloned()), Some(0));
+
+        // Start a slow run, and then a fast run before the slow run finishes
+        dom.in_runtime(|| query.set(1));
+        run_for(&mut dom, Duration::from_millis(20)).await;
+        assert_eq!(dom.in_runtime(|| memo.cloned()), None);
+        dom.in_runtime(|| query.set(2));
+        run_for(&mut dom, Duration::from_millis(200)).await;
+
+        // The slow run never overwrites the newer value
+        assert_eq!(dom.in_runtime(|| memo.cloned()), Some(2));
+        assert!(!dom.in_runtime(|| memo.is_stale()));
+        let finished = state.finished.borrow().clone();
+        match policy {
+            RerunPolicy::Cancel => assert_eq!(finished, [0, 2]),
+            RerunPolicy::Race => assert_eq!(finished, [0, 2, 1]),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! A JSON protocol for inspecting the live component tree of a running app from the CLI or a browser page.
+//!
+//! Clients send [`DevtoolsRequest`]s and the app answers every request with a [`DevtoolsMsg`]. The app owns the
+//! [`VirtualDom`], so it is responsible for reading requests and answering them with [`handle_devtools_request`] on the
+//! thread the VirtualDom lives on.
+
+use dioxus_core::{ComponentTree, ElementId, ScopeId, ScopeSnapshot, VirtualDom};
+use serde::{Deserialize, Serialize};
+
+/// A message a devtools client sends to the app
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum DevtoolsRequest {
+    /// Get a snapshot of the whole component tree
+    GetTree,
+
+    /// Get a snapshot of a single component
+    InspectScope(ScopeId),
+
+    /// Highlight the elements a component is mounted to
+    Highlight(ScopeId),
+
+    /// Stop highlighting any component
+    ClearHighlight,
+}
+
+/// A message the app sends to devtools clients
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum DevtoolsMsg {
+    /// A snapshot of the whole component tree. This is sent in response to [`DevtoolsRequest::GetTree`] and may be
+    /// sent by the app at any time after the tree changes
+    Tree(ComponentTree),
+
+    /// A snapshot of a single component
+    Scope(ScopeSnapshot),
+
+    /// The requested component is not mounted
+    ScopeNotFound(ScopeId),
+
+    /// The renderer should highlight these elements
+    Highlight {
+        /// The component that is highlighted
+        scope: ScopeId,
+
+        /// The top level nodes the component is mounted to
+        elements: Vec<ElementId>,
+    },
+
+    /// The renderer should stop highlighting any component
+    ClearHighlight,
+}
+
+/// Answer a devtools request with the current state of the VirtualDom.
+///
+/// Highlighting is up to the renderer: it should watch for [`DevtoolsMsg::Highlight`] and
+/// [`DevtoolsMsg::ClearHighlight`] responses and outline the elements in the message.
+pub fn handle_devtools_request(dom: &VirtualDom, request: &DevtoolsRequest) -> DevtoolsMsg {
+    match request {
+        DevtoolsRequest::GetTree => DevtoolsMsg::Tree(dom.component_tree()),
+        DevtoolsRequest::InspectScope(id) => match dom.inspect_scope(*id) {
+            Some(scope) => DevtoolsMsg::Scope(scope),
+            None => DevtoolsMsg::ScopeNotFound(*id),
+        },
+        DevtoolsRequest::Highlight(id) => match dom.inspect_scope(*id) {
+            Some(scope) => DevtoolsMsg::Highlight {
+                scope: *id,
+                elements: scope.elements,
+            },
+            None => DevtoolsMsg::ScopeNotFound(*id),
+        },
+        DevtoolsRequest::ClearHighlight => DevtoolsMsg::ClearHighlight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_devtools_msg() {
+        let request: DevtoolsRequest = serde_json::from_str(r#"{"InspectScope":3}"#).unwrap();
+        assert_eq!(request, DevtoolsRequest::InspectScope(ScopeId::APP));
+
+        let msg = DevtoolsMsg::Highlight {
+            scope: ScopeId::APP,
+            elements: vec![ElementId(1), ElementId(2)],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"Highlight":{"scope":3,"elements":[1,2]}}"#);
+    }
+}
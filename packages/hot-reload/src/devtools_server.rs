@@ -0,0 +1,118 @@
+use crate::{DevtoolsMsg, DevtoolsRequest};
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use tokio::{
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{broadcast, mpsc},
+};
+use tokio_tungstenite::tungstenite::Message;
+
+/// A websocket server devtools clients can connect to
+///
+/// Requests from every client are merged into one stream and every message is sent to all connected clients.
+///
+/// ```rust, no_run
+/// # use dioxus_core::{VirtualDom, Element, NoOpMutations, VNode};
+/// # use dioxus_hot_reload::*;
+/// # fn app() -> Element { Ok(VNode::placeholder()) }
+/// # async fn run() -> std::io::Result<()> {
+/// let mut dom = VirtualDom::new(app);
+/// dom.rebuild(&mut NoOpMutations);
+///
+/// let mut devtools = DevtoolsServer::bind("127.0.0.1:8081").await?;
+/// while let Some(request) = devtools.next_request().await {
+///     devtools.send(&handle_devtools_request(&dom, &request));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DevtoolsServer {
+    requests: mpsc::UnboundedReceiver<DevtoolsRequest>,
+    messages: broadcast::Sender<String>,
+    local_addr: SocketAddr,
+}
+
+impl DevtoolsServer {
+    /// Start listening for devtools clients on the given address
+    pub async fn bind(addr: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let (request_tx, requests) = mpsc::unbounded_channel();
+        let (messages, _) = broadcast::channel(64);
+
+        let client_messages = messages.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_client(
+                    stream,
+                    request_tx.clone(),
+                    client_messages.subscribe(),
+                ));
+            }
+        });
+
+        Ok(Self {
+            requests,
+            messages,
+            local_addr,
+        })
+    }
+
+    /// The address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Wait for the next request from any client
+    ///
+    /// Returns None once the server stops accepting connections and every client has disconnected
+    pub async fn next_request(&mut self) -> Option<DevtoolsRequest> {
+        self.requests.recv().await
+    }
+
+    /// Send a message to every connected client
+    pub fn send(&self, msg: &DevtoolsMsg) {
+        if let Ok(json) = serde_json::to_string(msg) {
+            // This only fails if there are no clients connected
+            _ = self.messages.send(json);
+        }
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    requests: mpsc::UnboundedSender<DevtoolsRequest>,
+    mut messages: broadcast::Receiver<String>,
+) {
+    let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+
+    loop {
+        tokio::select! {
+            msg = socket.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<DevtoolsRequest>(&text) {
+                        Ok(request) => _ = requests.send(request),
+                        Err(err) => tracing::warn!("Invalid devtools request {text:?}: {err}"),
+                    }
+                }
+                Some(Ok(Message::Ping(data))) => {
+                    _ = socket.send(Message::Pong(data)).await;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+            msg = messages.recv() => match msg {
+                Ok(json) => {
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        return;
+                    }
+                }
+                // If the client is too slow, skip the messages it missed. The next tree snapshot will catch it up
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+        }
+    }
+}
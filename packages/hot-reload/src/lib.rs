@@ -14,6 +14,15 @@ mod ws_receiver;
 #[cfg(feature = "serve")]
 pub use ws_receiver::*;
 
+mod devtools;
+pub use devtools::*;
+
+#[cfg(feature = "serve")]
+mod devtools_server;
+
+#[cfg(feature = "serve")]
+pub use devtools_server::*;
+
 /// A message the hot reloading server sends to the client
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(bound(deserialize = "'de: 'static"))]
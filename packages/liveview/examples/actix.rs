@@ -0,0 +1,28 @@
+use dioxus::prelude::*;
+use dioxus_liveview::{ActixRouter, LiveviewRouter};
+
+fn app() -> Element {
+    let mut num = use_signal(|| 0);
+
+    rsx! {
+        div {
+            "hello actix! {num}"
+            button { onclick: move |_| num += 1, "Increment" }
+        }
+    }
+}
+
+fn main() {
+    pretty_env_logger::init();
+
+    let addr: std::net::SocketAddr = ([127, 0, 0, 1], 3030).into();
+
+    println!("Listening on http://{addr}");
+
+    // actix runs on its own runtime
+    actix_web::rt::System::new().block_on(
+        ActixRouter::create_default_liveview_router()
+            .with_app("/", app)
+            .start(addr),
+    );
+}
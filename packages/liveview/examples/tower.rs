@@ -0,0 +1,27 @@
+use dioxus::prelude::*;
+use dioxus_liveview::{LiveViewService, LiveviewRouter};
+
+fn app() -> Element {
+    let mut num = use_signal(|| 0);
+
+    rsx! {
+        div {
+            "hello tower! {num}"
+            button { onclick: move |_| num += 1, "Increment" }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    pretty_env_logger::init();
+
+    let addr: std::net::SocketAddr = ([127, 0, 0, 1], 3030).into();
+
+    println!("Listening on http://{addr}");
+
+    LiveViewService::create_default_liveview_router()
+        .with_app("/", app)
+        .start(addr)
+        .await;
+}
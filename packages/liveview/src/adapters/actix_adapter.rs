@@ -0,0 +1,177 @@
+use crate::{index_page, ws_path, LiveViewError, LiveViewSocket, LiveviewApp, LiveviewRouter};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use actix_ws::{AggregatedMessage, MessageStream, Session};
+use futures_channel::mpsc::UnboundedReceiver;
+use futures_util::{Sink, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Convert an actix-ws session and message stream into a `LiveViewSocket`.
+///
+/// This is required to launch a LiveView app using the actix-web framework. This must be called inside of an actix
+/// runtime.
+///
+/// ```rust, ignore
+/// async fn liveview(req: HttpRequest, body: web::Payload) -> actix_web::Result<HttpResponse> {
+///     let (response, session, stream) = actix_ws::handle(&req, body)?;
+///     actix_web::rt::spawn(async move {
+///         _ = view.launch(actix_socket(session, stream), app).await;
+///     });
+///     Ok(response)
+/// }
+/// ```
+pub fn actix_socket(session: Session, stream: MessageStream) -> impl LiveViewSocket {
+    // The message stream is !Send, so it is read on the actix runtime and forwarded to the socket
+    let (tx, rx) = futures_channel::mpsc::unbounded();
+    let mut pong_session = session.clone();
+    actix_web::rt::spawn(async move {
+        let mut stream = stream.aggregate_continuations();
+        while let Some(message) = stream.next().await {
+            let message = match message {
+                Ok(AggregatedMessage::Text(text)) => Ok(text.as_bytes().to_vec()),
+                Ok(AggregatedMessage::Binary(bytes)) => Ok(bytes.to_vec()),
+                Ok(AggregatedMessage::Ping(bytes)) => {
+                    _ = pong_session.pong(&bytes).await;
+                    continue;
+                }
+                Ok(AggregatedMessage::Pong(_)) => continue,
+                Ok(AggregatedMessage::Close(_)) => break,
+                Err(_) => Err(LiveViewError::SendingFailed),
+            };
+            if tx.unbounded_send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let sink = futures_util::sink::unfold(session, |mut session, message: Vec<u8>| async move {
+        session
+            .binary(message)
+            .await
+            .map_err(|_| LiveViewError::SendingFailed)?;
+        Ok(session)
+    });
+
+    ActixSocket {
+        rx,
+        sink: Box::pin(sink),
+    }
+}
+
+struct ActixSocket {
+    rx: UnboundedReceiver<Result<Vec<u8>, LiveViewError>>,
+    sink: Pin<Box<dyn Sink<Vec<u8>, Error = LiveViewError> + Send>>,
+}
+
+impl Stream for ActixSocket {
+    type Item = Result<Vec<u8>, LiveViewError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_next_unpin(cx)
+    }
+}
+
+impl Sink<Vec<u8>> for ActixSocket {
+    type Error = LiveViewError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sink.as_mut().poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+        self.sink.as_mut().start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sink.as_mut().poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sink.as_mut().poll_close(cx)
+    }
+}
+
+/// The LiveView routes of an actix-web server. Build one with [`LiveviewRouter`], and either start it directly or add
+/// the routes to your own app with [`ActixRouter::configure`].
+#[derive(Clone, Default)]
+pub struct ActixRouter {
+    routes: Vec<(String, LiveviewApp)>,
+}
+
+impl ActixRouter {
+    /// Add the LiveView routes to an actix app.
+    ///
+    /// ```rust, ignore
+    /// let liveview = ActixRouter::create_default_liveview_router().with_app("/", app);
+    /// HttpServer::new(move || {
+    ///     let liveview = liveview.clone();
+    ///     App::new().configure(move |config| liveview.configure(config))
+    /// })
+    /// ```
+    pub fn configure(&self, config: &mut web::ServiceConfig) {
+        for (route, app) in &self.routes {
+            let ws_path = ws_path(route);
+            let index_page = index_page(&ws_path);
+            let app = app.clone();
+
+            config
+                .route(
+                    &ws_path,
+                    web::get().to(move |req: HttpRequest, body: web::Payload| {
+                        let app = app.clone();
+                        async move {
+                            let (response, session, stream) = actix_ws::handle(&req, body)?;
+                            actix_web::rt::spawn(async move {
+                                _ = app.launch(actix_socket(session, stream)).await;
+                            });
+                            Ok::<_, actix_web::Error>(response)
+                        }
+                    }),
+                )
+                .route(
+                    route,
+                    web::get().to(move || {
+                        let index_page = index_page.clone();
+                        async move {
+                            HttpResponse::Ok()
+                                .content_type("text/html; charset=utf-8")
+                                .body(index_page)
+                        }
+                    }),
+                );
+        }
+    }
+}
+
+impl LiveviewRouter for ActixRouter {
+    fn create_default_liveview_router() -> Self {
+        Self::default()
+    }
+
+    fn with_virtual_dom(
+        mut self,
+        route: &str,
+        app: impl Fn() -> dioxus_core::prelude::VirtualDom + Send + Sync + 'static,
+    ) -> Self {
+        self.routes.push((route.to_string(), LiveviewApp::new(app)));
+        self
+    }
+
+    async fn start(self, address: impl Into<std::net::SocketAddr>) {
+        let server = HttpServer::new(move || {
+            let router = self.clone();
+            App::new().configure(move |config| router.configure(config))
+        })
+        .bind(address.into());
+
+        let result = match server {
+            Ok(server) => server.run().await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            eprintln!("Failed to start actix server: {}", err);
+        }
+    }
+}
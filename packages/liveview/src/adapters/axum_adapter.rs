@@ -1,6 +1,4 @@
-use std::sync::Arc;
-
-use crate::{interpreter_glue, LiveViewError, LiveViewSocket, LiveviewRouter};
+use crate::{index_page, ws_path, LiveViewError, LiveViewSocket, LiveviewApp, LiveviewRouter};
 use axum::{
     extract::{
         ws::{Message, WebSocket},
@@ -22,11 +20,10 @@ pub fn axum_socket(ws: WebSocket) -> impl LiveViewSocket {
 }
 
 fn transform_rx(message: Result<Message, axum::Error>) -> Result<Vec<u8>, LiveViewError> {
-    message
+    // Clients on the binary protocol send binary messages, so text and binary messages are both passed through
+    Ok(message
         .map_err(|_| LiveViewError::SendingFailed)?
-        .into_text()
-        .map(|s| s.into_bytes())
-        .map_err(|_| LiveViewError::SendingFailed)
+        .into_data())
 }
 
 async fn transform_tx(message: Vec<u8>) -> Result<Message, axum::Error> {
@@ -43,41 +40,19 @@ impl LiveviewRouter for Router {
         route: &str,
         app: impl Fn() -> dioxus_core::prelude::VirtualDom + Send + Sync + 'static,
     ) -> Self {
-        let view = crate::LiveViewPool::new();
-
-        let ws_path = format!("{}/ws", route);
-        let title = crate::app_title();
-
-        let index_page_with_glue = move |glue: &str| {
-            Html(format!(
-                r#"
-        <!DOCTYPE html>
-        <html>
-            <head> <title>{title}</title>  </head>
-            <body> <div id="main"></div> </body>
-            {glue}
-        </html>
-        "#,
-            ))
-        };
-
-        let app = Arc::new(app);
+        let app = LiveviewApp::new(app);
+        let ws_path = ws_path(route);
+        let index_page = Html(index_page(&ws_path));
 
         self.route(
             &ws_path,
             get(move |ws: WebSocketUpgrade| async move {
-                let app = app.clone();
                 ws.on_upgrade(move |socket| async move {
-                    _ = view
-                        .launch_virtualdom(axum_socket(socket), move || app())
-                        .await;
+                    _ = app.launch(axum_socket(socket)).await;
                 })
             }),
         )
-        .route(
-            route,
-            get(move || async move { index_page_with_glue(&interpreter_glue(&ws_path)) }),
-        )
+        .route(route, get(move || async move { index_page }))
     }
 
     async fn start(self, address: impl Into<std::net::SocketAddr>) {
@@ -1,12 +1,29 @@
 use std::future::Future;
 
+use crate::{LiveViewError, LiveViewSocket};
 use dioxus_core::{Element, VirtualDom};
+use futures_util::{future::ready, Sink, SinkExt, Stream, StreamExt};
 
 #[cfg(feature = "axum")]
 pub mod axum_adapter;
 #[cfg(feature = "axum")]
 pub use axum_adapter::*;
 
+#[cfg(feature = "actix")]
+pub mod actix_adapter;
+#[cfg(feature = "actix")]
+pub use actix_adapter::*;
+
+#[cfg(feature = "warp")]
+pub mod warp_adapter;
+#[cfg(feature = "warp")]
+pub use warp_adapter::*;
+
+#[cfg(feature = "tower")]
+pub mod tower_adapter;
+#[cfg(feature = "tower")]
+pub use tower_adapter::*;
+
 /// A trait for servers that can be used to host a LiveView app.
 pub trait LiveviewRouter {
     /// Create a new router.
@@ -30,3 +47,112 @@ pub trait LiveviewRouter {
     /// Start the server on an address.
     fn start(self, address: impl Into<std::net::SocketAddr>) -> impl Future<Output = ()>;
 }
+
+/// Convert the websocket of any framework into a `LiveViewSocket`.
+///
+/// The websocket must be a `Stream` and `Sink` of the framework's message type. `into_bytes` reads the payload of a
+/// message from the client, and `binary` creates a binary message for the client. This works with websockets that
+/// don't have a dedicated adapter, like salvo's:
+///
+/// ```rust, ignore
+/// use salvo::websocket::{Message, WebSocketUpgrade};
+///
+/// #[handler]
+/// async fn liveview(req: &mut Request, res: &mut Response, depot: &mut Depot) -> Result<(), StatusError> {
+///     let view = depot.obtain::<LiveViewPool>().unwrap().clone();
+///     WebSocketUpgrade::new()
+///         .upgrade(req, res, move |ws| async move {
+///             _ = view.launch(generic_socket(ws, Message::into_bytes, Message::binary), app).await;
+///         })
+///         .await
+/// }
+/// ```
+pub fn generic_socket<S, M, E>(
+    ws: S,
+    into_bytes: fn(M) -> Vec<u8>,
+    binary: fn(Vec<u8>) -> M,
+) -> impl LiveViewSocket
+where
+    S: Stream<Item = Result<M, E>> + Sink<M> + Send + 'static,
+    M: Send + 'static,
+{
+    ws.sink_map_err(|_| LiveViewError::SendingFailed)
+        .with(move |bytes| ready(Ok(binary(bytes))))
+        .map(move |message| {
+            message
+                .map(into_bytes)
+                .map_err(|_| LiveViewError::SendingFailed)
+        })
+}
+
+/// The app served on a liveview route
+#[cfg(any(
+    feature = "axum",
+    feature = "actix",
+    feature = "warp",
+    feature = "tower"
+))]
+#[derive(Clone)]
+pub(crate) struct LiveviewApp {
+    pool: crate::LiveViewPool,
+    make_app: std::sync::Arc<dyn Fn() -> VirtualDom + Send + Sync>,
+}
+
+#[cfg(any(
+    feature = "axum",
+    feature = "actix",
+    feature = "warp",
+    feature = "tower"
+))]
+impl LiveviewApp {
+    pub(crate) fn new(app: impl Fn() -> VirtualDom + Send + Sync + 'static) -> Self {
+        Self {
+            pool: crate::LiveViewPool::new(),
+            make_app: std::sync::Arc::new(app),
+        }
+    }
+
+    /// Run the app for a client until the socket closes
+    pub(crate) async fn launch(
+        &self,
+        socket: impl crate::LiveViewSocket,
+    ) -> Result<(), crate::LiveViewError> {
+        let make_app = self.make_app.clone();
+        self.pool
+            .launch_virtualdom(socket, move || make_app())
+            .await
+    }
+}
+
+/// The path of the websocket for a liveview route
+#[cfg(any(
+    feature = "axum",
+    feature = "actix",
+    feature = "warp",
+    feature = "tower"
+))]
+pub(crate) fn ws_path(route: &str) -> String {
+    format!("{}/ws", route.trim_end_matches('/'))
+}
+
+/// The page that loads the interpreter and connects it to the websocket at `ws_path`
+#[cfg(any(
+    feature = "axum",
+    feature = "actix",
+    feature = "warp",
+    feature = "tower"
+))]
+pub(crate) fn index_page(ws_path: &str) -> String {
+    let title = crate::app_title();
+    let glue = crate::interpreter_glue(ws_path);
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html>
+            <head> <title>{title}</title>  </head>
+            <body> <div id="main"></div> </body>
+            {glue}
+        </html>
+        "#,
+    )
+}
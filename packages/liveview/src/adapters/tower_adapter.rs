@@ -0,0 +1,272 @@
+use crate::{index_page, ws_path, LiveViewError, LiveViewSocket, LiveviewApp, LiveviewRouter};
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::Full;
+use hyper::{
+    body::Bytes,
+    header::{
+        HeaderValue, CONNECTION, CONTENT_TYPE, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE,
+    },
+    Request, Response, StatusCode,
+};
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::{ready, Ready},
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::{
+    tungstenite::{self, handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+
+/// Convert a tokio-tungstenite WebSocket into a `LiveViewSocket`.
+///
+/// This works with any server that can hand out the upgraded connection of a websocket request.
+pub fn tungstenite_socket<S>(ws: WebSocketStream<S>) -> impl LiveViewSocket
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    ws.map(transform_rx)
+        .with(transform_tx)
+        .sink_map_err(|_| LiveViewError::SendingFailed)
+}
+
+fn transform_rx(message: Result<Message, tungstenite::Error>) -> Result<Vec<u8>, LiveViewError> {
+    Ok(message
+        .map_err(|_| LiveViewError::SendingFailed)?
+        .into_data())
+}
+
+async fn transform_tx(message: Vec<u8>) -> Result<Message, tungstenite::Error> {
+    Ok(Message::Binary(message))
+}
+
+/// A tower service that serves LiveView apps, including the websocket upgrade.
+///
+/// The service can be used with any hyper 1 server, or mounted in any framework that accepts tower services. The
+/// connection must be served with upgrades enabled for the websocket to connect:
+///
+/// ```rust, ignore
+/// let service = LiveViewService::create_default_liveview_router().with_app("/", app);
+/// hyper::server::conn::http1::Builder::new()
+///     .serve_connection(TokioIo::new(stream), TowerToHyperService::new(service))
+///     .with_upgrades()
+///     .await
+/// ```
+#[derive(Clone, Default)]
+pub struct LiveViewService {
+    routes: Arc<HashMap<String, Route>>,
+}
+
+#[derive(Clone)]
+enum Route {
+    Page(Bytes),
+    Socket(LiveviewApp),
+}
+
+impl<B> tower::Service<Request<B>> for LiveViewService {
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let response = match self.routes.get(req.uri().path()) {
+            Some(Route::Page(page)) => {
+                let mut response = Response::new(Full::new(page.clone()));
+                response.headers_mut().insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/html; charset=utf-8"),
+                );
+                response
+            }
+            Some(Route::Socket(app)) => upgrade(req, app.clone()),
+            None => status(StatusCode::NOT_FOUND),
+        };
+        ready(Ok(response))
+    }
+}
+
+/// Accept a websocket request and run the app on the upgraded connection
+fn upgrade<B>(mut req: Request<B>, app: LiveviewApp) -> Response<Full<Bytes>> {
+    let header_contains = |name, value: &str| {
+        req.headers().get_all(name).iter().any(|header| {
+            header
+                .to_str()
+                .map(|header| {
+                    header
+                        .split(',')
+                        .any(|part| part.trim().eq_ignore_ascii_case(value))
+                })
+                .unwrap_or(false)
+        })
+    };
+    if !header_contains(CONNECTION, "upgrade") || !header_contains(UPGRADE, "websocket") {
+        return status(StatusCode::BAD_REQUEST);
+    }
+    let Some(key) = req.headers().get(SEC_WEBSOCKET_KEY) else {
+        return status(StatusCode::BAD_REQUEST);
+    };
+    let accept = derive_accept_key(key.as_bytes());
+
+    let on_upgrade = hyper::upgrade::on(&mut req);
+    tokio::spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => {
+                let ws =
+                    WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None)
+                        .await;
+                _ = app.launch(tungstenite_socket(ws)).await;
+            }
+            Err(err) => tracing::error!("Failed to upgrade LiveView websocket: {err}"),
+        }
+    });
+
+    let mut response = status(StatusCode::SWITCHING_PROTOCOLS);
+    let headers = response.headers_mut();
+    headers.insert(CONNECTION, HeaderValue::from_static("upgrade"));
+    headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(SEC_WEBSOCKET_ACCEPT, accept.parse().unwrap());
+    response
+}
+
+fn status(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = status;
+    response
+}
+
+impl LiveviewRouter for LiveViewService {
+    fn create_default_liveview_router() -> Self {
+        Self::default()
+    }
+
+    fn with_virtual_dom(
+        mut self,
+        route: &str,
+        app: impl Fn() -> dioxus_core::prelude::VirtualDom + Send + Sync + 'static,
+    ) -> Self {
+        let ws_path = ws_path(route);
+        let page = Bytes::from(index_page(&ws_path));
+        let routes = Arc::make_mut(&mut self.routes);
+        routes.insert(ws_path, Route::Socket(LiveviewApp::new(app)));
+        routes.insert(route.to_string(), Route::Page(page));
+        self
+    }
+
+    async fn start(self, address: impl Into<std::net::SocketAddr>) {
+        let listener = match tokio::net::TcpListener::bind(address.into()).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                eprintln!("Failed to start hyper server: {}", err);
+                return;
+            }
+        };
+
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::error!("Failed to accept connection: {err}");
+                    continue;
+                }
+            };
+            let service = TowerToHyperService::new(self.clone());
+            tokio::spawn(async move {
+                if let Err(err) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .with_upgrades()
+                    .await
+                {
+                    tracing::error!("Failed to serve connection: {err}");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_socket;
+    use dioxus::prelude::*;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app() -> Element {
+        rsx! { div { "hello" } }
+    }
+
+    fn service() -> LiveViewService {
+        LiveViewService::create_default_liveview_router().with_app("/app", app)
+    }
+
+    async fn get(request: Request<()>) -> (StatusCode, String) {
+        let response = service().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    /// Send the first message of a client and wait for the server to pick a protocol
+    async fn initialize<S>(ws: &mut WebSocketStream<S>) -> Vec<u8>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let initialize = r#"{"method":"initialize","params":{}}"#;
+        ws.send(Message::Text(initialize.to_string()))
+            .await
+            .unwrap();
+        ws.next().await.unwrap().unwrap().into_data()
+    }
+
+    #[tokio::test]
+    async fn routes() {
+        let page = Request::get("/app").body(()).unwrap();
+        let (status, body) = get(page).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("/app/ws"));
+
+        let missing = Request::get("/missing").body(()).unwrap();
+        assert_eq!(get(missing).await.0, StatusCode::NOT_FOUND);
+
+        // The socket route only accepts websocket upgrades
+        let not_upgrade = Request::get("/app/ws").body(()).unwrap();
+        assert_eq!(get(not_upgrade).await.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn upgrades_websockets() {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(
+            hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(server), TowerToHyperService::new(service()))
+                .with_upgrades(),
+        );
+
+        let (mut ws, response) = tokio_tungstenite::client_async("ws://localhost/app/ws", client)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert!(initialize(&mut ws)
+            .await
+            .ends_with(br#""type":"protocol","data":{"binary":false,"compression":null}}"#));
+    }
+
+    #[tokio::test]
+    async fn generic_sockets() {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let server = WebSocketStream::from_raw_socket(server, Role::Server, None).await;
+        let socket = generic_socket(server, Message::into_data, Message::Binary);
+        tokio::spawn(async move { crate::LiveViewPool::new().launch(socket, app).await });
+
+        let mut ws = WebSocketStream::from_raw_socket(client, Role::Client, None).await;
+        assert_eq!(initialize(&mut ws).await.first(), Some(&0));
+    }
+}
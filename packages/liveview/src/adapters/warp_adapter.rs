@@ -0,0 +1,82 @@
+use crate::{index_page, ws_path, LiveViewError, LiveViewSocket, LiveviewApp, LiveviewRouter};
+use futures_util::{SinkExt, StreamExt};
+use warp::{
+    filters::{
+        path::FullPath,
+        ws::{Message, WebSocket, Ws},
+        BoxedFilter,
+    },
+    Filter, Rejection, Reply,
+};
+
+/// A warp filter that serves LiveView apps. Build one with [`LiveviewRouter`] and serve it with [`warp::serve`], or
+/// combine it with your own filters.
+pub type WarpRouter = BoxedFilter<(Box<dyn Reply>,)>;
+
+/// Convert a warp WebSocket into a `LiveViewSocket`.
+///
+/// This is required to launch a LiveView app using the warp web framework.
+pub fn warp_socket(ws: WebSocket) -> impl LiveViewSocket {
+    ws.map(transform_rx)
+        .with(transform_tx)
+        .sink_map_err(|_| LiveViewError::SendingFailed)
+}
+
+fn transform_rx(message: Result<Message, warp::Error>) -> Result<Vec<u8>, LiveViewError> {
+    Ok(message
+        .map_err(|_| LiveViewError::SendingFailed)?
+        .into_bytes())
+}
+
+async fn transform_tx(message: Vec<u8>) -> Result<Message, warp::Error> {
+    Ok(Message::binary(message))
+}
+
+impl LiveviewRouter for WarpRouter {
+    fn create_default_liveview_router() -> Self {
+        warp::any()
+            .and_then(|| async { Err::<Box<dyn Reply>, _>(warp::reject::not_found()) })
+            .boxed()
+    }
+
+    fn with_virtual_dom(
+        self,
+        route: &str,
+        app: impl Fn() -> dioxus_core::prelude::VirtualDom + Send + Sync + 'static,
+    ) -> Self {
+        let app = LiveviewApp::new(app);
+        let ws_path = ws_path(route);
+        let index_page = index_page(&ws_path);
+
+        let socket = exact_path(ws_path).and(warp::ws()).map(move |ws: Ws| {
+            let app = app.clone();
+            Box::new(ws.on_upgrade(move |socket| async move {
+                _ = app.launch(warp_socket(socket)).await;
+            })) as Box<dyn Reply>
+        });
+        let page = exact_path(route.to_string())
+            .and(warp::get())
+            .map(move || Box::new(warp::reply::html(index_page.clone())) as Box<dyn Reply>);
+
+        self.or(socket).unify().or(page).unify().boxed()
+    }
+
+    async fn start(self, address: impl Into<std::net::SocketAddr>) {
+        warp::serve(self).run(address.into()).await
+    }
+}
+
+/// Match requests for exactly this path
+fn exact_path(path: String) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and_then(move |full: FullPath| {
+            let matches = full.as_str() == path;
+            async move {
+                match matches {
+                    true => Ok(()),
+                    false => Err(warp::reject::not_found()),
+                }
+            }
+        })
+        .untuple_one()
+}
@@ -8,7 +8,9 @@ pub use adapters::*;
 
 mod element;
 pub mod pool;
+mod protocol;
 mod query;
+mod session;
 use dioxus_interpreter_js::NATIVE_JS;
 use futures_util::{SinkExt, StreamExt};
 pub use pool::*;
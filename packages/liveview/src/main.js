@@ -12,57 +12,278 @@ class IPC {
     window.interpreter = new NativeInterpreter();
     window.interpreter.initialize(root);
     window.interpreter.ipc = this;
+
+    // The session this page is rendering, and the number of edits it applied in that session. The server uses
+    // them to replay the edits we missed when the websocket reconnects.
+    this.session = null;
+    this.editsApplied = 0;
+    this.reconnectDelay = 500;
+    this.connect();
+  }
+
+  connect() {
     const ws = new WebSocket(WS_ADDR);
     ws.binaryType = "arraybuffer";
+    let pingInterval = null;
+    let acknowledgeTimeout = null;
+    // When the server can't replay the edits we missed, the next edits rebuild the page from scratch. Once they are
+    // applied, we applied this many edits of the session
+    let rebuildEdits = null;
+    // Frames are handled in order, even if some of them need to be decompressed first
+    let received = Promise.resolve();
+
+    // Every connection starts with JSON until the server switches it to the binary protocol
+    this.binary = false;
+    this.outgoing = [];
 
     function ping() {
       ws.send("__ping__");
     }
 
     ws.onopen = () => {
+      this.reconnectDelay = 500;
       // we ping every 30 seconds to keep the websocket alive
-      setInterval(ping, 30000);
-      ws.send(window.interpreter.serializeIpcMessage("initialize"));
+      pingInterval = setInterval(ping, 30000);
+      ws.send(
+        window.interpreter.serializeIpcMessage("initialize", {
+          session: this.session,
+          acknowledged: this.editsApplied,
+          protocol: {
+            binary: true,
+            compression:
+              typeof DecompressionStream == "undefined" ? [] : ["deflate-raw"],
+          },
+        })
+      );
     };
 
-    ws.onerror = (err) => {
-      // todo: retry the connection
+    ws.onclose = () => {
+      clearInterval(pingInterval);
+      clearTimeout(acknowledgeTimeout);
+      // Try to resume the session, backing off while the server is unreachable
+      setTimeout(() => this.connect(), this.reconnectDelay);
+      this.reconnectDelay = Math.min(this.reconnectDelay * 2, 10000);
     };
 
-    ws.onmessage = (message) => {
-      const u8view = new Uint8Array(message.data);
-      const binaryFrame = u8view[0] == 1;
-      const messageData = message.data.slice(1)
-      // The first byte tells the shim if this is a binary of text frame
-      if (binaryFrame) {
-        // binary frame
-        window.interpreter.run_from_bytes(messageData);
+    const applyEdits = (edits) => {
+      if (rebuildEdits != null) {
+        this.clear();
+        window.interpreter.run_from_bytes(edits);
+        this.editsApplied = rebuildEdits;
+        rebuildEdits = null;
+      } else {
+        window.interpreter.run_from_bytes(edits);
+        this.editsApplied += 1;
       }
-      else {
-        // text frame
 
-        let decoder = new TextDecoder("utf-8");
+      // Let the server know which edits we have so it can forget them
+      if (acknowledgeTimeout == null) {
+        acknowledgeTimeout = setTimeout(() => {
+          acknowledgeTimeout = null;
+          this.postMessage(
+            window.interpreter.serializeIpcMessage("acknowledge", {
+              edits: this.editsApplied,
+            })
+          );
+        }, 1000);
+      }
+    };
+
+    const handleText = (str) => {
+      // Ignore pongs
+      if (str == "__pong__") {
+        return;
+      }
+      const event = JSON.parse(str);
+      switch (event.type) {
+        case "query":
+          Function("Eval", `"use strict";${event.data};`)();
+          break;
+        case "session":
+          this.session = event.data.token;
+          break;
+        case "rebuild":
+          rebuildEdits = event.data.edits;
+          break;
+        case "protocol":
+          this.binary = event.data.binary;
+          break;
+      }
+    };
 
-        // Using decode method to get string output
-        let str = decoder.decode(messageData);
-        // Ignore pongs
-        if (str != "__pong__") {
-          const event = JSON.parse(str);
-          switch (event.type) {
-            case "query":
-              Function("Eval", `"use strict";${event.data};`)();
+    const handleFrame = async (data) => {
+      const decoder = new TextDecoder("utf-8");
+      const u8view = new Uint8Array(data);
+      // The first byte tells the shim if this is a binary, text or batched frame
+      if (u8view[0] == 1) {
+        // binary frame
+        applyEdits(data.slice(1));
+      } else if (u8view[0] == 0) {
+        // text frame
+        handleText(decoder.decode(data.slice(1)));
+      } else if (u8view[0] == 2) {
+        // batched frame: [kind: u8] [flags: u8] [length: u32] [payload] for every message
+        const view = new DataView(data);
+        let offset = 1;
+        while (offset < data.byteLength) {
+          const kind = view.getUint8(offset);
+          const flags = view.getUint8(offset + 1);
+          const length = view.getUint32(offset + 2, true);
+          let payload = data.slice(offset + 6, offset + 6 + length);
+          offset += 6 + length;
+          if (flags & 1) {
+            payload = await inflate(payload);
+          }
+          switch (kind) {
+            case 0:
+              handleText(decoder.decode(payload));
+              break;
+            case 1:
+              applyEdits(payload);
+              break;
+            case 2:
+              Function("Eval", `"use strict";${decoder.decode(payload)};`)();
               break;
           }
         }
       }
     };
 
+    ws.onmessage = (message) => {
+      received = received
+        .then(() => handleFrame(message.data))
+        .catch((err) => console.error(err));
+    };
+
     this.ws = ws;
   }
 
+  // Remove everything the server rendered so the DOM can be built again
+  clear() {
+    const interpreter = window.interpreter;
+    for (const name in interpreter.global) {
+      interpreter.root.removeEventListener(name, interpreter.global[name].callback);
+    }
+    interpreter.global = {};
+    interpreter.local = {};
+    interpreter.nodes = [interpreter.root];
+    interpreter.stack = [interpreter.root];
+    interpreter.templates = {};
+    interpreter.root.replaceChildren();
+  }
+
   postMessage(msg) {
-    this.ws.send(msg);
+    // Messages sent while reconnecting are dropped
+    if (this.ws.readyState != WebSocket.OPEN) {
+      return;
+    }
+    if (!this.binary) {
+      this.ws.send(msg);
+      return;
+    }
+
+    // Messages sent in the same task are batched into one frame
+    this.outgoing.push(encodeCbor(JSON.parse(msg)));
+    if (this.outgoing.length == 1) {
+      queueMicrotask(() => this.flush());
+    }
+  }
+
+  flush() {
+    const messages = this.outgoing;
+    this.outgoing = [];
+    let length = 1;
+    for (const message of messages) {
+      length += 6 + message.length;
+    }
+    const frame = new Uint8Array(length);
+    const view = new DataView(frame.buffer);
+    frame[0] = 2;
+    let offset = 1;
+    for (const message of messages) {
+      // kind 3 is a CBOR message, without any flags
+      frame[offset] = 3;
+      frame[offset + 1] = 0;
+      view.setUint32(offset + 2, message.length, true);
+      frame.set(message, offset + 6);
+      offset += 6 + message.length;
+    }
+    if (this.ws.readyState == WebSocket.OPEN) {
+      this.ws.send(frame);
+    }
+  }
+}
+
+async function inflate(data) {
+  const stream = new Blob([data])
+    .stream()
+    .pipeThrough(new DecompressionStream("deflate-raw"));
+  return await new Response(stream).arrayBuffer();
+}
+
+// Encode a JSON value as CBOR (RFC 8949)
+function encodeCbor(value) {
+  const bytes = [];
+  const encoder = new TextEncoder();
+
+  function writeHeader(major, length) {
+    if (length < 24) {
+      bytes.push((major << 5) | length);
+    } else if (length < 0x100) {
+      bytes.push((major << 5) | 24, length);
+    } else if (length < 0x10000) {
+      bytes.push((major << 5) | 25, length >> 8, length & 0xff);
+    } else {
+      bytes.push(
+        (major << 5) | 26,
+        (length >>> 24) & 0xff,
+        (length >>> 16) & 0xff,
+        (length >>> 8) & 0xff,
+        length & 0xff
+      );
+    }
+  }
+
+  function write(value) {
+    if (value === null || value === undefined) {
+      bytes.push(0xf6);
+    } else if (value === false) {
+      bytes.push(0xf4);
+    } else if (value === true) {
+      bytes.push(0xf5);
+    } else if (typeof value == "number") {
+      if (Number.isInteger(value) && Math.abs(value) <= 0xffffffff) {
+        if (value >= 0) {
+          writeHeader(0, value);
+        } else {
+          writeHeader(1, -1 - value);
+        }
+      } else {
+        const float = new DataView(new ArrayBuffer(8));
+        float.setFloat64(0, value);
+        bytes.push(0xfb, ...new Uint8Array(float.buffer));
+      }
+    } else if (typeof value == "string") {
+      const utf8 = encoder.encode(value);
+      writeHeader(3, utf8.length);
+      for (const byte of utf8) {
+        bytes.push(byte);
+      }
+    } else if (Array.isArray(value)) {
+      writeHeader(4, value.length);
+      value.forEach(write);
+    } else {
+      const entries = Object.entries(value).filter(([_, v]) => v !== undefined);
+      writeHeader(5, entries.length);
+      for (const [key, entry] of entries) {
+        write(key);
+        write(entry);
+      }
+    }
   }
+
+  write(value);
+  return new Uint8Array(bytes);
 }
 
 main();
@@ -2,21 +2,37 @@ use crate::{
     element::LiveviewElement,
     eval::init_eval,
     events::SerializedHtmlEventConverter,
+    protocol::{self, ClientMessage, Protocol, ProtocolOptions, ProtocolRequest, ServerMessage},
     query::{QueryEngine, QueryResult},
+    session::{run_session, BoxedSocket, SessionOptions, Sessions},
     LiveViewError,
 };
 use dioxus_core::prelude::*;
+#[cfg(all(feature = "hot-reload", debug_assertions))]
 use dioxus_hot_reload::DevserverMsg;
 use dioxus_html::{EventData, HtmlEvent, PlatformEventData};
 use dioxus_interpreter_js::MutationState;
-use futures_util::{pin_mut, SinkExt, StreamExt};
+use futures_util::{pin_mut, SinkExt, Stream, StreamExt};
 use serde::Serialize;
 use std::{rc::Rc, time::Duration};
 use tokio_util::task::LocalPoolHandle;
 
+/// A pool of threads that run the VirtualDoms of LiveView clients
+///
+/// Every client gets a session that keeps its VirtualDom alive for a grace period after the websocket disconnects. If
+/// the client reconnects within the grace period, it resumes the session and receives the edits it missed instead of
+/// starting the app from scratch. If the session no longer has those edits, the client rebuilds its DOM from the
+/// VirtualDom of the session instead.
+///
+/// Clients that support it are switched to a compact binary protocol when they connect. Older clients keep using
+/// JSON.
 #[derive(Clone)]
 pub struct LiveViewPool {
     pub(crate) pool: LocalPoolHandle,
+    sessions: Sessions,
+    session_grace_period: Duration,
+    replay_limit: usize,
+    protocol: ProtocolOptions,
 }
 
 impl Default for LiveViewPool {
@@ -32,9 +48,47 @@ impl LiveViewPool {
 
         LiveViewPool {
             pool: LocalPoolHandle::new(16),
+            sessions: Sessions::default(),
+            session_grace_period: Duration::from_secs(30),
+            replay_limit: 1024 * 1024,
+            protocol: ProtocolOptions::default(),
         }
     }
 
+    /// Set how long the VirtualDom of a session is kept alive after its client disconnects. Defaults to 30 seconds.
+    ///
+    /// A grace period of zero disables sessions: the VirtualDom is dropped as soon as the websocket closes.
+    pub fn with_session_grace_period(mut self, grace_period: Duration) -> Self {
+        self.session_grace_period = grace_period;
+        self
+    }
+
+    /// Set how many bytes of edits each session keeps around to replay to a client that reconnects. Defaults to 1MB.
+    ///
+    /// Edits are dropped once the client acknowledges them or the limit is reached. If a client reconnects after missing
+    /// more edits than the session kept, it receives the whole DOM instead.
+    pub fn with_replay_limit(mut self, bytes: usize) -> Self {
+        self.replay_limit = bytes;
+        self
+    }
+
+    /// Set if clients may switch to the binary protocol. Defaults to true.
+    ///
+    /// The binary protocol batches messages into fewer websocket frames and encodes events as CBOR instead of JSON,
+    /// which helps with high frequency events like `pointermove` or `scroll`. Clients that don't ask for it keep
+    /// using JSON either way.
+    pub fn with_binary_protocol(mut self, enabled: bool) -> Self {
+        self.protocol.binary = enabled;
+        self
+    }
+
+    /// Set the size in bytes above which messages to binary protocol clients are compressed, if the client supports
+    /// compression. `None` disables compression. Defaults to 1KB.
+    pub fn with_compression_threshold(mut self, bytes: Option<usize>) -> Self {
+        self.protocol.compression_threshold = bytes;
+        self
+    }
+
     pub async fn launch(
         &self,
         ws: impl LiveViewSocket,
@@ -53,16 +107,71 @@ impl LiveViewPool {
             .await
     }
 
+    /// Connect a websocket to a VirtualDom. If the client asks to resume a session that is still alive, the socket is
+    /// attached to the existing VirtualDom and `make_app` is never called.
+    ///
+    /// This resolves when the socket closes or is replaced by a newer connection to the same session.
     pub async fn launch_virtualdom<F: FnOnce() -> VirtualDom + Send + 'static>(
         &self,
         ws: impl LiveViewSocket,
         make_app: F,
     ) -> Result<(), LiveViewError> {
-        match self.pool.spawn_pinned(move || run(make_app(), ws)).await {
-            Ok(Ok(_)) => Ok(()),
-            Ok(Err(e)) => Err(e),
-            Err(_) => Err(LiveViewError::SendingFailed),
+        if self.session_grace_period.is_zero() {
+            let protocol = self.protocol;
+            return match self
+                .pool
+                .spawn_pinned(move || run_with_protocol(make_app(), ws, protocol))
+                .await
+            {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err(LiveViewError::SendingFailed),
+            };
         }
+
+        let mut socket: BoxedSocket = Box::pin(ws);
+
+        // The client tells us which session it wants to resume before anything else. Clients that don't start with
+        // an initialize message get a new session that handles their first message like any other
+        let (initialize, first_frame) = match socket.next().await {
+            Some(Ok(message)) => match serde_json::from_slice::<IpcMessage>(&message) {
+                Ok(IpcMessage::Initialize(initialize)) => (initialize, None),
+                _ => (Initialize::default(), Some(message)),
+            },
+            Some(Err(e)) => return Err(e),
+            None => return Ok(()),
+        };
+
+        let protocol = self.protocol.negotiate(&initialize.protocol);
+
+        if let Some(token) = initialize.session {
+            match self
+                .sessions
+                .resume(&token, socket, initialize.acknowledged, protocol)
+            {
+                Ok(closed) => {
+                    return closed.await.unwrap_or(Err(LiveViewError::SendingFailed));
+                }
+                // The session expired
+                Err(rejected) => socket = rejected,
+            }
+        }
+
+        let (token, connections, closed) = self.sessions.create(socket, protocol, first_frame);
+        let sessions = self.sessions.clone();
+        let options = SessionOptions {
+            token,
+            grace_period: self.session_grace_period,
+            replay_limit: self.replay_limit,
+        };
+        // The session outlives this connection, so the task is detached
+        drop(self.pool.spawn_pinned(move || async move {
+            let token = options.token.clone();
+            run_session(make_app(), connections, options).await;
+            sessions.remove(&token);
+        }));
+
+        closed.await.unwrap_or(Err(LiveViewError::SendingFailed))
     }
 }
 
@@ -116,106 +225,202 @@ impl<S> LiveViewSocket for S where
 /// As long as your framework can provide a Sink and Stream of Bytes, you can use this function.
 ///
 /// You might need to transform the error types of the web backend into the LiveView error type.
-pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
-    #[cfg(all(feature = "hot-reload", debug_assertions))]
-    let mut hot_reload_rx = {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        dioxus_hot_reload::connect(move |template| _ = tx.send(template));
-        rx
-    };
-
-    let mut mutations = MutationState::default();
-
-    // Create the a proxy for query engine
-    let (query_tx, mut query_rx) = tokio::sync::mpsc::unbounded_channel();
-    let query_engine = QueryEngine::new(query_tx);
-    vdom.in_runtime(|| {
-        ScopeId::ROOT.provide_context(query_engine.clone());
-        init_eval();
-    });
+///
+/// The VirtualDom is dropped when the socket closes. Use [`LiveViewPool`] to keep the VirtualDom alive while the client
+/// reconnects.
+pub async fn run(vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
+    run_with_protocol(vdom, ws, ProtocolOptions::default()).await
+}
+
+async fn run_with_protocol(
+    vdom: VirtualDom,
+    ws: impl LiveViewSocket,
+    options: ProtocolOptions,
+) -> Result<(), LiveViewError> {
+    let mut runner = DomRunner::new(vdom);
+    // Every client starts with JSON until it asks for something else
+    let mut protocol = Protocol::Json;
 
     // pin the futures so we can use select!
     pin_mut!(ws);
 
-    if let Some(edits) = {
-        vdom.rebuild(&mut mutations);
-        take_edits(&mut mutations)
-    } {
-        // send the initial render to the client
-        ws.send(edits).await?;
+    // the initial render is sent to the client with the first batch
+    let mut outgoing: Vec<_> = runner
+        .rebuild()
+        .map(ServerMessage::Edits)
+        .into_iter()
+        .collect();
+
+    loop {
+        for frame in protocol.encode(std::mem::take(&mut outgoing)) {
+            ws.send(frame).await?;
+        }
+
+        match runner.next_event(Some(&mut ws)).await {
+            RunnerEvent::Message(Some(Ok(frame))) => {
+                for handled in runner.handle_frame(&frame) {
+                    match handled {
+                        // respond with a pong every ping to keep the websocket alive
+                        Handled::Ping => outgoing.push(ServerMessage::Pong),
+                        Handled::Initialize(initialize) => {
+                            // Anything queued before the client asked to switch still uses the old protocol
+                            for frame in protocol.encode(std::mem::take(&mut outgoing)) {
+                                ws.send(frame).await?;
+                            }
+                            protocol = options.negotiate(&initialize.protocol);
+                            ws.send(protocol.announcement()).await?;
+                        }
+                        Handled::Acknowledged(_) => {}
+                    }
+                }
+            }
+            // log this I guess? when would we get an error here?
+            RunnerEvent::Message(Some(Err(_e))) => {}
+            RunnerEvent::Message(None) => return Ok(()),
+            RunnerEvent::Query(query) => outgoing.push(ServerMessage::Query(query)),
+            RunnerEvent::Work => {}
+        }
+
+        outgoing.extend(runner.render().await.map(ServerMessage::Edits));
     }
+}
+
+// desktop uses this wrapper struct thing around the actual event itself
+// this is sorta driven by tao/wry
+#[derive(serde::Deserialize, Debug)]
+#[serde(tag = "method", content = "params")]
+pub(crate) enum IpcMessage {
+    #[serde(rename = "user_event")]
+    Event(HtmlEvent),
+    #[serde(rename = "query")]
+    Query(QueryResult),
+    #[serde(rename = "initialize")]
+    Initialize(#[serde(default)] Initialize),
+    #[serde(rename = "acknowledge")]
+    Acknowledge(Acknowledge),
+}
+
+/// The first message the client sends after connecting
+#[derive(serde::Deserialize, Debug, Default)]
+#[serde(default)]
+pub(crate) struct Initialize {
+    /// The token of the session the client wants to resume
+    pub(crate) session: Option<String>,
+    /// The number of edits the client applied in that session
+    pub(crate) acknowledged: u64,
+    /// The protocol the client wants to switch to
+    pub(crate) protocol: ProtocolRequest,
+}
+
+/// Sent by the client to tell the server how many edits it has applied
+#[derive(serde::Deserialize, Debug)]
+pub(crate) struct Acknowledge {
+    pub(crate) edits: u64,
+}
 
-    // desktop uses this wrapper struct thing around the actual event itself
-    // this is sorta driven by tao/wry
-    #[derive(serde::Deserialize, Debug)]
-    #[serde(tag = "method", content = "params")]
-    enum IpcMessage {
-        #[serde(rename = "user_event")]
-        Event(HtmlEvent),
-        #[serde(rename = "query")]
-        Query(QueryResult),
+/// A message from the client the runner could not handle on its own
+pub(crate) enum Handled {
+    /// The client sent a ping and is waiting for a pong
+    Ping,
+    /// The client applied this many edits
+    Acknowledged(u64),
+    /// The client (re)connected
+    Initialize(Initialize),
+}
+
+/// Something the runner needs to handle
+pub(crate) enum RunnerEvent {
+    /// A message from the client, or `None` if the socket closed
+    Message(Option<Result<Vec<u8>, LiveViewError>>),
+    /// A query that needs to be sent to the client
+    Query(String),
+    /// The VirtualDom has work to do
+    Work,
+}
+
+/// Drives a VirtualDom and turns its mutations into the edits that are sent to the client
+pub(crate) struct DomRunner {
+    vdom: VirtualDom,
+    mutations: MutationState,
+    query_engine: QueryEngine,
+    query_rx: tokio::sync::mpsc::UnboundedReceiver<String>,
+    #[cfg(all(feature = "hot-reload", debug_assertions))]
+    hot_reload_rx: tokio::sync::mpsc::UnboundedReceiver<DevserverMsg>,
+}
+
+impl DomRunner {
+    pub(crate) fn new(vdom: VirtualDom) -> Self {
+        #[cfg(all(feature = "hot-reload", debug_assertions))]
+        let hot_reload_rx = {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            dioxus_hot_reload::connect(move |template| _ = tx.send(template));
+            rx
+        };
+
+        // Create the a proxy for query engine
+        let (query_tx, query_rx) = tokio::sync::mpsc::unbounded_channel();
+        let query_engine = QueryEngine::new(query_tx);
+        vdom.in_runtime(|| {
+            ScopeId::ROOT.provide_context(query_engine.clone());
+            init_eval();
+        });
+
+        Self {
+            vdom,
+            mutations: MutationState::default(),
+            query_engine,
+            query_rx,
+            #[cfg(all(feature = "hot-reload", debug_assertions))]
+            hot_reload_rx,
+        }
     }
 
-    loop {
+    /// Build the VirtualDom and return the edits for the initial render
+    pub(crate) fn rebuild(&mut self) -> Option<Vec<u8>> {
+        self.vdom.rebuild(&mut self.mutations);
+        take_edits(&mut self.mutations)
+    }
+
+    /// Return the edits that build the current DOM for a client that starts from scratch
+    pub(crate) fn replay(&mut self) -> Vec<u8> {
+        // The client forgets its templates with the rest of its DOM, so they are registered again with new ids
+        self.mutations = MutationState::default();
+        self.vdom.replay(&mut self.mutations);
+        take_edits(&mut self.mutations).unwrap_or_default()
+    }
+
+    /// Wait for the next message from the socket, query or work in the VirtualDom. Without a socket, only the
+    /// VirtualDom is polled.
+    pub(crate) async fn next_event<S>(&mut self, socket: Option<&mut S>) -> RunnerEvent
+    where
+        S: Stream<Item = Result<Vec<u8>, LiveViewError>> + Unpin,
+    {
         #[cfg(all(feature = "hot-reload", debug_assertions))]
-        let hot_reload_wait = hot_reload_rx.recv();
+        let hot_reload_wait = self.hot_reload_rx.recv();
         #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
         let hot_reload_wait: std::future::Pending<Option<()>> = std::future::pending();
 
+        let message = async {
+            match socket {
+                Some(socket) => socket.next().await,
+                None => std::future::pending().await,
+            }
+        };
+
         tokio::select! {
             // poll any futures or suspense
-            _ = vdom.wait_for_work() => {}
+            _ = self.vdom.wait_for_work() => RunnerEvent::Work,
 
-            evt = ws.next() => {
-                match evt.as_ref().map(|o| o.as_deref()) {
-                    // respond with a pong every ping to keep the websocket alive
-                    Some(Ok(b"__ping__")) => {
-                        ws.send(text_frame("__pong__")).await?;
-                    }
-                    Some(Ok(evt)) => {
-                        if let Ok(message) = serde_json::from_str::<IpcMessage>(&String::from_utf8_lossy(evt)) {
-                            match message {
-                                IpcMessage::Event(evt) => {
-                                    // Intercept the mounted event and insert a custom element type
-                                    if let EventData::Mounted = &evt.data {
-                                        let element = LiveviewElement::new(evt.element, query_engine.clone());
-                                        vdom.handle_event(
-                                            &evt.name,
-                                            Rc::new(PlatformEventData::new(Box::new(element))),
-                                            evt.element,
-                                            evt.bubbles,
-                                        );
-                                    } else {
-                                        vdom.handle_event(
-                                            &evt.name,
-                                            evt.data.into_any(),
-                                            evt.element,
-                                            evt.bubbles,
-                                        );
-                                    }
-                                }
-                                IpcMessage::Query(result) => {
-                                    query_engine.send(result);
-                                },
-                            }
-                        }
-                    }
-                    // log this I guess? when would we get an error here?
-                    Some(Err(_e)) => {}
-                    None => return Ok(()),
-                }
-            }
+            message = message => RunnerEvent::Message(message),
 
             // handle any new queries
-            Some(query) = query_rx.recv() => {
-                ws.send(text_frame(&serde_json::to_string(&ClientUpdate::Query(query)).unwrap())).await?;
-            }
+            Some(query) = self.query_rx.recv() => RunnerEvent::Query(query),
 
             Some(msg) = hot_reload_wait => {
                 #[cfg(all(feature = "hot-reload", debug_assertions))]
                 match msg{
                     DevserverMsg::HotReload(msg)=> {
-                        dioxus_hot_reload::apply_changes(&mut vdom, &msg);
+                        dioxus_hot_reload::apply_changes(&mut self.vdom, &msg);
                     }
                     DevserverMsg::Shutdown => {
                         std::process::exit(0);
@@ -229,40 +434,94 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
                 }
                 #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
                 let () = msg;
+                RunnerEvent::Work
+            }
+        }
+    }
+
+    /// Handle a frame from the client and return the messages the caller needs to respond to
+    pub(crate) fn handle_frame(&mut self, frame: &[u8]) -> Vec<Handled> {
+        let mut handled = Vec::new();
+        for message in protocol::decode(frame) {
+            let message = match message {
+                ClientMessage::Ping => {
+                    handled.push(Handled::Ping);
+                    continue;
+                }
+                ClientMessage::Ipc(message) => message,
+            };
+
+            match message {
+                IpcMessage::Event(evt) => {
+                    // Intercept the mounted event and insert a custom element type
+                    if let EventData::Mounted = &evt.data {
+                        let element = LiveviewElement::new(evt.element, self.query_engine.clone());
+                        self.vdom.handle_event(
+                            &evt.name,
+                            Rc::new(PlatformEventData::new(Box::new(element))),
+                            evt.element,
+                            evt.bubbles,
+                        );
+                    } else {
+                        self.vdom.handle_event(
+                            &evt.name,
+                            evt.data.into_any(),
+                            evt.element,
+                            evt.bubbles,
+                        );
+                    }
+                }
+                IpcMessage::Query(result) => {
+                    self.query_engine.send(result);
+                }
+                IpcMessage::Acknowledge(Acknowledge { edits }) => {
+                    handled.push(Handled::Acknowledged(edits));
+                }
+                IpcMessage::Initialize(initialize) => {
+                    handled.push(Handled::Initialize(initialize));
+                }
             }
         }
+        handled
+    }
 
+    /// Render the VirtualDom and return the edits, if anything changed
+    pub(crate) async fn render(&mut self) -> Option<Vec<u8>> {
         // wait for suspense to resolve in a 10ms window
         tokio::select! {
             _ = tokio::time::sleep(Duration::from_millis(10)) => {}
-            _ = vdom.wait_for_suspense() => {}
+            _ = self.vdom.wait_for_suspense() => {}
         }
 
         // render the vdom
-        vdom.render_immediate(&mut mutations);
+        self.vdom.render_immediate(&mut self.mutations);
 
-        if let Some(edits) = take_edits(&mut mutations) {
-            ws.send(edits).await?;
-        }
+        take_edits(&mut self.mutations)
     }
 }
 
-fn text_frame(text: &str) -> Vec<u8> {
-    let mut bytes = vec![0];
-    bytes.extend(text.as_bytes());
-    bytes
-}
-
 fn take_edits(mutations: &mut MutationState) -> Option<Vec<u8>> {
-    // Add an extra one at the beginning to tell the shim this is a binary frame
-    let mut bytes = vec![1];
+    let mut bytes = Vec::new();
     mutations.write_memory_into(&mut bytes);
-    (bytes.len() > 1).then_some(bytes)
+    (!bytes.is_empty()).then_some(bytes)
 }
 
 #[derive(Serialize)]
 #[serde(tag = "type", content = "data")]
-enum ClientUpdate {
+pub(crate) enum ClientUpdate {
     #[serde(rename = "query")]
     Query(String),
+    /// Tells the client which session it is connected to, and if it resumed the session it asked for
+    #[serde(rename = "session")]
+    Session { token: String, resumed: bool },
+    /// Tells the client to clear its DOM because the next edits build it from scratch. Once it applied them, the
+    /// client has applied `edits` edits of the session.
+    #[serde(rename = "rebuild")]
+    Rebuild { edits: u64 },
+    /// Tells the client which protocol to use from now on
+    #[serde(rename = "protocol")]
+    Protocol {
+        binary: bool,
+        compression: Option<&'static str>,
+    },
 }
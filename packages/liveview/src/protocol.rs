@@ -0,0 +1,453 @@
+//! How messages are framed on the websocket
+//!
+//! Clients start with the JSON protocol where every websocket message holds one message: text prefixed with `0`, or
+//! edits prefixed with `1`. A client can ask for the binary protocol in its `initialize` message. Once the server
+//! accepts, both sides batch their messages into frames that start with `2`, followed by any number of messages:
+//!
+//! ```text
+//! [kind: u8] [flags: u8] [length: u32 little endian] [payload: length bytes]
+//! ```
+//!
+//! Events and query results from the client are encoded as CBOR. Payloads with the [`COMPRESSED`] flag are compressed
+//! with raw deflate.
+
+use crate::pool::{ClientUpdate, IpcMessage};
+use serde::Deserialize;
+
+/// The first byte of a frame in the binary protocol
+const BATCH: u8 = 2;
+
+/// A UTF-8 text message
+const TEXT: u8 = 0;
+/// Edits for the interpreter
+const EDITS: u8 = 1;
+/// A script the client evaluates for a query
+const QUERY: u8 = 2;
+/// A CBOR encoded message from the client
+const CBOR: u8 = 3;
+
+/// The payload is compressed with raw deflate
+const COMPRESSED: u8 = 1;
+
+/// The only compression format the server supports. This is the name the browser's `DecompressionStream` uses.
+const DEFLATE_RAW: &str = "deflate-raw";
+
+/// The largest payload the server will inflate from a client
+const MAX_INFLATED_SIZE: usize = 16 * 1024 * 1024;
+
+/// A message for the client
+pub(crate) enum ServerMessage {
+    Edits(Vec<u8>),
+    Query(String),
+    Update(ClientUpdate),
+    Pong,
+}
+
+/// A message from the client
+pub(crate) enum ClientMessage {
+    Ping,
+    Ipc(IpcMessage),
+}
+
+/// The protocol a client asks for when it connects
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub(crate) struct ProtocolRequest {
+    binary: bool,
+    /// The compression formats the client can decompress
+    compression: Vec<String>,
+}
+
+/// The protocols the server offers to clients
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ProtocolOptions {
+    pub(crate) binary: bool,
+    pub(crate) compression_threshold: Option<usize>,
+}
+
+impl Default for ProtocolOptions {
+    fn default() -> Self {
+        Self {
+            binary: true,
+            compression_threshold: Some(1024),
+        }
+    }
+}
+
+impl ProtocolOptions {
+    /// Pick the protocol for a client
+    pub(crate) fn negotiate(&self, request: &ProtocolRequest) -> Protocol {
+        if !self.binary || !request.binary {
+            return Protocol::Json;
+        }
+
+        let compress_above = self.compression_threshold.filter(|_| {
+            request
+                .compression
+                .iter()
+                .any(|format| format == DEFLATE_RAW)
+        });
+        Protocol::Binary { compress_above }
+    }
+}
+
+/// The protocol used to talk to a client
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    #[default]
+    Json,
+    Binary {
+        /// Payloads larger than this are compressed
+        compress_above: Option<usize>,
+    },
+}
+
+impl Protocol {
+    /// The frame that tells the client which protocol the server picked. It is always sent with the JSON protocol
+    /// because the client keeps using JSON until it arrives.
+    pub(crate) fn announcement(&self) -> Vec<u8> {
+        let (binary, compression) = match self {
+            Protocol::Json => (false, None),
+            Protocol::Binary { compress_above } => (true, compress_above.map(|_| DEFLATE_RAW)),
+        };
+        let update = ClientUpdate::Protocol {
+            binary,
+            compression,
+        };
+        text_frame(&serde_json::to_string(&update).unwrap())
+    }
+
+    /// Encode messages into websocket frames. The binary protocol batches all of the messages into one frame.
+    pub(crate) fn encode(&self, messages: Vec<ServerMessage>) -> Vec<Vec<u8>> {
+        match self {
+            Protocol::Json => messages.into_iter().map(encode_json).collect(),
+            Protocol::Binary { .. } if messages.is_empty() => Vec::new(),
+            Protocol::Binary { compress_above } => {
+                let mut frame = vec![BATCH];
+                for message in messages {
+                    let (kind, payload) = match message {
+                        ServerMessage::Edits(edits) => (EDITS, edits),
+                        ServerMessage::Query(query) => (QUERY, query.into_bytes()),
+                        ServerMessage::Update(update) => {
+                            (TEXT, serde_json::to_vec(&update).unwrap())
+                        }
+                        ServerMessage::Pong => (TEXT, b"__pong__".to_vec()),
+                    };
+                    let compressed = match compress_above {
+                        Some(threshold) if payload.len() > *threshold => {
+                            Some(miniz_oxide::deflate::compress_to_vec(&payload, 6))
+                                .filter(|compressed| compressed.len() < payload.len())
+                        }
+                        _ => None,
+                    };
+                    match compressed {
+                        Some(compressed) => push_message(&mut frame, kind, COMPRESSED, &compressed),
+                        None => push_message(&mut frame, kind, 0, &payload),
+                    }
+                }
+                vec![frame]
+            }
+        }
+    }
+}
+
+fn encode_json(message: ServerMessage) -> Vec<u8> {
+    match message {
+        ServerMessage::Edits(edits) => {
+            // Add an extra one at the beginning to tell the shim this is a binary frame
+            let mut bytes = Vec::with_capacity(edits.len() + 1);
+            bytes.push(EDITS);
+            bytes.extend(edits);
+            bytes
+        }
+        ServerMessage::Query(query) => {
+            text_frame(&serde_json::to_string(&ClientUpdate::Query(query)).unwrap())
+        }
+        ServerMessage::Update(update) => text_frame(&serde_json::to_string(&update).unwrap()),
+        ServerMessage::Pong => text_frame("__pong__"),
+    }
+}
+
+fn text_frame(text: &str) -> Vec<u8> {
+    let mut bytes = vec![TEXT];
+    bytes.extend(text.as_bytes());
+    bytes
+}
+
+fn push_message(frame: &mut Vec<u8>, kind: u8, flags: u8, payload: &[u8]) {
+    frame.push(kind);
+    frame.push(flags);
+    frame.extend((payload.len() as u32).to_le_bytes());
+    frame.extend(payload);
+}
+
+/// Decode a frame from the client in either protocol. Messages that can't be decoded are skipped.
+pub(crate) fn decode(frame: &[u8]) -> Vec<ClientMessage> {
+    let Some(mut rest) = frame.strip_prefix(&[BATCH]) else {
+        return decode_text(frame).into_iter().collect();
+    };
+
+    let mut messages = Vec::new();
+    while let [kind, flags, a, b, c, d, tail @ ..] = rest {
+        let len = u32::from_le_bytes([*a, *b, *c, *d]) as usize;
+        if tail.len() < len {
+            tracing::error!("Truncated message in LiveView frame");
+            break;
+        }
+        let (payload, tail) = tail.split_at(len);
+        rest = tail;
+
+        let inflated;
+        let payload = if flags & COMPRESSED != 0 {
+            match miniz_oxide::inflate::decompress_to_vec_with_limit(payload, MAX_INFLATED_SIZE) {
+                Ok(bytes) => {
+                    inflated = bytes;
+                    &inflated
+                }
+                Err(err) => {
+                    tracing::error!("Failed to inflate LiveView message: {err:?}");
+                    continue;
+                }
+            }
+        } else {
+            payload
+        };
+
+        let message = match *kind {
+            TEXT => decode_text(payload),
+            CBOR => match ciborium::from_reader(payload) {
+                Ok(message) => Some(ClientMessage::Ipc(message)),
+                Err(err) => {
+                    tracing::error!("Failed to decode LiveView message: {err}");
+                    None
+                }
+            },
+            _ => None,
+        };
+        messages.extend(message);
+    }
+    messages
+}
+
+/// Decode a JSON message or ping from the client
+fn decode_text(text: &[u8]) -> Option<ClientMessage> {
+    if text == b"__ping__" {
+        return Some(ClientMessage::Ping);
+    }
+    serde_json::from_slice(text).ok().map(ClientMessage::Ipc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::Acknowledge;
+
+    fn cbor(message: serde_json::Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&message, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn acknowledge(edits: u64) -> Vec<u8> {
+        cbor(serde_json::json!({ "method": "acknowledge", "params": { "edits": edits } }))
+    }
+
+    fn batch(messages: &[(u8, u8, &[u8])]) -> Vec<u8> {
+        let mut frame = vec![BATCH];
+        for (kind, flags, payload) in messages {
+            push_message(&mut frame, *kind, *flags, payload);
+        }
+        frame
+    }
+
+    /// Split a frame in the binary protocol into its messages
+    fn split(frame: &[u8]) -> Vec<(u8, u8, Vec<u8>)> {
+        let mut rest = frame.strip_prefix(&[BATCH]).unwrap();
+        let mut messages = Vec::new();
+        while let [kind, flags, a, b, c, d, tail @ ..] = rest {
+            let len = u32::from_le_bytes([*a, *b, *c, *d]) as usize;
+            let (payload, tail) = tail.split_at(len);
+            messages.push((*kind, *flags, payload.to_vec()));
+            rest = tail;
+        }
+        assert!(rest.is_empty());
+        messages
+    }
+
+    fn request(binary: bool, compression: &[&str]) -> ProtocolRequest {
+        ProtocolRequest {
+            binary,
+            compression: compression
+                .iter()
+                .map(|format| format.to_string())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn negotiation() {
+        let options = ProtocolOptions::default();
+        assert_eq!(
+            options.negotiate(&request(true, &[DEFLATE_RAW])),
+            Protocol::Binary {
+                compress_above: Some(1024)
+            }
+        );
+        // Payloads are only compressed if the client can decompress them
+        assert_eq!(
+            options.negotiate(&request(true, &["gzip"])),
+            Protocol::Binary {
+                compress_above: None
+            }
+        );
+        assert_eq!(
+            options.negotiate(&request(false, &[DEFLATE_RAW])),
+            Protocol::Json
+        );
+
+        let json_only = ProtocolOptions {
+            binary: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            json_only.negotiate(&request(true, &[DEFLATE_RAW])),
+            Protocol::Json
+        );
+        let uncompressed = ProtocolOptions {
+            compression_threshold: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            uncompressed.negotiate(&request(true, &[DEFLATE_RAW])),
+            Protocol::Binary {
+                compress_above: None
+            }
+        );
+    }
+
+    #[test]
+    fn json_sends_one_frame_per_message() {
+        let frames = Protocol::Json.encode(vec![
+            ServerMessage::Edits(vec![7, 8]),
+            ServerMessage::Pong,
+            ServerMessage::Query("1 + 1".to_string()),
+        ]);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0], [EDITS, 7, 8]);
+        assert_eq!(frames[1], b"\0__pong__");
+        assert_eq!(frames[2], b"\0{\"type\":\"query\",\"data\":\"1 + 1\"}");
+    }
+
+    #[test]
+    fn binary_batches_messages() {
+        let protocol = Protocol::Binary {
+            compress_above: None,
+        };
+        assert!(protocol.encode(Vec::new()).is_empty());
+
+        let frames = protocol.encode(vec![
+            ServerMessage::Edits(vec![7, 8]),
+            ServerMessage::Pong,
+            ServerMessage::Query("1 + 1".to_string()),
+        ]);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            split(&frames[0]),
+            [
+                (EDITS, 0, vec![7, 8]),
+                (TEXT, 0, b"__pong__".to_vec()),
+                (QUERY, 0, b"1 + 1".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_compresses_large_payloads() {
+        let protocol = Protocol::Binary {
+            compress_above: Some(16),
+        };
+        let large = vec![1; 1024];
+        let frames = protocol.encode(vec![
+            ServerMessage::Edits(vec![1; 16]),
+            ServerMessage::Edits(large.clone()),
+        ]);
+        let messages = split(&frames[0]);
+        assert_eq!(messages[0], (EDITS, 0, vec![1; 16]));
+        let (kind, flags, payload) = &messages[1];
+        assert_eq!((*kind, *flags), (EDITS, COMPRESSED));
+        assert!(payload.len() < large.len());
+        assert_eq!(
+            miniz_oxide::inflate::decompress_to_vec(payload).unwrap(),
+            large
+        );
+    }
+
+    #[test]
+    fn decodes_json_frames() {
+        assert!(matches!(decode(b"__ping__")[..], [ClientMessage::Ping]));
+        assert!(matches!(
+            decode(br#"{"method":"acknowledge","params":{"edits":3}}"#)[..],
+            [ClientMessage::Ipc(IpcMessage::Acknowledge(Acknowledge {
+                edits: 3
+            }))]
+        ));
+        assert!(decode(b"not json").is_empty());
+    }
+
+    #[test]
+    fn decodes_batched_frames() {
+        let frame = batch(&[
+            (TEXT, 0, b"__ping__"),
+            (CBOR, 0, &acknowledge(3)),
+            // Unknown kinds are skipped
+            (42, 0, b"?"),
+            (CBOR, 0, &acknowledge(5)),
+        ]);
+        assert!(matches!(
+            decode(&frame)[..],
+            [
+                ClientMessage::Ping,
+                ClientMessage::Ipc(IpcMessage::Acknowledge(Acknowledge { edits: 3 })),
+                ClientMessage::Ipc(IpcMessage::Acknowledge(Acknowledge { edits: 5 })),
+            ]
+        ));
+    }
+
+    #[test]
+    fn truncated_messages_end_the_frame() {
+        let mut frame = batch(&[(TEXT, 0, b"__ping__"), (CBOR, 0, &acknowledge(3))]);
+        frame.pop();
+        assert!(matches!(decode(&frame)[..], [ClientMessage::Ping]));
+
+        // A header without the full length is ignored too
+        let frame = [&batch(&[(TEXT, 0, b"__ping__")])[..], &[CBOR, 0, 1]].concat();
+        assert!(matches!(decode(&frame)[..], [ClientMessage::Ping]));
+    }
+
+    #[test]
+    fn decodes_compressed_messages() {
+        let compressed = miniz_oxide::deflate::compress_to_vec(&acknowledge(3), 6);
+        let frame = batch(&[
+            (CBOR, COMPRESSED, &compressed),
+            // Payloads that aren't valid deflate are skipped
+            (CBOR, COMPRESSED, b"not deflate"),
+            (TEXT, 0, b"__ping__"),
+        ]);
+        assert!(matches!(
+            decode(&frame)[..],
+            [
+                ClientMessage::Ipc(IpcMessage::Acknowledge(Acknowledge { edits: 3 })),
+                ClientMessage::Ping,
+            ]
+        ));
+    }
+
+    #[test]
+    fn inflating_is_limited() {
+        // A tiny payload that would inflate to more than the limit
+        let bomb = miniz_oxide::deflate::compress_to_vec(&vec![0; MAX_INFLATED_SIZE + 1], 1);
+        assert!(bomb.len() < 1024 * 1024);
+        let frame = batch(&[(TEXT, COMPRESSED, &bomb), (TEXT, 0, b"__ping__")]);
+        assert!(matches!(decode(&frame)[..], [ClientMessage::Ping]));
+    }
+}
@@ -0,0 +1,617 @@
+//! Sessions keep the VirtualDom of a client alive while its websocket reconnects
+
+use crate::{
+    pool::{ClientUpdate, DomRunner, Handled, RunnerEvent},
+    protocol::{Protocol, ServerMessage},
+    LiveViewError,
+};
+use dioxus_core::VirtualDom;
+use futures_channel::{mpsc, oneshot};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// A socket with the type erased so it can be moved into a running session
+pub(crate) trait Socket:
+    Sink<Vec<u8>, Error = LiveViewError> + Stream<Item = Result<Vec<u8>, LiveViewError>> + Send
+{
+}
+
+impl<S> Socket for S where
+    S: Sink<Vec<u8>, Error = LiveViewError> + Stream<Item = Result<Vec<u8>, LiveViewError>> + Send
+{
+}
+
+pub(crate) type BoxedSocket = Pin<Box<dyn Socket>>;
+
+/// A socket that wants to attach to a session
+pub(crate) struct Connection {
+    socket: BoxedSocket,
+    /// The number of edits the client already applied in this session
+    acknowledged: u64,
+    /// If the client asked for this session, or the session was just created for it
+    resumed: bool,
+    protocol: Protocol,
+    /// A frame the client sent instead of initializing the connection
+    first_frame: Option<Vec<u8>>,
+    /// Resolves when the socket closes or is replaced by a newer connection
+    closed: oneshot::Sender<Result<(), LiveViewError>>,
+}
+
+/// The sessions that are alive, by token
+#[derive(Clone, Default)]
+pub(crate) struct Sessions {
+    sessions: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Connection>>>>,
+}
+
+impl Sessions {
+    /// Attach a socket to an existing session. Returns the socket if the session is gone.
+    pub(crate) fn resume(
+        &self,
+        token: &str,
+        socket: BoxedSocket,
+        acknowledged: u64,
+        protocol: Protocol,
+    ) -> Result<oneshot::Receiver<Result<(), LiveViewError>>, BoxedSocket> {
+        let (closed, on_closed) = oneshot::channel();
+        let connection = Connection {
+            socket,
+            acknowledged,
+            resumed: true,
+            protocol,
+            first_frame: None,
+            closed,
+        };
+        let session = self.sessions.lock().unwrap().get(token).cloned();
+        match session {
+            Some(session) => match session.unbounded_send(connection) {
+                Ok(()) => Ok(on_closed),
+                Err(err) => Err(err.into_inner().socket),
+            },
+            None => Err(connection.socket),
+        }
+    }
+
+    /// Create a new session with the socket as its first connection
+    pub(crate) fn create(
+        &self,
+        socket: BoxedSocket,
+        protocol: Protocol,
+        first_frame: Option<Vec<u8>>,
+    ) -> (
+        String,
+        mpsc::UnboundedReceiver<Connection>,
+        oneshot::Receiver<Result<(), LiveViewError>>,
+    ) {
+        // The token is all a client needs to take over a session, so it must not be guessable
+        let token = uuid::Uuid::new_v4().to_string();
+        let (connections_tx, connections) = mpsc::unbounded();
+        let (closed, on_closed) = oneshot::channel();
+        connections_tx
+            .unbounded_send(Connection {
+                socket,
+                acknowledged: 0,
+                resumed: false,
+                protocol,
+                first_frame,
+                closed,
+            })
+            .unwrap();
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(token.clone(), connections_tx);
+        (token, connections, on_closed)
+    }
+
+    pub(crate) fn remove(&self, token: &str) {
+        self.sessions.lock().unwrap().remove(token);
+    }
+}
+
+pub(crate) struct SessionOptions {
+    pub(crate) token: String,
+    pub(crate) grace_period: Duration,
+    pub(crate) replay_limit: usize,
+}
+
+/// Run a VirtualDom for a session until no client has been connected for the grace period
+pub(crate) async fn run_session(
+    vdom: VirtualDom,
+    mut connections: mpsc::UnboundedReceiver<Connection>,
+    options: SessionOptions,
+) {
+    let mut runner = DomRunner::new(vdom);
+    let mut history = EditHistory::new(options.replay_limit);
+    let mut client = Client::new(options.grace_period);
+
+    // The session is created for its first connection, which builds its DOM from the initial edits
+    let Some(first) = connections.next().await else {
+        return;
+    };
+    let initial = runner.rebuild().unwrap_or_default();
+    history.push(initial.clone());
+    let first_frame = first.first_frame.clone();
+    client
+        .attach(
+            first,
+            &options.token,
+            CatchUp::Rebuild(initial, history.next()),
+        )
+        .await;
+    if let Some(frame) = first_frame {
+        handle_frame(&mut runner, &mut client, &mut history, &frame);
+    }
+    client.flush().await;
+
+    loop {
+        let deadline = client.deadline;
+        let expired = async move {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            event = runner.next_event(client.socket()) => match event {
+                RunnerEvent::Message(Some(Ok(frame))) => {
+                    handle_frame(&mut runner, &mut client, &mut history, &frame);
+                }
+                RunnerEvent::Message(Some(Err(_e))) => {}
+                RunnerEvent::Message(None) => client.disconnect(Ok(())),
+                RunnerEvent::Query(query) => client.query(query),
+                RunnerEvent::Work => {}
+            },
+
+            Some(connection) = connections.next() => {
+                history.acknowledge(connection.acknowledged);
+                // If some of the edits the client missed were already dropped, it rebuilds its DOM from the live
+                // VirtualDom instead
+                let catch_up = match history.since(connection.acknowledged) {
+                    Some(missed) => CatchUp::Replay(missed.cloned().collect()),
+                    None => CatchUp::Rebuild(runner.replay(), history.next()),
+                };
+
+                // A newer connection always replaces the current one. Mobile clients often reconnect before the
+                // server notices that the old socket is dead
+                client.attach(connection, &options.token, catch_up).await;
+            }
+
+            _ = expired => return,
+        }
+
+        // Keep rendering while the client is gone so it can catch up when it reconnects
+        if let Some(edits) = runner.render().await {
+            client.queue(ServerMessage::Edits(edits.clone()));
+            history.push(edits);
+        }
+
+        client.flush().await;
+    }
+}
+
+fn handle_frame(
+    runner: &mut DomRunner,
+    client: &mut Client,
+    history: &mut EditHistory,
+    frame: &[u8],
+) {
+    for handled in runner.handle_frame(frame) {
+        match handled {
+            // respond with a pong every ping to keep the websocket alive
+            Handled::Ping => client.queue(ServerMessage::Pong),
+            Handled::Acknowledged(edits) => history.acknowledge(edits),
+            // The protocol was negotiated when the client connected
+            Handled::Initialize(_) => {}
+        }
+    }
+}
+
+/// How a client that connects to a session catches up with it
+enum CatchUp {
+    /// Apply the edits the client missed to its current DOM
+    Replay(Vec<Vec<u8>>),
+    /// Replace the DOM of the client with these edits, after which the client applied this many edits of the session
+    Rebuild(Vec<u8>, u64),
+}
+
+/// The client connected to a session
+struct Client {
+    connected: Option<Connected>,
+    /// When the session ends if no client connects
+    deadline: Option<Instant>,
+    grace_period: Duration,
+    /// Messages that are sent together at the end of the current iteration
+    outgoing: Vec<ServerMessage>,
+    /// Queries that were created while no client was connected
+    queued_queries: Vec<String>,
+}
+
+struct Connected {
+    socket: BoxedSocket,
+    closed: oneshot::Sender<Result<(), LiveViewError>>,
+    protocol: Protocol,
+}
+
+impl Client {
+    fn new(grace_period: Duration) -> Self {
+        Self {
+            connected: None,
+            deadline: Some(Instant::now() + grace_period),
+            grace_period,
+            outgoing: Vec::new(),
+            queued_queries: Vec::new(),
+        }
+    }
+
+    fn socket(&mut self) -> Option<&mut BoxedSocket> {
+        self.connected
+            .as_mut()
+            .map(|connected| &mut connected.socket)
+    }
+
+    async fn connect(
+        &mut self,
+        socket: BoxedSocket,
+        closed: oneshot::Sender<Result<(), LiveViewError>>,
+        protocol: Protocol,
+    ) {
+        self.disconnect(Ok(()));
+        self.deadline = None;
+        self.connected = Some(Connected {
+            socket,
+            closed,
+            protocol,
+        });
+        self.send(protocol.announcement()).await;
+    }
+
+    /// Connect a client and queue the edits it needs to catch up
+    async fn attach(&mut self, connection: Connection, token: &str, catch_up: CatchUp) {
+        self.connect(connection.socket, connection.closed, connection.protocol)
+            .await;
+        self.queue(ServerMessage::Update(ClientUpdate::Session {
+            token: token.to_string(),
+            resumed: connection.resumed,
+        }));
+        match catch_up {
+            CatchUp::Replay(missed) => {
+                for edits in missed {
+                    self.queue(ServerMessage::Edits(edits));
+                }
+            }
+            CatchUp::Rebuild(dom, edits) => {
+                self.queue(ServerMessage::Update(ClientUpdate::Rebuild { edits }));
+                self.queue(ServerMessage::Edits(dom));
+            }
+        }
+        for query in std::mem::take(&mut self.queued_queries) {
+            self.query(query);
+        }
+    }
+
+    fn disconnect(&mut self, result: Result<(), LiveViewError>) {
+        if let Some(connected) = self.connected.take() {
+            self.outgoing.clear();
+            _ = connected.closed.send(result);
+            self.deadline = Some(Instant::now() + self.grace_period);
+        }
+    }
+
+    /// Queue a message for the client. Messages are dropped while no client is connected.
+    fn queue(&mut self, message: ServerMessage) {
+        if self.connected.is_some() {
+            self.outgoing.push(message);
+        }
+    }
+
+    fn query(&mut self, query: String) {
+        match self.connected {
+            Some(_) => self.outgoing.push(ServerMessage::Query(query)),
+            None => self.queued_queries.push(query),
+        }
+    }
+
+    /// Send all queued messages to the client
+    async fn flush(&mut self) {
+        let Some(connected) = &self.connected else {
+            return;
+        };
+        for frame in connected
+            .protocol
+            .encode(std::mem::take(&mut self.outgoing))
+        {
+            self.send(frame).await;
+        }
+    }
+
+    async fn send(&mut self, frame: Vec<u8>) {
+        if let Some(connected) = &mut self.connected {
+            if let Err(err) = connected.socket.send(frame).await {
+                self.disconnect(Err(err));
+            }
+        }
+    }
+}
+
+/// The edits of a session that a reconnecting client might have missed
+///
+/// Edits are counted from the start of the session. Edits the client acknowledged are never replayed, so they are
+/// dropped right away. The oldest edits are dropped once the history grows past its limit even if the client never
+/// acknowledged them, so a client can only resume from an edit that is still in the history.
+struct EditHistory {
+    edits: VecDeque<Vec<u8>>,
+    /// The number of the first edit in `edits`
+    first: u64,
+    acknowledged: u64,
+    bytes: usize,
+    limit: usize,
+}
+
+impl EditHistory {
+    fn new(limit: usize) -> Self {
+        Self {
+            edits: VecDeque::new(),
+            first: 0,
+            acknowledged: 0,
+            bytes: 0,
+            limit,
+        }
+    }
+
+    /// The number of the next edit
+    fn next(&self) -> u64 {
+        self.first + self.edits.len() as u64
+    }
+
+    fn push(&mut self, edits: Vec<u8>) {
+        self.bytes += edits.len();
+        self.edits.push_back(edits);
+        self.trim();
+    }
+
+    fn acknowledge(&mut self, edits: u64) {
+        self.acknowledged = self.acknowledged.max(edits.min(self.next()));
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.first < self.acknowledged || self.bytes > self.limit {
+            let Some(edits) = self.edits.pop_front() else {
+                break;
+            };
+            self.bytes -= edits.len();
+            self.first += 1;
+        }
+    }
+
+    /// Get the edits after the first `applied` edits, or `None` if some of them were already dropped
+    fn since(&self, applied: u64) -> Option<impl Iterator<Item = &Vec<u8>>> {
+        (self.first..=self.next())
+            .contains(&applied)
+            .then(|| self.edits.range((applied - self.first) as usize..))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LiveViewPool;
+    use dioxus::prelude::*;
+    use std::task::{Context, Poll};
+
+    fn replay(history: &EditHistory, applied: u64) -> Option<Vec<Vec<u8>>> {
+        history.since(applied).map(|edits| edits.cloned().collect())
+    }
+
+    #[test]
+    fn history_replays_missed_edits() {
+        let mut history = EditHistory::new(1024);
+        history.push(vec![0]);
+        history.push(vec![1]);
+        history.push(vec![2]);
+
+        assert_eq!(replay(&history, 0), Some(vec![vec![0], vec![1], vec![2]]));
+        assert_eq!(replay(&history, 2), Some(vec![vec![2]]));
+        assert_eq!(replay(&history, 3), Some(vec![]));
+        // The client can't have applied edits that were never sent
+        assert_eq!(replay(&history, 4), None);
+    }
+
+    #[test]
+    fn history_drops_acknowledged_edits() {
+        let mut history = EditHistory::new(1024);
+        history.push(vec![0]);
+        history.push(vec![1]);
+        history.acknowledge(1);
+        assert_eq!(replay(&history, 0), None);
+        assert_eq!(replay(&history, 1), Some(vec![vec![1]]));
+
+        // Acknowledgements never go backwards, or past the edits that were sent
+        history.acknowledge(0);
+        assert_eq!(replay(&history, 1), Some(vec![vec![1]]));
+        history.acknowledge(10);
+        assert_eq!(replay(&history, 2), Some(vec![]));
+        history.push(vec![2]);
+        assert_eq!(replay(&history, 2), Some(vec![vec![2]]));
+    }
+
+    #[test]
+    fn history_is_limited_without_acknowledgements() {
+        let mut history = EditHistory::new(4);
+        history.push(vec![0; 2]);
+        history.push(vec![1; 2]);
+        history.push(vec![2; 2]);
+        assert_eq!(history.bytes, 4);
+        assert_eq!(replay(&history, 0), None);
+        assert_eq!(replay(&history, 1), Some(vec![vec![1; 2], vec![2; 2]]));
+
+        // Edits larger than the limit are never kept
+        history.push(vec![3; 5]);
+        assert_eq!(history.bytes, 0);
+        assert_eq!(replay(&history, 3), None);
+        assert_eq!(replay(&history, 4), Some(vec![]));
+    }
+
+    /// A socket connected to channels instead of a client
+    struct TestSocket {
+        from_client: mpsc::UnboundedReceiver<Result<Vec<u8>, LiveViewError>>,
+        to_client: mpsc::UnboundedSender<Vec<u8>>,
+    }
+
+    impl Stream for TestSocket {
+        type Item = Result<Vec<u8>, LiveViewError>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.from_client.poll_next_unpin(cx)
+        }
+    }
+
+    impl Sink<Vec<u8>> for TestSocket {
+        type Error = LiveViewError;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+            self.to_client
+                .unbounded_send(item)
+                .map_err(|_| LiveViewError::SendingFailed)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    struct TestClient {
+        to_server: mpsc::UnboundedSender<Result<Vec<u8>, LiveViewError>>,
+        from_server: mpsc::UnboundedReceiver<Vec<u8>>,
+    }
+
+    impl TestClient {
+        /// Connect to the pool and ask to resume a session
+        fn connect(pool: &LiveViewPool, session: Option<&str>, acknowledged: u64) -> Self {
+            let initialize = serde_json::json!({
+                "method": "initialize",
+                "params": { "session": session, "acknowledged": acknowledged },
+            });
+            Self::connect_with(pool, initialize.to_string().into_bytes())
+        }
+
+        /// Connect to the pool and send this frame first
+        fn connect_with(pool: &LiveViewPool, first_frame: Vec<u8>) -> Self {
+            fn app() -> Element {
+                rsx! { div { "hello" } }
+            }
+
+            let (to_server, from_client) = mpsc::unbounded();
+            let (to_client, from_server) = mpsc::unbounded();
+            let socket = TestSocket {
+                from_client,
+                to_client,
+            };
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.launch(socket, app).await });
+
+            to_server.unbounded_send(Ok(first_frame)).unwrap();
+            Self {
+                to_server,
+                from_server,
+            }
+        }
+
+        /// Wait for the next update the server sends as json
+        async fn update(&mut self) -> serde_json::Value {
+            while let Some(frame) = self.from_server.next().await {
+                if let Some(text) = frame.strip_prefix(&[0]) {
+                    return serde_json::from_slice(text).unwrap();
+                }
+            }
+            panic!("the server closed the socket before sending an update");
+        }
+
+        /// Wait for the server to tell the client which session it joined
+        async fn session(&mut self) -> (String, bool) {
+            loop {
+                let update = self.update().await;
+                if update["type"] == "session" {
+                    let data = &update["data"];
+                    return (
+                        data["token"].as_str().unwrap().to_string(),
+                        data["resumed"].as_bool().unwrap(),
+                    );
+                }
+            }
+        }
+
+        fn disconnect(self) {
+            self.to_server.close_channel();
+        }
+    }
+
+    #[tokio::test]
+    async fn clients_resume_sessions() {
+        let pool = LiveViewPool::new();
+        let mut client = TestClient::connect(&pool, None, 0);
+        let (token, resumed) = client.session().await;
+        assert!(!resumed);
+        client.disconnect();
+
+        let mut client = TestClient::connect(&pool, Some(&token), 0);
+        assert_eq!(client.session().await, (token, true));
+    }
+
+    #[tokio::test]
+    async fn stale_resumes_rebuild_the_dom() {
+        // Nothing is kept for replay, so a client that missed any edits can't catch up
+        let pool = LiveViewPool::new().with_replay_limit(0);
+        let mut client = TestClient::connect(&pool, None, 0);
+        let (token, _) = client.session().await;
+        // The first client still gets the initial edits
+        let rebuild = client.update().await;
+        assert_eq!(rebuild["type"], "rebuild");
+        assert_eq!(rebuild["data"]["edits"], 1);
+        let edits = client.from_server.next().await.unwrap();
+        assert_eq!(edits.first(), Some(&1));
+        client.disconnect();
+
+        // The session is resumed, but the client gets the whole DOM instead of the edits it missed
+        let mut client = TestClient::connect(&pool, Some(&token), 0);
+        assert_eq!(client.session().await, (token, true));
+        let rebuild = client.update().await;
+        assert_eq!(rebuild["type"], "rebuild");
+        assert_eq!(rebuild["data"]["edits"], 1);
+        let edits = client.from_server.next().await.unwrap();
+        assert_eq!(edits.first(), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn clients_without_a_handshake_keep_their_first_message() {
+        let pool = LiveViewPool::new();
+        let mut client = TestClient::connect_with(&pool, b"__ping__".to_vec());
+        let (_, resumed) = client.session().await;
+        assert!(!resumed);
+
+        // The ping is answered after the initial edits
+        let mut frames = Vec::new();
+        while let Some(frame) = client.from_server.next().await {
+            if frame == b"\0__pong__" {
+                return;
+            }
+            frames.push(frame);
+        }
+        panic!("the ping was never answered: {frames:?}");
+    }
+}
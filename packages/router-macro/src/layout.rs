@@ -3,6 +3,7 @@ use quote::quote;
 use syn::Path;
 
 use crate::nest::{Nest, NestId};
+use crate::route::NamedArgs;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LayoutId(pub usize);
@@ -11,6 +12,7 @@ pub struct LayoutId(pub usize);
 pub struct Layout {
     pub comp: Path,
     pub active_nests: Vec<NestId>,
+    pub loader: Option<Path>,
 }
 
 impl Layout {
@@ -34,7 +36,12 @@ impl Layout {
         // Then parse the component name
         let _ = input.parse::<syn::Token![,]>();
         let comp: Path = input.parse()?;
+        let loader = NamedArgs::parse(input, &["loader"])?.loader;
 
-        Ok(Self { comp, active_nests })
+        Ok(Self {
+            comp,
+            active_nests,
+            loader,
+        })
     }
 }
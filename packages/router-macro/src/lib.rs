@@ -91,11 +91,13 @@ mod segment;
 /// # fn IndexComponent() -> Element { VNode::empty() }
 /// ```
 ///
-/// # `#[route("path", component)]`
+/// # `#[route("path", component, loader = function, enumerate = function)]`
 ///
-/// The `#[route]` attribute is used to define a route. It takes up to 2 parameters:
+/// The `#[route]` attribute is used to define a route. It takes up to 4 parameters:
 /// - `path`: The path to the enum variant (relative to the parent nest)
 /// - (optional) `component`: The component to render when the route is matched. If not specified, the name of the variant is used
+/// - (optional) `loader`: An async function that loads data for the route. It is called with the fields of the variant in order
+/// - (optional) `enumerate`: A function that returns every value of the variant. Routes with dynamic segments are only included in `Routable::all_routes` (and pre-rendered by static generation) if they can be enumerated
 ///
 /// Routes are the most basic attribute. They allow you to define a route and the component to render when the route is matched. The component must take all dynamic parameters of the route and all parent nests.
 /// The next variant will be tied to the component. If you link to that variant, the component will be rendered.
@@ -114,6 +116,49 @@ mod segment;
 /// # fn Index() -> Element { VNode::empty() }
 /// ```
 ///
+/// Loaders start when the route matches, and the component renders once the data is loaded. The component reads the data with `use_route_data`:
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// #[derive(Clone, Debug, PartialEq, Routable)]
+/// enum Route {
+///     #[route("/user/:id", User, loader = load_user)]
+///     User { id: usize },
+/// }
+///
+/// async fn load_user(id: usize) -> String {
+///     format!("User {id}")
+/// }
+///
+/// #[component]
+/// fn User(id: usize) -> Element {
+///     let name = use_route_data(load_user);
+///     rsx! { "{name}" }
+/// }
+/// ```
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// #[derive(Clone, Debug, PartialEq, Routable)]
+/// enum Route {
+///     #[route("/blog/:slug", enumerate = blog_posts)]
+///     BlogPost { slug: String },
+/// }
+///
+/// fn blog_posts() -> Vec<Route> {
+///     ["hello-world", "routing"]
+///         .into_iter()
+///         .map(|slug| Route::BlogPost { slug: slug.to_string() })
+///         .collect()
+/// }
+///
+/// assert_eq!(Route::all_routes().len(), 2);
+/// # #[component]
+/// # fn BlogPost(slug: String) -> Element { VNode::empty() }
+/// ```
+///
 /// # `#[redirect("path", function)]`
 ///
 /// The `#[redirect]` attribute is used to define a redirect. It takes 2 parameters:
@@ -187,10 +232,11 @@ mod segment;
 /// # fn Home() -> Element { VNode::empty() }
 /// ```
 ///
-/// # `#[layout(component)]`
+/// # `#[layout(component, loader = function)]`
 ///
-/// The `#[layout]` attribute is used to define a layout. It takes 1 parameter:
+/// The `#[layout]` attribute is used to define a layout. It takes up to 2 parameters:
 /// - `component`: The component to render when the route is matched. If not specified, the name of the variant is used
+/// - (optional) `loader`: An async function that loads data for the layout. It is called with the dynamic parameters of the nests the layout is in. Layout loaders run in parallel with the loaders of the layout's children
 ///
 /// The layout component allows you to wrap all children of the layout in a component. The child routes are rendered in the Outlet of the layout component. The layout component must take all dynamic parameters of the nests it is nested in.
 ///
@@ -700,11 +746,13 @@ impl RouteEnum {
         let site_map = &self.site_map;
 
         let mut matches = Vec::new();
+        let mut enumerated_routes = Vec::new();
 
         // Collect all routes matches
         for route in &self.endpoints {
             if let RouteEndpoint::Route(route) = route {
                 matches.push(route.routable_match(&self.layouts, &self.nests));
+                enumerated_routes.extend(route.enumerated_routes());
             }
         }
 
@@ -721,6 +769,13 @@ impl RouteEnum {
                         _ => VNode::empty()
                     }
                 }
+
+                fn enumerated_routes() -> Vec<Self> {
+                    #[allow(unused_mut)]
+                    let mut routes = Vec::new();
+                    #(#enumerated_routes)*
+                    routes
+                }
             }
         }
     }
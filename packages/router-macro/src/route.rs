@@ -22,31 +22,82 @@ use crate::segment::RouteSegment;
 struct RouteArgs {
     route: LitStr,
     comp_name: Option<Path>,
+    named: NamedArgs,
 }
 
 impl Parse for RouteArgs {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
         let route = input.parse::<LitStr>()?;
 
+        let _ = input.parse::<syn::Token![,]>();
+        let comp_name = if NamedArgs::is_next(input) {
+            None
+        } else {
+            input.parse().ok()
+        };
+
         Ok(RouteArgs {
             route,
-            comp_name: {
-                let _ = input.parse::<syn::Token![,]>();
-                input.parse().ok()
-            },
+            comp_name,
+            named: NamedArgs::parse(input, &["loader", "enumerate"])?,
         })
     }
 }
 
+/// Named arguments like `loader = function` that follow the positional arguments of an attribute
+#[derive(Debug, Default)]
+pub(crate) struct NamedArgs {
+    pub loader: Option<Path>,
+    pub enumerate: Option<Path>,
+}
+
+impl NamedArgs {
+    /// Check if the next argument is `name = path`
+    fn is_next(input: ParseStream<'_>) -> bool {
+        input.peek(Ident) && input.peek2(syn::Token![=])
+    }
+
+    /// Parse `, name = path` arguments until the end of the input. Only the names in `allowed` are accepted
+    pub(crate) fn parse(input: ParseStream<'_>, allowed: &[&str]) -> syn::Result<Self> {
+        let mut args = Self::default();
+        loop {
+            let _ = input.parse::<syn::Token![,]>();
+            if input.is_empty() {
+                return Ok(args);
+            }
+            let name = input.parse::<Ident>()?;
+            let slot = match name.to_string().as_str() {
+                "loader" if allowed.contains(&"loader") => &mut args.loader,
+                "enumerate" if allowed.contains(&"enumerate") => &mut args.enumerate,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        format!(
+                            "Unknown argument `{name}`. Expected one of: {}",
+                            allowed.join(", ")
+                        ),
+                    ))
+                }
+            };
+            input.parse::<syn::Token![=]>()?;
+            *slot = Some(input.parse()?);
+        }
+    }
+}
+
 struct ChildArgs {
     route: LitStr,
+    named: NamedArgs,
 }
 
 impl Parse for ChildArgs {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
         let route = input.parse::<LitStr>()?;
 
-        Ok(ChildArgs { route })
+        Ok(ChildArgs {
+            route,
+            named: NamedArgs::parse(input, &["enumerate"])?,
+        })
     }
 }
 
@@ -60,6 +111,8 @@ pub(crate) struct Route {
     pub hash: Option<HashFragment>,
     pub nests: Vec<NestId>,
     pub layouts: Vec<LayoutId>,
+    pub loader: Option<Path>,
+    pub enumerate: Option<Path>,
     fields: Vec<(Ident, Type)>,
 }
 
@@ -75,6 +128,7 @@ impl Route {
             .find(|attr| attr.path().is_ident("route"));
         let route;
         let ty;
+        let named;
         let route_name = variant.ident.clone();
         match route_attr {
             Some(attr) => {
@@ -84,6 +138,7 @@ impl Route {
                     component: comp_name,
                 };
                 route = args.route.value();
+                named = args.named;
             }
             None => {
                 if let Some(route_attr) = variant
@@ -93,6 +148,7 @@ impl Route {
                 {
                     let args = route_attr.parse_args::<ChildArgs>()?;
                     route = args.route.value();
+                    named = args.named;
                     match &variant.fields {
                         syn::Fields::Named(fields) => {
                             // find either a field with #[child] or a field named "child"
@@ -163,6 +219,8 @@ impl Route {
             hash,
             nests,
             layouts,
+            loader: named.loader,
+            enumerate: named.enumerate,
             fields,
         })
     }
@@ -216,9 +274,45 @@ impl Route {
 
         let mut tokens = TokenStream2::new();
 
+        // Start every loader of the route and its layouts in the outermost component so they load in parallel, and
+        // make each layout or route wait for its own loader so the layouts around a route stay mounted when it changes
+        let loaders = self.loaders(layouts, nests);
+        let wrap_loaders = |idx: usize, render: TokenStream2| {
+            let mut render = render;
+            if let Some((_, loader)) = loaders.iter().find(|(level, _)| *level == idx) {
+                render = quote! {
+                    {
+                        let loader = #loader;
+                        rsx! {
+                            dioxus_router::loader::RouteLoaderGate {
+                                loader,
+                                {#render}
+                            }
+                        }
+                    }
+                };
+            }
+            if idx != 0 || loaders.is_empty() {
+                return render;
+            }
+            let all_loaders = loaders.iter().map(|(_, loader)| loader);
+            quote! {
+                {
+                    let loaders = vec![#(#all_loaders,)*];
+                    rsx! {
+                        dioxus_router::loader::RouteLoaders {
+                            route: self.to_string(),
+                            loaders,
+                            {#render}
+                        }
+                    }
+                }
+            }
+        };
+
         // First match all layouts
         for (idx, layout_id) in self.layouts.iter().copied().enumerate() {
-            let render_layout = layouts[layout_id.0].routable_match(nests);
+            let render_layout = wrap_loaders(idx, layouts[layout_id.0].routable_match(nests));
             let dynamic_segments = self.dynamic_segments();
             let mut field_name = None;
             if let RouteType::Child(field) = &self.ty {
@@ -249,14 +343,20 @@ impl Route {
             RouteType::Leaf { component } => {
                 let dynamic_segments = self.dynamic_segments();
                 let dynamic_segments_from_route = self.dynamic_segments();
-                quote! {
-                    #[allow(unused)]
-                    (#last_index, Self::#name { #(#dynamic_segments,)* }) => {
+                let render = wrap_loaders(
+                    last_index,
+                    quote! {
                         rsx! {
                             #component {
                                 #(#dynamic_segments_from_route: #dynamic_segments_from_route,)*
                             }
                         }
+                    },
+                );
+                quote! {
+                    #[allow(unused)]
+                    (#last_index, Self::#name { #(#dynamic_segments,)* }) => {
+                        #render
                     }
                 }
             }
@@ -265,6 +365,61 @@ impl Route {
         tokens
     }
 
+    /// The loaders of every layout the route is in, followed by the loader of the route itself, with the level they
+    /// are rendered at
+    fn loaders(&self, layouts: &[Layout], nests: &[Nest]) -> Vec<(usize, TokenStream2)> {
+        let layout_loaders = self.layouts.iter().enumerate().filter_map(|(idx, id)| {
+            let layout = &layouts[id.0];
+            let loader = layout.loader.as_ref()?;
+            let args = layout
+                .active_nests
+                .iter()
+                .flat_map(|id| nests[id.0].dynamic_segments());
+            Some((
+                idx,
+                quote! {
+                    dioxus_router::loader::RouteLoaderFn::new(#loader, (#(#args.clone(),)*))
+                },
+            ))
+        });
+        let route_loader = self.loader.as_ref().map(|loader| {
+            let args = self.dynamic_segments();
+            (
+                self.layouts.len(),
+                quote! {
+                    dioxus_router::loader::RouteLoaderFn::new(#loader, (#(#args.clone(),)*))
+                },
+            )
+        });
+        layout_loaders.chain(route_loader).collect()
+    }
+
+    /// Add the routes this variant can render to `routes` if they can be enumerated
+    pub fn enumerated_routes(&self) -> Option<TokenStream2> {
+        if let Some(enumerate) = &self.enumerate {
+            return Some(quote! {
+                routes.extend(#enumerate());
+            });
+        }
+
+        // Children without any dynamic segments of their own can be enumerated through the child route
+        match &self.ty {
+            RouteType::Child(field) if self.fields.is_empty() => {
+                let name = &self.route_name;
+                let child_name = field.ident.as_ref().unwrap();
+                let child_ty = &field.ty;
+                Some(quote! {
+                    routes.extend(
+                        <#child_ty as dioxus_router::routable::Routable>::all_routes()
+                            .into_iter()
+                            .map(|#child_name| Self::#name { #child_name }),
+                    );
+                })
+            }
+            _ => None,
+        }
+    }
+
     fn dynamic_segments(&self) -> impl Iterator<Item = TokenStream2> + '_ {
         self.fields.iter().map(|(name, _)| {
             quote! {#name}
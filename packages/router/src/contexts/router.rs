@@ -1,14 +1,17 @@
 use std::{
     any::Any,
     collections::HashSet,
+    future::Future,
+    pin::Pin,
     rc::Rc,
     sync::{Arc, RwLock},
 };
 
 use dioxus_lib::prelude::*;
+use futures_util::FutureExt;
 
 use crate::{
-    navigation::NavigationTarget,
+    navigation::{GuardResult, NavigationKind, NavigationTarget, PendingNavigation},
     prelude::{AnyHistoryProvider, IntoRoutable, SiteMapSegment},
     routable::Routable,
     router_cfg::RouterConfig,
@@ -50,6 +53,69 @@ pub(crate) type RoutingCallback<R> =
 pub(crate) type AnyRoutingCallback =
     Arc<dyn Fn(RouterContext) -> Option<NavigationTarget<Rc<dyn Any>>>>;
 
+/// A function the router will call before every navigation.
+pub(crate) type NavigationGuard<R> =
+    Arc<dyn Fn(PendingNavigation<R>) -> Pin<Box<dyn Future<Output = GuardResult<R>>>>>;
+type AnyNavigationGuard = Rc<
+    dyn Fn(
+        RouterContext,
+        NavigationKind,
+        Option<NavigationTarget<Rc<dyn Any>>>,
+    ) -> Pin<Box<dyn Future<Output = GuardResult<Rc<dyn Any>>>>>,
+>;
+
+/// A navigation the router will perform once it passes every blocker and guard.
+#[derive(Clone)]
+pub(crate) enum NavigationAction {
+    Push(NavigationTarget<Rc<dyn Any>>),
+    Replace(NavigationTarget<Rc<dyn Any>>),
+    GoBack,
+    GoForward,
+}
+
+impl NavigationAction {
+    fn kind(&self) -> NavigationKind {
+        match self {
+            NavigationAction::Push(_) => NavigationKind::Push,
+            NavigationAction::Replace(_) => NavigationKind::Replace,
+            NavigationAction::GoBack => NavigationKind::GoBack,
+            NavigationAction::GoForward => NavigationKind::GoForward,
+        }
+    }
+
+    fn target(&self) -> Option<NavigationTarget<Rc<dyn Any>>> {
+        match self {
+            NavigationAction::Push(target) | NavigationAction::Replace(target) => {
+                Some(target.clone())
+            }
+            NavigationAction::GoBack | NavigationAction::GoForward => None,
+        }
+    }
+}
+
+/// A blocker registered with [`use_navigation_blocker`](crate::prelude::use_navigation_blocker).
+struct Blocker {
+    id: usize,
+    blocking: Memo<bool>,
+    blocked: Signal<Option<NavigationAction>>,
+}
+
+fn target_to_any<R: Routable>(target: NavigationTarget<R>) -> NavigationTarget<Rc<dyn Any>> {
+    match target {
+        NavigationTarget::Internal(r) => NavigationTarget::Internal(Rc::new(r) as Rc<dyn Any>),
+        NavigationTarget::External(s) => NavigationTarget::External(s),
+    }
+}
+
+fn target_from_any<R: Routable>(target: NavigationTarget<Rc<dyn Any>>) -> NavigationTarget<R> {
+    match target {
+        NavigationTarget::Internal(r) => {
+            NavigationTarget::Internal(r.downcast::<R>().unwrap().as_ref().clone())
+        }
+        NavigationTarget::External(s) => NavigationTarget::External(s),
+    }
+}
+
 struct RouterContextInner {
     /// The current prefix.
     prefix: Option<String>,
@@ -62,11 +128,19 @@ struct RouterContextInner {
     subscriber_update: Arc<dyn Fn(ScopeId)>,
     routing_callback: Option<AnyRoutingCallback>,
 
+    guards: Vec<AnyNavigationGuard>,
+    blockers: Vec<Blocker>,
+    next_blocker_id: usize,
+    /// Incremented every time a navigation starts. Guards that finish after a newer navigation started are ignored
+    navigation_generation: u64,
+
     failure_external_navigation: fn() -> Element,
 
     any_route_to_string: fn(&dyn Any) -> String,
 
     site_map: &'static [SiteMapSegment],
+
+    all_routes: fn() -> Vec<String>,
 }
 
 impl RouterContextInner {
@@ -122,16 +196,38 @@ impl RouterContext {
                         inner: ctx,
                         _marker: std::marker::PhantomData,
                     };
-                    update(ctx).map(|t| match t {
-                        NavigationTarget::Internal(r) => {
-                            NavigationTarget::Internal(Rc::new(r) as Rc<dyn Any>)
-                        }
-                        NavigationTarget::External(s) => NavigationTarget::External(s),
-                    })
+                    update(ctx).map(target_to_any)
                 })
                     as Arc<dyn Fn(RouterContext) -> Option<NavigationTarget<Rc<dyn Any>>>>
             }),
 
+            guards: cfg
+                .guards
+                .into_iter()
+                .map(|guard| {
+                    Rc::new(move |ctx: RouterContext, kind, to: Option<_>| {
+                        let navigation = PendingNavigation {
+                            kind,
+                            from: ctx.current::<R>(),
+                            to: to.map(target_from_any),
+                        };
+                        let result = guard(navigation);
+                        Box::pin(async move {
+                            match result.await {
+                                GuardResult::Allow => GuardResult::Allow,
+                                GuardResult::Cancel => GuardResult::Cancel,
+                                GuardResult::Redirect(target) => {
+                                    GuardResult::Redirect(target_to_any(target))
+                                }
+                            }
+                        }) as Pin<Box<dyn Future<Output = _>>>
+                    }) as AnyNavigationGuard
+                })
+                .collect(),
+            blockers: Vec::new(),
+            next_blocker_id: 0,
+            navigation_generation: 0,
+
             failure_external_navigation: cfg.failure_external_navigation,
 
             any_route_to_string: |route| {
@@ -149,6 +245,8 @@ impl RouterContext {
             },
 
             site_map: R::SITE_MAP,
+
+            all_routes: || R::all_routes().iter().map(ToString::to_string).collect(),
         };
 
         // set the updater
@@ -185,37 +283,21 @@ impl RouterContext {
     ///
     /// Will fail silently if there is no previous location to go to.
     pub fn go_back(&self) {
-        {
-            self.inner.write_unchecked().history.go_back();
-        }
-
-        self.change_route();
+        self.navigate(NavigationAction::GoBack);
     }
 
     /// Go back to the next location.
     ///
     /// Will fail silently if there is no next location to go to.
     pub fn go_forward(&self) {
-        {
-            self.inner.write_unchecked().history.go_forward();
-        }
-
-        self.change_route();
+        self.navigate(NavigationAction::GoForward);
     }
 
     pub(crate) fn push_any(
         &self,
         target: NavigationTarget<Rc<dyn Any>>,
     ) -> Option<ExternalNavigationFailure> {
-        {
-            let mut write = self.inner.write_unchecked();
-            match target {
-                NavigationTarget::Internal(p) => write.history.push(p),
-                NavigationTarget::External(e) => return write.external(e),
-            }
-        }
-
-        self.change_route()
+        self.navigate(NavigationAction::Push(target))
     }
 
     /// Push a new location.
@@ -223,15 +305,7 @@ impl RouterContext {
     /// The previous location will be available to go back to.
     pub fn push(&self, target: impl Into<IntoRoutable>) -> Option<ExternalNavigationFailure> {
         let target = self.resolve_into_routable(target.into());
-        {
-            let mut write = self.inner.write_unchecked();
-            match target {
-                NavigationTarget::Internal(p) => write.history.push(p),
-                NavigationTarget::External(e) => return write.external(e),
-            }
-        }
-
-        self.change_route()
+        self.navigate(NavigationAction::Push(target))
     }
 
     /// Replace the current location.
@@ -239,18 +313,142 @@ impl RouterContext {
     /// The previous location will **not** be available to go back to.
     pub fn replace(&self, target: impl Into<IntoRoutable>) -> Option<ExternalNavigationFailure> {
         let target = self.resolve_into_routable(target.into());
+        self.navigate(NavigationAction::Replace(target))
+    }
+
+    /// Run a navigation through the blockers and guards before applying it.
+    ///
+    /// If a guard is still pending when this returns, the navigation finishes in the background and any external
+    /// navigation failure is only reported through the router error.
+    fn navigate(&self, action: NavigationAction) -> Option<ExternalNavigationFailure> {
+        let blocker = self
+            .inner
+            .read()
+            .blockers
+            .iter()
+            .find(|blocker| *blocker.blocking.peek())
+            .map(|blocker| blocker.blocked);
+        if let Some(mut blocked) = blocker {
+            blocked.set(Some(action));
+            return None;
+        }
 
+        self.navigate_unblocked(action)
+    }
+
+    /// Run a navigation through the guards, skipping any blockers.
+    ///
+    /// If a newer navigation starts while the guards are still pending, the result of the guards is ignored.
+    pub(crate) fn navigate_unblocked(
+        &self,
+        action: NavigationAction,
+    ) -> Option<ExternalNavigationFailure> {
+        let (guards, generation) = {
+            let mut write = self.inner.write_unchecked();
+            write.navigation_generation += 1;
+            (write.guards.clone(), write.navigation_generation)
+        };
+        if guards.is_empty() {
+            return self.apply_navigation(action);
+        }
+
+        let myself = *self;
+        let kind = action.kind();
+        let target = action.target();
+        let mut result = Box::pin(async move {
+            for guard in guards {
+                match guard(myself, kind, target.clone()).await {
+                    GuardResult::Allow => {}
+                    result => return result,
+                }
+            }
+            GuardResult::Allow
+        });
+
+        // Most guards resolve immediately, so try to finish the navigation before returning
+        if let Some(result) = (&mut result).now_or_never() {
+            return self.finish_navigation(action, result);
+        }
+        spawn_forever(async move {
+            let result = result.await;
+            if myself.inner.peek().navigation_generation == generation {
+                myself.finish_navigation(action, result);
+            }
+        });
+        None
+    }
+
+    fn finish_navigation(
+        &self,
+        action: NavigationAction,
+        result: GuardResult<Rc<dyn Any>>,
+    ) -> Option<ExternalNavigationFailure> {
+        match (result, action) {
+            (GuardResult::Allow, action) => self.apply_navigation(action),
+            (GuardResult::Cancel, _) => None,
+            (GuardResult::Redirect(target), NavigationAction::Push(_)) => {
+                self.apply_navigation(NavigationAction::Push(target))
+            }
+            (GuardResult::Redirect(target), _) => {
+                self.apply_navigation(NavigationAction::Replace(target))
+            }
+        }
+    }
+
+    fn apply_navigation(&self, action: NavigationAction) -> Option<ExternalNavigationFailure> {
         {
-            let mut state = self.inner.write_unchecked();
-            match target {
-                NavigationTarget::Internal(p) => state.history.replace(p),
-                NavigationTarget::External(e) => return state.external(e),
+            let mut write = self.inner.write_unchecked();
+            match action {
+                NavigationAction::GoBack => write.history.go_back(),
+                NavigationAction::GoForward => write.history.go_forward(),
+                NavigationAction::Push(NavigationTarget::Internal(p)) => write.history.push(p),
+                NavigationAction::Replace(NavigationTarget::Internal(p)) => {
+                    write.history.replace(p)
+                }
+                NavigationAction::Push(NavigationTarget::External(e))
+                | NavigationAction::Replace(NavigationTarget::External(e)) => {
+                    return write.external(e)
+                }
             }
         }
 
         self.change_route()
     }
 
+    pub(crate) fn add_blocker(
+        &self,
+        blocking: Memo<bool>,
+        blocked: Signal<Option<NavigationAction>>,
+    ) -> usize {
+        let mut write = self.inner.write_unchecked();
+        let id = write.next_blocker_id;
+        write.next_blocker_id += 1;
+        write.blockers.push(Blocker {
+            id,
+            blocking,
+            blocked,
+        });
+        id
+    }
+
+    pub(crate) fn remove_blocker(&self, id: usize) {
+        self.inner
+            .write_unchecked()
+            .blockers
+            .retain(|blocker| blocker.id != id);
+        self.update_unload_blocking();
+    }
+
+    /// Let the history provider know if any blocker is active, so it can block leaving the app
+    pub(crate) fn update_unload_blocking(&self) {
+        let mut write = self.inner.write_unchecked();
+        let blocked = write
+            .blockers
+            .iter()
+            .any(|blocker| blocker.blocking.try_peek().is_ok_and(|blocking| *blocking));
+        write.history.block_unload(blocked);
+    }
+
     /// The route that is currently active.
     pub fn current<R: Routable>(&self) -> R {
         self.inner
@@ -316,6 +514,11 @@ impl RouterContext {
         self.inner.read().site_map
     }
 
+    /// Get every route the router can render without any outside information. See [`Routable::all_routes`].
+    pub fn all_routes(&self) -> Vec<String> {
+        (self.inner.read().all_routes)()
+    }
+
     pub(crate) fn render_error(&self) -> Option<Element> {
         let inner_read = self.inner.write_unchecked();
         inner_read
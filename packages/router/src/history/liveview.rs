@@ -45,6 +45,7 @@ enum Action<R: Routable> {
     Push(R),
     Replace(R),
     External(String),
+    BlockUnload(bool),
 }
 
 impl<R: Routable> Timeline<R>
@@ -228,6 +229,16 @@ where
                                 location.href = "{url}";
                             "#
                         )),
+                        Action::BlockUnload(true) => create_eval(
+                            r#"
+                                window.onbeforeunload = (event) => { event.preventDefault(); event.returnValue = ""; };
+                            "#,
+                        ),
+                        Action::BlockUnload(false) => create_eval(
+                            r#"
+                                window.onbeforeunload = null;
+                            "#,
+                        ),
                     };
                 }
             }
@@ -382,6 +393,10 @@ where
         let mut updater_callback = self.updater_callback.write().unwrap();
         *updater_callback = callback;
     }
+
+    fn block_unload(&mut self, blocked: bool) {
+        let _ = self.action_tx.send(Action::BlockUnload(blocked));
+    }
 }
 
 mod routes {
@@ -284,6 +284,16 @@ pub trait HistoryProvider<R: Routable> {
     /// updates are received, they should call `callback`, which will cause the router to update.
     #[allow(unused_variables)]
     fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {}
+
+    /// Ask the user to confirm before leaving the application in ways the router cannot intercept, like closing the
+    /// tab or reloading the page.
+    ///
+    /// This is called with `true` while a [`use_navigation_blocker`](crate::prelude::use_navigation_blocker) is
+    /// blocking navigation, and with `false` once no blocker is active.
+    ///
+    /// Providers that cannot leave the application, like the [`MemoryHistory`], don't need to implement this.
+    #[allow(unused_variables)]
+    fn block_unload(&mut self, blocked: bool) {}
 }
 
 pub(crate) trait AnyHistoryProvider {
@@ -317,6 +327,9 @@ pub(crate) trait AnyHistoryProvider {
 
     #[allow(unused_variables)]
     fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {}
+
+    #[allow(unused_variables)]
+    fn block_unload(&mut self, blocked: bool) {}
 }
 
 pub(crate) struct AnyHistoryProviderImplWrapper<R, H> {
@@ -389,4 +402,8 @@ where
     fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
         self.inner.updater(callback)
     }
+
+    fn block_unload(&mut self, blocked: bool) {
+        self.inner.block_unload(blocked)
+    }
 }
@@ -46,6 +46,7 @@ pub struct WebHistory<R: Routable> {
     do_scroll_restoration: bool,
     history: History,
     listener_navigation: Option<EventListener>,
+    listener_unload: Option<EventListener>,
     listener_animation_frame: Arc<Mutex<Option<AnimationFrame>>>,
     prefix: Option<String>,
     window: Window,
@@ -109,6 +110,7 @@ impl<R: Routable> WebHistory<R> {
             do_scroll_restoration,
             history,
             listener_navigation: None,
+            listener_unload: None,
             listener_animation_frame: Default::default(),
             prefix,
             window,
@@ -246,4 +248,19 @@ where
             }
         }));
     }
+
+    fn block_unload(&mut self, blocked: bool) {
+        if !blocked {
+            self.listener_unload = None;
+        } else if self.listener_unload.is_none() {
+            // Preventing the default beforeunload action makes the browser ask the user before leaving the page
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            self.listener_unload = Some(EventListener::new_with_options(
+                &self.window,
+                "beforeunload",
+                options,
+                |event| event.prevent_default(),
+            ));
+        }
+    }
 }
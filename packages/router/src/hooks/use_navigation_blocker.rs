@@ -0,0 +1,96 @@
+use dioxus_lib::prelude::*;
+
+use crate::contexts::router::NavigationAction;
+use crate::prelude::RouterContext;
+
+/// A handle to a blocker created with [`use_navigation_blocker`].
+#[derive(Clone, Copy)]
+pub struct NavigationBlocker {
+    router: RouterContext,
+    blocking: Memo<bool>,
+    blocked: Signal<Option<NavigationAction>>,
+}
+
+impl NavigationBlocker {
+    /// Check if the blocker is currently preventing navigation.
+    pub fn is_blocking(&self) -> bool {
+        (self.blocking)()
+    }
+
+    /// Check if a navigation was blocked and is waiting for [`NavigationBlocker::proceed`] or
+    /// [`NavigationBlocker::reset`].
+    pub fn is_blocked(&self) -> bool {
+        self.blocked.read().is_some()
+    }
+
+    /// Continue the blocked navigation. Navigation guards still run for the navigation.
+    pub fn proceed(&self) {
+        let mut blocked = self.blocked;
+        if let Some(action) = blocked.take() {
+            self.router.navigate_unblocked(action);
+        }
+    }
+
+    /// Forget the blocked navigation and stay on the current page.
+    pub fn reset(&self) {
+        let mut blocked = self.blocked;
+        blocked.set(None);
+    }
+}
+
+/// A hook that prevents the router from leaving the current page while `when` returns `true`.
+///
+/// Navigations through the router are held until the user confirms them with [`NavigationBlocker::proceed`] or
+/// cancels them with [`NavigationBlocker::reset`]. While the blocker is active, the [`HistoryProvider`](crate::prelude::HistoryProvider)
+/// is also asked to block leaving the application, which shows the browser's confirmation dialog on the web.
+///
+/// `when` is reactive, so it can read signals.
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// # use dioxus_router::prelude::*;
+/// #[component]
+/// fn EditProfile() -> Element {
+///     let mut name = use_signal(String::new);
+///     let mut saved = use_signal(|| true);
+///     let blocker = use_navigation_blocker(move || !saved());
+///
+///     rsx! {
+///         input {
+///             value: "{name}",
+///             oninput: move |event| {
+///                 name.set(event.value());
+///                 saved.set(false);
+///             }
+///         }
+///         if blocker.is_blocked() {
+///             "You have unsaved changes. Leave anyway?"
+///             button { onclick: move |_| blocker.proceed(), "Leave" }
+///             button { onclick: move |_| blocker.reset(), "Stay" }
+///         }
+///     }
+/// }
+/// ```
+#[must_use]
+pub fn use_navigation_blocker(when: impl FnMut() -> bool + 'static) -> NavigationBlocker {
+    let router = use_hook(|| {
+        try_consume_context::<RouterContext>()
+            .expect("Must be called in a descendant of a Router component")
+    });
+    let blocking = use_memo(when);
+    let blocked = use_signal(|| None);
+
+    let id = use_hook(|| router.add_blocker(blocking, blocked));
+    use_effect(move || {
+        // Rerun whenever the blocker changes to keep the history provider up to date
+        blocking();
+        router.update_unload_blocking();
+    });
+    use_drop(move || router.remove_blocker(id));
+
+    NavigationBlocker {
+        router,
+        blocking,
+        blocked,
+    }
+}
@@ -0,0 +1,51 @@
+use dioxus_lib::prelude::*;
+
+use crate::loader::{LoadedRouteData, RouteData, RouteLoader};
+
+/// A hook that provides access to the data loaded by a route or layout loader.
+///
+/// The loader function is used to find the data, so it must be the same function passed to `loader = ..` in the
+/// `#[route]` or `#[layout]` attribute of the current route or one of its layouts. The route only renders once every
+/// loader resolves, so the data is always available.
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// # use dioxus_router::prelude::*;
+/// #[derive(Clone, Routable)]
+/// enum Route {
+///     #[route("/user/:id", loader = load_user)]
+///     User { id: usize },
+/// }
+///
+/// async fn load_user(id: usize) -> String {
+///     format!("User {id}")
+/// }
+///
+/// #[component]
+/// fn User(id: usize) -> Element {
+///     let name = use_route_data(load_user);
+///     rsx! { h1 { "{name}" } }
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if the loader is not attached to the current route or one of its layouts.
+#[must_use]
+pub fn use_route_data<L, Args>(loader: L) -> L::Output
+where
+    L: RouteLoader<Args>,
+    L::Output: RouteData,
+{
+    let _ = loader;
+    let data = use_hook(|| {
+        try_consume_context::<LoadedRouteData<L, L::Output>>().unwrap_or_else(|| {
+            panic!(
+                "The loader {} is not attached to the current route",
+                std::any::type_name::<L>()
+            )
+        })
+    });
+    data.value()
+        .expect("Routes are only rendered after their loaders resolve")
+}
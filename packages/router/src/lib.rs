@@ -5,8 +5,10 @@
 #![deny(missing_docs)]
 #![allow(non_snake_case)]
 
+pub mod loader;
 pub mod navigation;
 pub mod routable;
+pub mod sitemap;
 
 /// Components interacting with the router.
 pub mod components {
@@ -49,6 +51,12 @@ pub mod hooks {
 
     mod use_navigator;
     pub use use_navigator::*;
+
+    mod use_navigation_blocker;
+    pub use use_navigation_blocker::*;
+
+    mod use_route_data;
+    pub use use_route_data::*;
 }
 
 pub use hooks::router;
@@ -59,6 +67,7 @@ pub mod prelude {
     pub use crate::contexts::*;
     pub use crate::history::*;
     pub use crate::hooks::*;
+    pub use crate::loader::{RouteData, RouteLoader};
     pub use crate::navigation::*;
     pub use crate::routable::*;
     pub use crate::router_cfg::RouterConfig;
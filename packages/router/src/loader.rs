@@ -0,0 +1,320 @@
+//! Data loaders that run when a route matches.
+//!
+//! A loader is an async function attached to a route or layout with `#[route("/user/:id", User, loader = load_user)]`
+//! or `#[layout(UserFrame, loader = load_frame)]`. When a route matches, the loaders of the route and every layout
+//! it is nested in start at the same time, and each layout and the route render once their own loader resolves. A
+//! loader only runs again when its own arguments change. Components read the data with
+//! [`use_route_data`](crate::prelude::use_route_data).
+//!
+//! With the `fullstack` feature, loaders run with `use_server_future`, so the data loaded while rendering on the
+//! server is serialized into the page and hydrated on the client instead of being loaded again.
+
+use std::{future::Future, marker::PhantomData, rc::Rc};
+
+use dioxus_lib::prelude::*;
+
+/// Data that can be returned from a route loader.
+///
+/// With the `fullstack` feature, the data must be serializable so it can be sent to the client.
+#[cfg(feature = "fullstack")]
+pub trait RouteData: Clone + serde::Serialize + serde::de::DeserializeOwned + 'static {}
+
+#[cfg(feature = "fullstack")]
+impl<T: Clone + serde::Serialize + serde::de::DeserializeOwned + 'static> RouteData for T {}
+
+/// Data that can be returned from a route loader.
+///
+/// With the `fullstack` feature, the data must be serializable so it can be sent to the client.
+#[cfg(not(feature = "fullstack"))]
+pub trait RouteData: Clone + 'static {}
+
+#[cfg(not(feature = "fullstack"))]
+impl<T: Clone + 'static> RouteData for T {}
+
+/// An async function that loads data for a route.
+///
+/// This is implemented for every function that returns a future. Loaders for routes are called with the route's
+/// fields in the order they are declared in the enum variant. Loaders for layouts are called with the parameters of
+/// the nests the layout is in.
+pub trait RouteLoader<Args>: Copy + 'static {
+    /// The data the loader returns.
+    type Output;
+    /// The future the loader returns.
+    type Future: Future<Output = Self::Output> + 'static;
+
+    /// Start loading the data.
+    fn load(&self, args: Args) -> Self::Future;
+}
+
+macro_rules! impl_route_loader {
+    ($($arg:ident),*) => {
+        impl<Func, Fut, $($arg,)*> RouteLoader<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg),*) -> Fut + Copy + 'static,
+            Fut: Future + 'static,
+        {
+            type Output = Fut::Output;
+            type Future = Fut;
+
+            #[allow(non_snake_case)]
+            fn load(&self, ($($arg,)*): ($($arg,)*)) -> Self::Future {
+                self($($arg),*)
+            }
+        }
+    };
+}
+
+impl_route_loader!();
+impl_route_loader!(A);
+impl_route_loader!(A, B);
+impl_route_loader!(A, B, C);
+impl_route_loader!(A, B, C, D);
+impl_route_loader!(A, B, C, D, E);
+impl_route_loader!(A, B, C, D, E, F);
+impl_route_loader!(A, B, C, D, E, F, G);
+impl_route_loader!(A, B, C, D, E, F, G, H);
+
+/// The data loaded by a loader. Every loader function has a unique type, so the context is unique for each loader.
+pub(crate) struct LoadedRouteData<L, T: 'static> {
+    state: CopyValue<LoaderState<T>>,
+    /// The gates waiting for this loader. They rerun when the loader restarts or resolves
+    gates: CopyValue<Vec<ScopeId>>,
+    _loader: PhantomData<L>,
+}
+
+impl<L, T> Clone for LoadedRouteData<L, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L, T> Copy for LoadedRouteData<L, T> {}
+
+impl<L, T: Clone> LoadedRouteData<L, T> {
+    /// The data the loader returned, if it has resolved
+    pub(crate) fn value(&self) -> Option<T> {
+        match *self.state.peek() {
+            LoaderState::Pending => None,
+            LoaderState::Ready(resource) => resource.cloned(),
+        }
+    }
+
+    fn set_state(&mut self, state: LoaderState<T>) {
+        if *self.state.peek() != state {
+            self.state.set(state);
+            for gate in self.gates.peek().iter() {
+                needs_update_any(*gate);
+            }
+        }
+    }
+}
+
+enum LoaderState<T: 'static> {
+    /// The loader is running and the resource is not available yet. This only happens with `use_server_future`
+    Pending,
+    Ready(Resource<T>),
+}
+
+impl<T> PartialEq for LoaderState<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Pending, Self::Pending) => true,
+            (Self::Ready(a), Self::Ready(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Clone for LoaderState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for LoaderState<T> {}
+
+/// Provide the context for a loader and return the generation of its arguments. The generation changes every time the
+/// arguments change.
+fn use_loader_generation<L, Args>(args: &Args) -> usize
+where
+    L: RouteLoader<Args>,
+    L::Output: RouteData,
+    Args: Clone + PartialEq + 'static,
+{
+    use_hook(|| {
+        provide_context(LoadedRouteData::<L, L::Output> {
+            state: CopyValue::new(LoaderState::Pending),
+            gates: CopyValue::new(Vec::new()),
+            _loader: PhantomData,
+        })
+    });
+    let mut last = use_hook(|| CopyValue::new((args.clone(), 0)));
+    if last.peek().0 != *args {
+        let generation = last.peek().1 + 1;
+        last.set((args.clone(), generation));
+    }
+    let generation = last.peek().1;
+    generation
+}
+
+/// Run a loader as a hook and share its state with the gates waiting for it. The loader runs in its own component that
+/// is keyed by the generation of its arguments, so it starts again with a new resource when they change.
+fn use_route_loader<L, Args>(loader: L, args: Args) -> Result<(), RenderError>
+where
+    L: RouteLoader<Args>,
+    L::Output: RouteData,
+    Args: Clone + 'static,
+{
+    let mut data = use_hook(consume_context::<LoadedRouteData<L, L::Output>>);
+    let load = move || loader.load(args.clone());
+
+    #[cfg(feature = "fullstack")]
+    let (state, result) = match dioxus_fullstack::prelude::use_server_future(load) {
+        Ok(resource) => (LoaderState::Ready(resource), Ok(())),
+        Err(err) => (LoaderState::Pending, Err(err)),
+    };
+    #[cfg(not(feature = "fullstack"))]
+    let (state, result) = {
+        let resource = use_resource(load);
+        (LoaderState::Ready(resource), resource.suspend().map(|_| ()))
+    };
+
+    data.set_state(state);
+    result
+}
+
+/// Wait for a loader in a gate. Returns `Ok(false)` if the resource does not exist yet.
+fn use_loader_gate<L, Args>() -> Result<bool, RenderError>
+where
+    L: RouteLoader<Args>,
+    L::Output: RouteData,
+{
+    let data = use_hook(|| {
+        let mut data = consume_context::<LoadedRouteData<L, L::Output>>();
+        data.gates.write().push(current_scope_id().unwrap());
+        data
+    });
+    use_drop(move || {
+        let id = current_scope_id().unwrap();
+        let mut gates = data.gates;
+        gates.write().retain(|gate| *gate != id);
+    });
+
+    let state = *data.state.peek();
+    match state {
+        LoaderState::Pending => Ok(false),
+        LoaderState::Ready(resource) => resource.suspend().map(|_| true),
+    }
+}
+
+/// A loader with the arguments it will be called with. This is created by the [`Routable`](crate::routable::Routable) macro.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct RouteLoaderFn {
+    generation: Rc<dyn Fn() -> usize>,
+    load: Rc<dyn Fn() -> Result<(), RenderError>>,
+    wait: Rc<dyn Fn() -> Result<bool, RenderError>>,
+}
+
+impl RouteLoaderFn {
+    #[doc(hidden)]
+    pub fn new<L, Args>(loader: L, args: Args) -> Self
+    where
+        L: RouteLoader<Args>,
+        L::Output: RouteData,
+        Args: Clone + PartialEq + 'static,
+    {
+        Self {
+            generation: Rc::new({
+                let args = args.clone();
+                move || use_loader_generation::<L, Args>(&args)
+            }),
+            load: Rc::new(move || use_route_loader(loader, args.clone())),
+            wait: Rc::new(use_loader_gate::<L, Args>),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Props, Clone)]
+pub struct RouteLoadersProps {
+    /// The route the loaders were created for. The loaders check if their arguments changed when this changes.
+    route: String,
+    loaders: Vec<RouteLoaderFn>,
+    children: Element,
+}
+
+impl PartialEq for RouteLoadersProps {
+    fn eq(&self, other: &Self) -> bool {
+        // The loaders are created again every time the router renders, but they only depend on the route
+        self.route == other.route && self.children == other.children
+    }
+}
+
+/// Starts every loader for a route at the same time. This is rendered by the [`Routable`](crate::routable::Routable)
+/// macro around the outermost component of routes with loaders.
+///
+/// Each loader runs in its own component next to the children that only restarts when the arguments of that loader
+/// change. The layouts and route wait for their own loader in a [`RouteLoaderGate`], so changing a parameter of the
+/// route doesn't reload or remount the layouts around it.
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn RouteLoaders(props: RouteLoadersProps) -> Element {
+    let generations: Vec<usize> = props
+        .loaders
+        .iter()
+        .map(|loader| (loader.generation)())
+        .collect();
+
+    // The loaders are rendered before the children so the gates can find them
+    rsx! {
+        for (index, (loader, generation)) in props.loaders.into_iter().zip(generations).enumerate() {
+            RouteLoaderRunner { key: "{index}-{generation}", loader }
+        }
+        {props.children}
+    }
+}
+
+#[derive(Props, Clone)]
+struct RouteLoaderRunnerProps {
+    loader: RouteLoaderFn,
+}
+
+impl PartialEq for RouteLoaderRunnerProps {
+    fn eq(&self, _: &Self) -> bool {
+        // The loader is keyed by the generation of its arguments, so it never needs to rerun with new props
+        true
+    }
+}
+
+/// Runs a single loader. This suspends while the loader is running so the loader keeps running during suspense even if
+/// the gate waiting for it is not rendered yet.
+#[allow(non_snake_case)]
+fn RouteLoaderRunner(props: RouteLoaderRunnerProps) -> Element {
+    (props.loader.load)()?;
+    VNode::empty()
+}
+
+#[doc(hidden)]
+#[derive(Props, Clone)]
+pub struct RouteLoaderGateProps {
+    loader: RouteLoaderFn,
+    children: Element,
+}
+
+impl PartialEq for RouteLoaderGateProps {
+    fn eq(&self, other: &Self) -> bool {
+        self.children == other.children
+    }
+}
+
+/// Renders the children once the loader started by [`RouteLoaders`] resolves. This is rendered by the
+/// [`Routable`](crate::routable::Routable) macro around each layout and route with a loader.
+#[doc(hidden)]
+#[allow(non_snake_case)]
+pub fn RouteLoaderGate(props: RouteLoaderGateProps) -> Element {
+    if !(props.loader.wait)()? {
+        return VNode::empty();
+    }
+    props.children
+}
@@ -123,3 +123,42 @@ impl<R: Routable> FromStr for NavigationTarget<R> {
         }
     }
 }
+
+/// The kind of navigation that a [navigation guard](crate::prelude::RouterConfig::guard) is asked to approve.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NavigationKind {
+    /// A new location is pushed onto the history.
+    Push,
+    /// The current location is replaced.
+    Replace,
+    /// The router goes back to the previous location.
+    GoBack,
+    /// The router goes forward to the next location.
+    GoForward,
+}
+
+/// A navigation that has not happened yet. This is passed to [navigation guards](crate::prelude::RouterConfig::guard).
+#[derive(Clone, PartialEq, Debug)]
+pub struct PendingNavigation<R> {
+    /// The kind of navigation.
+    pub kind: NavigationKind,
+    /// The route that is currently active.
+    pub from: R,
+    /// The target of the navigation.
+    ///
+    /// This is [`None`] for [`NavigationKind::GoBack`] and [`NavigationKind::GoForward`], because the history
+    /// provider decides where those navigations go.
+    pub to: Option<NavigationTarget<R>>,
+}
+
+/// The result of a [navigation guard](crate::prelude::RouterConfig::guard).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GuardResult<R> {
+    /// Let the navigation continue to the next guard.
+    Allow,
+    /// Cancel the navigation. The current location will not change.
+    Cancel,
+    /// Navigate to a different target instead. A redirect from a push is pushed, every other redirect replaces the
+    /// current location. Guards do not run again for the redirect.
+    Redirect(NavigationTarget<R>),
+}
@@ -707,6 +707,53 @@ pub trait Routable: FromStr + Display + Clone + 'static {
             })
             .collect()
     }
+
+    /// Gets the routes with dynamic segments that can be enumerated.
+    ///
+    /// The derive macro implements this with the `enumerate` function passed to `#[route("/:dynamic", enumerate = function)]`
+    /// and with the routes of `#[child]` routes.
+    fn enumerated_routes() -> Vec<Self> {
+        Vec::new()
+    }
+
+    /// Gets every route that can be rendered without any outside information. This includes all [static routes](Routable::static_routes)
+    /// and all [enumerated routes](Routable::enumerated_routes).
+    ///
+    /// ```rust
+    /// use dioxus_router::prelude::*;
+    /// use dioxus::prelude::*;
+    ///
+    /// #[component]
+    /// fn Home() -> Element { VNode::empty() }
+    /// #[component]
+    /// fn User(id: usize) -> Element { VNode::empty() }
+    ///
+    /// #[derive(Routable, Clone, PartialEq, Debug)]
+    /// enum Route {
+    ///     #[route("/")]
+    ///     Home {},
+    ///     #[route("/user/:id", enumerate = users)]
+    ///     User { id: usize },
+    /// }
+    ///
+    /// fn users() -> impl Iterator<Item = Route> {
+    ///     (1..=2).map(|id| Route::User { id })
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Route::all_routes(),
+    ///     [Route::Home {}, Route::User { id: 1 }, Route::User { id: 2 }]
+    /// );
+    /// ```
+    fn all_routes() -> Vec<Self> {
+        let mut routes = Self::static_routes();
+        routes.extend(Self::enumerated_routes());
+
+        // Static child routes are found both in the site map and in the child's enumerated routes
+        let mut seen = std::collections::HashSet::new();
+        routes.retain(|route| seen.insert(route.to_string()));
+        routes
+    }
 }
 
 /// A type erased map of the site structure.
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use dioxus_lib::prelude::*;
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
 /// Global configuration options for the router.
 ///
@@ -23,6 +23,7 @@ pub struct RouterConfig<R> {
     pub(crate) failure_external_navigation: fn() -> Element,
     pub(crate) history: Option<Box<dyn AnyHistoryProvider>>,
     pub(crate) on_update: Option<RoutingCallback<R>>,
+    pub(crate) guards: Vec<NavigationGuard<R>>,
     pub(crate) initial_route: Option<R>,
 }
 
@@ -32,6 +33,7 @@ impl<R> Default for RouterConfig<R> {
             failure_external_navigation: FailureExternalNavigation,
             history: None,
             on_update: None,
+            guards: Vec::new(),
             initial_route: None,
         }
     }
@@ -81,6 +83,53 @@ where
         }
     }
 
+    /// Add a guard that runs before every navigation with [`push`](Navigator::push),
+    /// [`replace`](Navigator::replace), [`go_back`](Navigator::go_back) or [`go_forward`](Navigator::go_forward).
+    ///
+    /// Guards run in the order they were added. Each guard can allow the navigation, cancel it or redirect it to a
+    /// different target. If a guard is async, the navigation finishes once every guard resolves.
+    ///
+    /// Navigations the router does not start, like the browser back button, are not guarded. Use
+    /// [`use_navigation_blocker`] to ask the user before leaving the page.
+    ///
+    /// ```rust,no_run
+    /// # use dioxus_router::prelude::*;
+    /// # use dioxus::prelude::*;
+    /// # #[component]
+    /// # fn Index() -> Element { VNode::empty() }
+    /// # #[component]
+    /// # fn Admin() -> Element { VNode::empty() }
+    /// # #[component]
+    /// # fn Login() -> Element { VNode::empty() }
+    /// # fn logged_in() -> bool { false }
+    /// #[derive(Clone, Routable)]
+    /// enum Route {
+    ///     #[route("/")]
+    ///     Index {},
+    ///     #[route("/admin")]
+    ///     Admin {},
+    ///     #[route("/login")]
+    ///     Login {},
+    /// }
+    ///
+    /// let cfg = RouterConfig::default().guard(|navigation: PendingNavigation<Route>| async move {
+    ///     match navigation.to {
+    ///         Some(NavigationTarget::Internal(Route::Admin {})) if !logged_in() => {
+    ///             GuardResult::Redirect(Route::Login {}.into())
+    ///         }
+    ///         _ => GuardResult::Allow,
+    ///     }
+    /// });
+    /// ```
+    pub fn guard<F>(mut self, guard: impl Fn(PendingNavigation<R>) -> F + 'static) -> Self
+    where
+        F: Future<Output = GuardResult<R>> + 'static,
+    {
+        self.guards
+            .push(Arc::new(move |navigation| Box::pin(guard(navigation))));
+        self
+    }
+
     /// The [`HistoryProvider`] the router should use.
     ///
     /// Defaults to a different history provider depending on the target platform.
@@ -0,0 +1,201 @@
+//! Generate a [sitemap](https://www.sitemaps.org/protocol.html) for the routes of an application.
+//!
+//! ```rust
+//! use dioxus::prelude::*;
+//! use dioxus_router::sitemap::*;
+//!
+//! #[derive(Routable, Clone, PartialEq, Debug)]
+//! enum Route {
+//!     #[route("/")]
+//!     Home {},
+//!     #[route("/blog/:slug", enumerate = blog_posts)]
+//!     BlogPost { slug: String },
+//! }
+//!
+//! fn blog_posts() -> Vec<Route> {
+//!     vec![Route::BlogPost { slug: "hello-world".to_string() }]
+//! }
+//!
+//! let sitemap = Sitemap::new("https://example.com").with_entries(Route::all_routes().into_iter().map(|route| {
+//!     match &route {
+//!         Route::Home {} => SitemapEntry::new(route).priority(1.0),
+//!         Route::BlogPost { .. } => SitemapEntry::new(route)
+//!             .last_modified("2024-06-01")
+//!             .change_frequency(ChangeFrequency::Monthly),
+//!     }
+//! }));
+//!
+//! std::fs::write(std::env::temp_dir().join("sitemap.xml"), sitemap.to_string()).unwrap();
+//! # #[component]
+//! # fn Home() -> Element { VNode::empty() }
+//! # #[component]
+//! # fn BlogPost(slug: String) -> Element { VNode::empty() }
+//! ```
+
+use std::fmt::{Display, Write};
+
+use crate::routable::Routable;
+
+/// How frequently a page is likely to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeFrequency {
+    /// The page changes every time it is accessed
+    Always,
+    /// The page changes about once an hour
+    Hourly,
+    /// The page changes about once a day
+    Daily,
+    /// The page changes about once a week
+    Weekly,
+    /// The page changes about once a month
+    Monthly,
+    /// The page changes about once a year
+    Yearly,
+    /// The page is archived and never changes
+    Never,
+}
+
+impl Display for ChangeFrequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        })
+    }
+}
+
+/// A page in a [`Sitemap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    route: String,
+    last_modified: Option<String>,
+    change_frequency: Option<ChangeFrequency>,
+    priority: Option<f32>,
+}
+
+impl SitemapEntry {
+    /// Create an entry for a route without any metadata.
+    pub fn new(route: impl Display) -> Self {
+        Self {
+            route: route.to_string(),
+            last_modified: None,
+            change_frequency: None,
+            priority: None,
+        }
+    }
+
+    /// The route of the page, relative to the base url of the sitemap.
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    /// Set the date the page was last modified. This should be a [W3C datetime](https://www.w3.org/TR/NOTE-datetime)
+    /// like `2024-06-01` or `2024-06-01T12:00:00+00:00`.
+    pub fn last_modified(mut self, date: impl Into<String>) -> Self {
+        self.last_modified = Some(date.into());
+        self
+    }
+
+    /// Set how frequently the page is likely to change.
+    pub fn change_frequency(mut self, change_frequency: ChangeFrequency) -> Self {
+        self.change_frequency = Some(change_frequency);
+        self
+    }
+
+    /// Set the priority of the page relative to the other pages on the site. The priority is clamped between `0.0` and `1.0`.
+    pub fn priority(mut self, priority: f32) -> Self {
+        self.priority = Some(priority.clamp(0.0, 1.0));
+        self
+    }
+}
+
+/// A sitemap that can be rendered to xml with [`Display`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sitemap {
+    base_url: String,
+    entries: Vec<SitemapEntry>,
+}
+
+impl Sitemap {
+    /// Create an empty sitemap for a site hosted at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Create a sitemap with [every route](Routable::all_routes) of a router.
+    pub fn from_routes<R: Routable>(base_url: impl Into<String>) -> Self {
+        Self::new(base_url).with_entries(R::all_routes().into_iter().map(SitemapEntry::new))
+    }
+
+    /// Add entries to the sitemap.
+    pub fn with_entries(mut self, entries: impl IntoIterator<Item = SitemapEntry>) -> Self {
+        self.entries.extend(entries);
+        self
+    }
+
+    /// The entries in the sitemap.
+    pub fn entries(&self) -> &[SitemapEntry] {
+        &self.entries
+    }
+}
+
+impl Extend<SitemapEntry> for Sitemap {
+    fn extend<T: IntoIterator<Item = SitemapEntry>>(&mut self, iter: T) {
+        self.entries.extend(iter);
+    }
+}
+
+impl Display for Sitemap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let base_url = self.base_url.trim_end_matches('/');
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            f,
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#
+        )?;
+        for entry in &self.entries {
+            f.write_str("  <url>\n    <loc>")?;
+            write_escaped(f, base_url)?;
+            if !entry.route.starts_with('/') {
+                f.write_char('/')?;
+            }
+            write_escaped(f, &entry.route)?;
+            f.write_str("</loc>\n")?;
+            if let Some(last_modified) = &entry.last_modified {
+                f.write_str("    <lastmod>")?;
+                write_escaped(f, last_modified)?;
+                f.write_str("</lastmod>\n")?;
+            }
+            if let Some(change_frequency) = entry.change_frequency {
+                writeln!(f, "    <changefreq>{change_frequency}</changefreq>")?;
+            }
+            if let Some(priority) = entry.priority {
+                writeln!(f, "    <priority>{priority:.1}</priority>")?;
+            }
+            f.write_str("  </url>\n")?;
+        }
+        writeln!(f, "</urlset>")
+    }
+}
+
+fn write_escaped(f: &mut impl Write, text: &str) -> std::fmt::Result {
+    for c in text.chars() {
+        match c {
+            '&' => f.write_str("&amp;")?,
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            '"' => f.write_str("&quot;")?,
+            '\'' => f.write_str("&apos;")?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
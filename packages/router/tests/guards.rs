@@ -0,0 +1,191 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, time::Duration};
+
+#[derive(Routable, Clone, PartialEq, Debug)]
+enum Route {
+    #[route("/")]
+    Home {},
+    #[route("/admin")]
+    Admin {},
+    #[route("/login")]
+    Login {},
+}
+
+thread_local! {
+    static BLOCKER: RefCell<Option<NavigationBlocker>> = const { RefCell::new(None) };
+    static BLOCKING: RefCell<bool> = const { RefCell::new(true) };
+}
+
+#[component]
+fn Home() -> Element {
+    let blocker = use_navigation_blocker(|| BLOCKING.with(|blocking| *blocking.borrow()));
+    BLOCKER.with(|cell| *cell.borrow_mut() = Some(blocker));
+    rsx! {
+        "home"
+        if blocker.is_blocked() {
+            "leave?"
+        }
+    }
+}
+
+#[component]
+fn Admin() -> Element {
+    rsx! { "admin" }
+}
+
+#[component]
+fn Login() -> Element {
+    rsx! { "login" }
+}
+
+#[derive(Props, Clone)]
+struct AppProps {
+    config: fn() -> RouterConfig<Route>,
+}
+
+impl PartialEq for AppProps {
+    fn eq(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+fn App(props: AppProps) -> Element {
+    let config = props.config;
+    rsx! {
+        Router::<Route> { config: move |_| config() }
+    }
+}
+
+fn prepare(config: fn() -> RouterConfig<Route>) -> VirtualDom {
+    let mut vdom = VirtualDom::new_with_props(App, AppProps { config });
+    vdom.rebuild_in_place();
+    vdom
+}
+
+fn navigate(vdom: &mut VirtualDom, navigate: impl FnOnce(RouterContext)) -> String {
+    vdom.in_runtime(|| navigate(root_router().unwrap()));
+    vdom.render_immediate(&mut dioxus_core::NoOpMutations);
+    dioxus_ssr::render(vdom)
+}
+
+fn not_blocking() -> RouterConfig<Route> {
+    BLOCKING.with(|blocking| *blocking.borrow_mut() = false);
+    RouterConfig::default().history(MemoryHistory::default())
+}
+
+#[test]
+fn guards_can_redirect_and_cancel() {
+    let mut vdom = prepare(|| {
+        not_blocking().guard(|navigation: PendingNavigation<Route>| async move {
+            match navigation.to {
+                Some(NavigationTarget::Internal(Route::Admin {})) => {
+                    GuardResult::Redirect(Route::Login {}.into())
+                }
+                None => GuardResult::Cancel,
+                _ => GuardResult::Allow,
+            }
+        })
+    });
+
+    assert_eq!(
+        navigate(&mut vdom, |router| {
+            router.push(Route::Admin {});
+        }),
+        "login"
+    );
+
+    // Going back has no target, so the guard cancels it
+    assert_eq!(navigate(&mut vdom, |router| router.go_back()), "login");
+}
+
+#[tokio::test]
+async fn async_guards_finish_in_the_background() {
+    let mut vdom = prepare(|| {
+        not_blocking().guard(|navigation: PendingNavigation<Route>| async move {
+            assert_eq!(navigation.from, Route::Home {});
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            GuardResult::Allow
+        })
+    });
+
+    // The navigation waits for the guard
+    assert_eq!(
+        navigate(&mut vdom, |router| {
+            router.push(Route::Admin {});
+        }),
+        "home"
+    );
+
+    tokio::time::timeout(Duration::from_secs(1), vdom.wait_for_work())
+        .await
+        .unwrap();
+    vdom.render_immediate(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&vdom), "admin");
+}
+
+#[tokio::test]
+async fn stale_async_guards_are_ignored() {
+    let mut vdom = prepare(|| {
+        not_blocking().guard(|navigation: PendingNavigation<Route>| async move {
+            // The first navigation takes longer to check than the second
+            let delay = match navigation.to {
+                Some(NavigationTarget::Internal(Route::Admin {})) => 50,
+                _ => 10,
+            };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            GuardResult::Allow
+        })
+    });
+
+    navigate(&mut vdom, |router| {
+        router.push(Route::Admin {});
+    });
+    navigate(&mut vdom, |router| {
+        router.push(Route::Login {});
+    });
+
+    // Only the latest navigation is applied, even though the first guard finishes last
+    let _ = tokio::time::timeout(Duration::from_millis(100), async {
+        loop {
+            vdom.wait_for_work().await;
+            vdom.render_immediate(&mut dioxus_core::NoOpMutations);
+        }
+    })
+    .await;
+    assert_eq!(dioxus_ssr::render(&vdom), "login");
+}
+
+#[test]
+fn blockers_hold_navigation_until_it_proceeds() {
+    let mut vdom = prepare(|| {
+        BLOCKING.with(|blocking| *blocking.borrow_mut() = true);
+        RouterConfig::default().history(MemoryHistory::default())
+    });
+
+    assert_eq!(
+        navigate(&mut vdom, |router| {
+            router.push(Route::Admin {});
+        }),
+        "homeleave?"
+    );
+
+    // Resetting keeps the current page
+    assert_eq!(
+        navigate(&mut vdom, |_| {
+            BLOCKER.with(|blocker| blocker.borrow().unwrap().reset());
+        }),
+        "home"
+    );
+
+    navigate(&mut vdom, |router| {
+        router.push(Route::Admin {});
+    });
+    assert_eq!(
+        navigate(&mut vdom, |_| {
+            BLOCKER.with(|blocker| blocker.borrow().unwrap().proceed());
+        }),
+        "admin"
+    );
+}
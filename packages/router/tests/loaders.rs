@@ -0,0 +1,119 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, time::Duration};
+
+#[derive(Routable, Clone, PartialEq, Debug)]
+#[rustfmt::skip]
+enum Route {
+    #[nest("/user/:id")]
+        #[layout(UserFrame, loader = load_user)]
+            #[route("/posts/:post", loader = load_post)]
+            Post { id: usize, post: usize },
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(event: String) {
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+async fn load_user(id: usize) -> String {
+    log(format!("start user {id}"));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    log(format!("end user {id}"));
+    format!("user {id}")
+}
+
+async fn load_post(id: usize, post: usize) -> String {
+    log(format!("start post {post}"));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    log(format!("end post {post}"));
+    format!("post {post} by {id}")
+}
+
+#[component]
+fn UserFrame(id: usize) -> Element {
+    use_hook(|| log("mount frame".to_string()));
+    let user = use_route_data(load_user);
+    rsx! {
+        h1 { "{user}" }
+        Outlet::<Route> {}
+    }
+}
+
+#[component]
+fn Post(id: usize, post: usize) -> Element {
+    let user = use_route_data(load_user);
+    let post = use_route_data(load_post);
+    rsx! { p { "{post} ({user})" } }
+}
+
+fn App() -> Element {
+    rsx! {
+        Router::<Route> {
+            config: |_| {
+                RouterConfig::default()
+                    .history(MemoryHistory::with_initial_path(Route::Post { id: 1, post: 2 }))
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn loaders_run_in_parallel_before_rendering() {
+    let mut vdom = VirtualDom::new(App);
+    vdom.rebuild_in_place();
+    assert_eq!(dioxus_ssr::render(&vdom), "");
+
+    vdom.wait_for_suspense().await;
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "<h1>user 1</h1><p>post 2 by 1 (user 1)</p>"
+    );
+
+    // The post loader starts before the user loader finishes
+    let events = EVENTS.with(|events| events.take());
+    assert_eq!(
+        events[..2],
+        ["start user 1".to_string(), "start post 2".to_string()]
+    );
+
+    // Navigating to a new route loads the data again
+    vdom.in_runtime(|| {
+        root_router().unwrap().push(Route::Post { id: 1, post: 3 });
+    });
+    vdom.render_immediate(&mut dioxus_core::NoOpMutations);
+    vdom.wait_for_suspense().await;
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "<h1>user 1</h1><p>post 3 by 1 (user 1)</p>"
+    );
+}
+
+#[tokio::test]
+async fn changing_a_route_parameter_only_reloads_its_loader() {
+    let mut vdom = VirtualDom::new(App);
+    vdom.rebuild_in_place();
+    vdom.wait_for_suspense().await;
+
+    // Only change the post segment. The user loader's arguments stay the same
+    vdom.in_runtime(|| {
+        root_router().unwrap().push(Route::Post { id: 1, post: 3 });
+    });
+    vdom.render_immediate(&mut dioxus_core::NoOpMutations);
+    vdom.wait_for_suspense().await;
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "<h1>user 1</h1><p>post 3 by 1 (user 1)</p>"
+    );
+
+    // The layout stays mounted and its loader doesn't run again
+    let events = EVENTS.with(|events| events.take());
+    let count = |name: &str| events.iter().filter(|event| *event == name).count();
+    assert_eq!(count("start user 1"), 1);
+    assert_eq!(count("mount frame"), 1);
+    assert!(events.contains(&"start post 3".to_string()));
+}
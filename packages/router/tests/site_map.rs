@@ -51,3 +51,104 @@ fn with_class() {
         ],
     );
 }
+
+#[test]
+fn enumerated_routes() {
+    #[derive(Routable, Clone, PartialEq, Debug)]
+    enum ChildRoute {
+        #[route("/")]
+        ChildRoot {},
+        #[route("/:page", enumerate = pages)]
+        Page { page: usize },
+    }
+
+    fn pages() -> Vec<ChildRoute> {
+        vec![ChildRoute::Page { page: 1 }, ChildRoute::Page { page: 2 }]
+    }
+
+    #[derive(Routable, Clone, PartialEq, Debug)]
+    enum Route {
+        #[route("/")]
+        Root {},
+        #[route("/blog/:slug", enumerate = posts)]
+        Post { slug: String },
+        #[route("/user/:id")]
+        User { id: usize },
+        #[child("/child")]
+        Nested { child: ChildRoute },
+    }
+
+    fn posts() -> impl Iterator<Item = Route> {
+        ["a", "b&c"].into_iter().map(|slug| Route::Post {
+            slug: slug.to_string(),
+        })
+    }
+
+    #[component]
+    fn Root() -> Element {
+        unimplemented!()
+    }
+
+    #[component]
+    fn Post(slug: String) -> Element {
+        unimplemented!()
+    }
+
+    #[component]
+    fn User(id: usize) -> Element {
+        unimplemented!()
+    }
+
+    #[component]
+    fn ChildRoot() -> Element {
+        unimplemented!()
+    }
+
+    #[component]
+    fn Page(page: usize) -> Element {
+        unimplemented!()
+    }
+
+    // Routes without an enumerate function are skipped and static child routes are only included once
+    let routes: Vec<_> = Route::all_routes().iter().map(Route::to_string).collect();
+    assert_eq!(
+        routes,
+        [
+            "/",
+            "/child/",
+            "/blog/a",
+            "/blog/b%26c",
+            "/child/1",
+            "/child/2"
+        ]
+    );
+
+    let sitemap = dioxus_router::sitemap::Sitemap::new("https://example.com/").with_entries(
+        Route::all_routes().into_iter().take(3).map(|route| {
+            let entry = dioxus_router::sitemap::SitemapEntry::new(&route);
+            match route {
+                Route::Root {} => entry.priority(1.0),
+                Route::Post { .. } => entry.last_modified("2024-06-01"),
+                _ => entry,
+            }
+        }),
+    );
+    assert_eq!(
+        sitemap.to_string(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://example.com/</loc>
+    <priority>1.0</priority>
+  </url>
+  <url>
+    <loc>https://example.com/child/</loc>
+  </url>
+  <url>
+    <loc>https://example.com/blog/a</loc>
+    <lastmod>2024-06-01</lastmod>
+  </url>
+</urlset>
+"#
+    );
+}
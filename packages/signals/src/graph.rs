@@ -0,0 +1,342 @@
+use dioxus_core::prelude::*;
+use generational_box::GenerationalBoxId;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    panic::Location,
+    sync::{Mutex, Weak},
+};
+
+type SubscriberSet = Mutex<HashSet<ReactiveContext>>;
+
+/// A signal that was created on this thread
+struct Registered {
+    kind: SignalKind,
+    created_at: Option<&'static Location<'static>>,
+    owner: ScopeId,
+    subscribers: Weak<SubscriberSet>,
+    // Returns false once the value of the signal is dropped
+    alive: Box<dyn Fn() -> bool>,
+    // The reactive context that recomputes the memo, and the number of times the memo was read
+    memo: Option<(ReactiveContext, usize)>,
+}
+
+thread_local! {
+    static SIGNALS: RefCell<HashMap<GenerationalBoxId, Registered>> = RefCell::new(HashMap::new());
+    // Dropped signals are removed once the registry grows past this size
+    static PRUNE_AT: Cell<usize> = const { Cell::new(256) };
+}
+
+/// Record a new signal in the signal graph
+#[cfg(debug_assertions)]
+pub(crate) fn register_signal<T, S>(signal: crate::Signal<T, S>)
+where
+    T: 'static,
+    S: generational_box::Storage<crate::SignalData<T>>,
+{
+    use generational_box::BorrowError;
+
+    let Ok(data) = signal.inner.value.try_read() else {
+        return;
+    };
+    let registered = Registered {
+        kind: SignalKind::Signal,
+        created_at: signal.inner.value.created_at(),
+        owner: signal.origin_scope(),
+        subscribers: std::sync::Arc::downgrade(&data.subscribers),
+        alive: Box::new(move || {
+            !matches!(signal.inner.value.try_read(), Err(BorrowError::Dropped(_)))
+        }),
+        memo: None,
+    };
+    drop(data);
+    _ = SIGNALS.try_with(|signals| {
+        let mut signals = signals.borrow_mut();
+        if signals.len() >= PRUNE_AT.get() {
+            signals.retain(|_, signal| (signal.alive)());
+            PRUNE_AT.set((signals.len() * 2).max(256));
+        }
+        signals.insert(signal.id(), registered);
+    });
+}
+
+/// Mark a signal as the value of a memo that is recomputed by `context`
+#[cfg(debug_assertions)]
+pub(crate) fn register_memo(signal: GenerationalBoxId, context: ReactiveContext) {
+    _ = SIGNALS.try_with(|signals| {
+        if let Some(signal) = signals.borrow_mut().get_mut(&signal) {
+            signal.kind = SignalKind::Memo;
+            signal.memo = Some((context, 0));
+        }
+    });
+}
+
+/// Record a read of a memo
+#[cfg(debug_assertions)]
+pub(crate) fn memo_read(signal: GenerationalBoxId) {
+    _ = SIGNALS.try_with(|signals| {
+        if let Some((_, reads)) = signals
+            .borrow_mut()
+            .get_mut(&signal)
+            .and_then(|signal| signal.memo.as_mut())
+        {
+            *reads += 1;
+        }
+    });
+}
+
+/// The kind of reactive value in a [`SignalGraph`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SignalKind {
+    /// A [`Signal`], including signals that back other reactive values like resources.
+    Signal,
+    /// A [`Memo`](crate::Memo).
+    Memo,
+}
+
+/// A snapshot of the reactive graph: every signal and memo that is alive on the current thread, and the reactive
+/// contexts that subscribe to them.
+///
+/// Signals are only tracked in debug builds. In release builds, the graph is always empty.
+///
+/// The graph can find common reactivity bugs:
+/// - [`SignalGraph::leaks`] finds signals that are still alive after the scope that created them was dropped. This
+///   usually means the signal was created with an [`Owner`](generational_box::Owner) that is never dropped.
+/// - [`SignalGraph::unread_memos`] finds memos that are kept up to date, but never read.
+///
+/// It can also be exported to [DOT](SignalGraph::to_dot) or [JSON](SignalGraph::to_json) to visualize the graph:
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// fn app() -> Element {
+///     let count = use_signal(|| 0);
+///     let doubled = use_memo(move || count() * 2);
+///
+///     rsx! {
+///         button {
+///             onclick: move |_| {
+///                 let graph = SignalGraph::capture();
+///                 // Paste the output into graphviz to see which components and memos read each signal
+///                 println!("{}", graph.to_dot());
+///             },
+///             "{doubled}"
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SignalGraph {
+    /// Every signal and memo that is alive.
+    pub signals: Vec<SignalNode>,
+}
+
+/// A signal or memo in a [`SignalGraph`].
+#[derive(Clone, Debug)]
+pub struct SignalNode {
+    /// The id of the signal.
+    pub id: GenerationalBoxId,
+    /// If the node is a signal or a memo.
+    pub kind: SignalKind,
+    /// Where the signal was created.
+    pub created_at: Option<&'static Location<'static>>,
+    /// The scope the signal was created in.
+    pub owner: ScopeId,
+    /// If the scope the signal was created in is still alive.
+    pub owner_alive: bool,
+    /// The reactive contexts that rerun when the signal changes.
+    pub subscribers: Vec<SubscriberNode>,
+    /// How many times the memo was read. This is None for signals.
+    pub reads: Option<usize>,
+}
+
+/// A reactive context that subscribes to a signal in a [`SignalGraph`].
+#[derive(Clone, Debug)]
+pub struct SubscriberNode {
+    /// The id of the reactive context.
+    pub id: GenerationalBoxId,
+    /// The component that reruns when the reactive context is marked dirty.
+    pub scope: Option<ScopeId>,
+    /// The memo that is recomputed when the reactive context is marked dirty.
+    pub memo: Option<GenerationalBoxId>,
+    /// Where the reactive context was created.
+    pub created_at: Option<&'static Location<'static>>,
+}
+
+impl SignalGraph {
+    /// Capture the signals that are currently alive on this thread.
+    ///
+    /// This reads every signal, so it should not be called while a signal is borrowed mutably on this thread.
+    pub fn capture() -> Self {
+        let signals = SIGNALS
+            .try_with(|signals| {
+                let mut signals = signals.borrow_mut();
+                signals.retain(|_, signal| (signal.alive)());
+                let memos: HashMap<_, _> = signals
+                    .iter()
+                    .filter_map(|(id, signal)| Some((signal.memo.as_ref()?.0.id(), *id)))
+                    .collect();
+
+                signals
+                    .iter()
+                    .map(|(id, signal)| {
+                        let subscribers = signal
+                            .subscribers
+                            .upgrade()
+                            .map(|subscribers| {
+                                let subscribers = subscribers.lock().unwrap();
+                                subscribers
+                                    .iter()
+                                    .map(|context| SubscriberNode {
+                                        id: context.id(),
+                                        scope: context.rerun_scope(),
+                                        memo: memos.get(&context.id()).copied(),
+                                        created_at: context.created_at(),
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        SignalNode {
+                            id: *id,
+                            kind: signal.kind,
+                            created_at: signal.created_at,
+                            owner: signal.owner,
+                            owner_alive: signal.owner.is_alive(),
+                            subscribers,
+                            reads: signal.memo.as_ref().map(|(_, reads)| *reads),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { signals }
+    }
+
+    /// Get the signals that are still alive after the scope that created them was dropped.
+    pub fn leaks(&self) -> impl Iterator<Item = &SignalNode> {
+        self.signals.iter().filter(|signal| !signal.owner_alive)
+    }
+
+    /// Get the memos that were never read.
+    pub fn unread_memos(&self) -> impl Iterator<Item = &SignalNode> {
+        self.signals.iter().filter(|signal| signal.reads == Some(0))
+    }
+
+    /// Export the graph in the [DOT](https://graphviz.org/doc/info/lang.html) format. Each signal has an edge to
+    /// every component or memo that subscribes to it. Leaked signals and unread memos are highlighted in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph signals {\n");
+        let mut contexts = HashSet::new();
+        for signal in &self.signals {
+            let shape = match signal.kind {
+                SignalKind::Signal => "box",
+                SignalKind::Memo => "ellipse",
+            };
+            let color = if !signal.owner_alive || signal.reads == Some(0) {
+                "red"
+            } else {
+                "black"
+            };
+            let label = format!("{:?}\n{}", signal.kind, location(signal.created_at));
+            _ = writeln!(
+                dot,
+                "  \"{:?}\" [shape={shape}, color={color}, label={}];",
+                signal.id,
+                dot_string(&label)
+            );
+            for subscriber in &signal.subscribers {
+                // Memos already have a node
+                let target = match subscriber.memo {
+                    Some(memo) => memo,
+                    None => {
+                        if contexts.insert(subscriber.id) {
+                            let label = match subscriber.scope {
+                                Some(scope) => {
+                                    format!("{} ({scope:?})", scope.name().unwrap_or("Component"))
+                                }
+                                None => location(subscriber.created_at),
+                            };
+                            _ = writeln!(
+                                dot,
+                                "  \"{:?}\" [shape=component, label={}];",
+                                subscriber.id,
+                                dot_string(&label)
+                            );
+                        }
+                        subscriber.id
+                    }
+                };
+                _ = writeln!(dot, "  \"{:?}\" -> \"{:?}\";", signal.id, target);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Export the graph as JSON. The JSON is a list of signals with the same fields as [`SignalNode`].
+    pub fn to_json(&self) -> String {
+        let signals: Vec<String> = self
+            .signals
+            .iter()
+            .map(|signal| {
+                let subscribers: Vec<String> = signal
+                    .subscribers
+                    .iter()
+                    .map(|subscriber| {
+                        format!(
+                            r#"{{"id":{},"scope":{},"memo":{},"created_at":{}}}"#,
+                            json_string(&format!("{:?}", subscriber.id)),
+                            json_option(subscriber.scope.map(|scope| scope.0)),
+                            json_option(
+                                subscriber
+                                    .memo
+                                    .map(|memo| json_string(&format!("{memo:?}")))
+                            ),
+                            json_option(subscriber.created_at.map(|at| json_string(&at.to_string()))),
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#"{{"id":{},"kind":"{:?}","created_at":{},"owner":{},"owner_alive":{},"reads":{},"subscribers":[{}]}}"#,
+                    json_string(&format!("{:?}", signal.id)),
+                    signal.kind,
+                    json_option(signal.created_at.map(|at| json_string(&at.to_string()))),
+                    signal.owner.0,
+                    signal.owner_alive,
+                    json_option(signal.reads),
+                    subscribers.join(",")
+                )
+            })
+            .collect();
+        format!("[{}]", signals.join(","))
+    }
+}
+
+fn location(location: Option<&'static Location<'static>>) -> String {
+    location.map_or_else(|| "unknown location".to_string(), |at| at.to_string())
+}
+
+fn dot_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => _ = write!(escaped, "\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_option(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,402 @@
+use crate::{subscribers::Subscribers, CopyValue, Readable, Signal, Writable};
+use dioxus_core::prelude::*;
+use generational_box::GenerationalBoxId;
+use instant::{Duration, Instant};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Called before a tracked signal is written with the current value of the signal. Returns a value to drop after the
+/// write finishes.
+type WriteHook = Rc<dyn Fn(&dyn Any) -> Option<Box<dyn Any>>>;
+
+thread_local! {
+    // The histories that track each signal
+    static TRACKED: RefCell<HashMap<GenerationalBoxId, Vec<(GenerationalBoxId, WriteHook)>>> =
+        RefCell::new(HashMap::new());
+}
+
+// The number of signals tracked by any history on any thread. Most apps never use a history, so writes skip the
+// thread local lookup while this is zero
+static TRACKED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Record a write to a signal in every history that tracks it. This is called by [`Signal`] before it hands out a
+/// mutable reference to its value.
+pub(crate) fn before_write(signal: GenerationalBoxId, value: &dyn Any) -> Option<Box<dyn Any>> {
+    // Histories only register hooks on the thread they track signals on, so a relaxed load sees them
+    if TRACKED_COUNT.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    let hooks: Vec<WriteHook> = TRACKED
+        .try_with(|tracked| {
+            tracked
+                .borrow()
+                .get(&signal)
+                .map(|hooks| hooks.iter().map(|(_, hook)| hook.clone()).collect())
+        })
+        .ok()
+        .flatten()?;
+    let after: Vec<Box<dyn Any>> = hooks.iter().filter_map(|hook| hook(value)).collect();
+    (!after.is_empty()).then(|| Box::new(after) as Box<dyn Any>)
+}
+
+/// Undo and redo writes to a group of signals.
+///
+/// Every write made through [`Writable::write`] (or any method that uses it like `set` or `+=`) to a signal that is
+/// [tracked](History::track) is recorded as an undoable step:
+/// - Use [`History::batch`] to group writes to several signals into one step.
+/// - Use [`History::with_coalesce`] to merge writes to the same signal that happen in quick succession (like typing in
+///   a text input) into one step.
+/// - The oldest steps are forgotten once there are more than [`History::with_max_depth`] steps.
+///
+/// [`History::can_undo`] and [`History::can_redo`] subscribe to the history, so buttons that use them update
+/// automatically.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use std::time::Duration;
+///
+/// fn Editor() -> Element {
+///     let mut text = use_signal(String::new);
+///     let mut bold = use_signal(|| false);
+///     let history = use_hook(|| {
+///         let history = History::new().with_coalesce(Duration::from_millis(500));
+///         history.track(text);
+///         history.track(bold);
+///         history
+///     });
+///
+///     rsx! {
+///         input { value: "{text}", oninput: move |event| text.set(event.value()) }
+///         button {
+///             // Clearing the text and the formatting is undone in one step
+///             onclick: move |_| history.batch(|| {
+///                 text.set(String::new());
+///                 bold.set(false);
+///             }),
+///             "Clear"
+///         }
+///         button { disabled: !history.can_undo(), onclick: move |_| { history.undo(); }, "Undo" }
+///         button { disabled: !history.can_redo(), onclick: move |_| { history.redo(); }, "Redo" }
+///     }
+/// }
+/// ```
+pub struct History {
+    inner: CopyValue<HistoryData>,
+}
+
+struct HistoryData {
+    id: Option<GenerationalBoxId>,
+    undo: VecDeque<Step>,
+    redo: Vec<Step>,
+    max_depth: usize,
+    coalesce: Duration,
+    // The last write that was recorded, and when it happened
+    last_write: Option<(GenerationalBoxId, Instant)>,
+    // The step that is being built by `History::batch`
+    batch: Option<Step>,
+    // Writes made while undoing or redoing are not recorded
+    restoring: bool,
+    tracked: Vec<GenerationalBoxId>,
+    // Subscribers that read if the history can undo or redo
+    subscribers: Subscribers,
+}
+
+/// An undoable step with the value of every signal before the step
+#[derive(Default)]
+struct Step {
+    changes: Vec<Change>,
+}
+
+struct Change {
+    signal: GenerationalBoxId,
+    value: Box<dyn Any>,
+    // Replace the value of the signal and return the old value. Returns None if the signal was dropped
+    restore: Rc<dyn Fn(Box<dyn Any>) -> Option<Box<dyn Any>>>,
+}
+
+impl Step {
+    fn record(&mut self, change: Change) {
+        // Only the value before the first write matters
+        if !self.changes.iter().any(|c| c.signal == change.signal) {
+            self.changes.push(change);
+        }
+    }
+
+    /// Restore every value in the step, and return the step that reverts it
+    fn restore(self) -> Step {
+        let mut reverse = Step::default();
+        for change in self.changes.into_iter().rev() {
+            if let Some(value) = (change.restore)(change.value) {
+                reverse.changes.push(Change {
+                    signal: change.signal,
+                    value,
+                    restore: change.restore,
+                });
+            }
+        }
+        reverse
+    }
+}
+
+impl History {
+    /// Create a new history owned by the current scope. The history keeps up to 100 steps and doesn't coalesce writes.
+    #[track_caller]
+    pub fn new() -> Self {
+        Self::new_in_scope(current_scope_id().expect("in a virtual dom"))
+    }
+
+    /// Create a new history with a custom owner scope. The history stops tracking signals when the owner scope is dropped.
+    #[track_caller]
+    pub fn new_in_scope(owner: ScopeId) -> Self {
+        let inner = CopyValue::new_in_scope(
+            HistoryData {
+                id: None,
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                max_depth: 100,
+                coalesce: Duration::ZERO,
+                last_write: None,
+                batch: None,
+                restoring: false,
+                tracked: Vec::new(),
+                subscribers: Subscribers::default(),
+            },
+            owner,
+        );
+        inner.write_unchecked().id = Some(inner.id());
+        Self { inner }
+    }
+
+    /// Set the maximum number of steps the history keeps. Once there are more steps, the oldest steps are forgotten.
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        self.inner.write_unchecked().max_depth = max_depth;
+        self
+    }
+
+    /// Merge writes to the same signal into one step if they happen within `window` of the last write.
+    pub fn with_coalesce(self, window: Duration) -> Self {
+        self.inner.write_unchecked().coalesce = window;
+        self
+    }
+
+    /// Record every write to a signal in this history.
+    pub fn track<T: Clone + 'static>(&self, signal: Signal<T>) {
+        let id = signal.id();
+        let history = *self;
+        let restore = Rc::new(move |value: Box<dyn Any>| {
+            let value = *value.downcast::<T>().ok()?;
+            let mut signal = signal;
+            let mut write = signal.try_write().ok()?;
+            Some(Box::new(std::mem::replace(&mut *write, value)) as Box<dyn Any>)
+        });
+        let hook: WriteHook = Rc::new(move |value: &dyn Any| {
+            let value = value.downcast_ref::<T>()?.clone();
+            history.record(Change {
+                signal: id,
+                value: Box::new(value),
+                restore: restore.clone(),
+            })
+        });
+
+        let mut inner = self.inner.write_unchecked();
+        if inner.tracked.contains(&id) {
+            return;
+        }
+        inner.tracked.push(id);
+        let history_id = self.inner.id();
+        TRACKED.with_borrow_mut(|tracked| tracked.entry(id).or_default().push((history_id, hook)));
+        TRACKED_COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Stop recording writes to a signal. Steps that were already recorded can still be undone.
+    pub fn untrack<T: 'static>(&self, signal: Signal<T>) {
+        let id = signal.id();
+        self.inner
+            .write_unchecked()
+            .tracked
+            .retain(|tracked| *tracked != id);
+        untrack(id, self.inner.id());
+    }
+
+    /// Group every write made inside `f` into one undoable step.
+    pub fn batch<O>(&self, f: impl FnOnce() -> O) -> O {
+        // Batches inside of batches are part of the outer batch
+        if self.inner.peek().batch.is_some() {
+            return f();
+        }
+
+        self.inner.write_unchecked().batch = Some(Step::default());
+        let output = f();
+        let step = self.inner.write_unchecked().batch.take();
+        if let Some(step) = step.filter(|step| !step.changes.is_empty()) {
+            let mut inner = self.inner.write_unchecked();
+            inner.last_write = None;
+            inner.push(step);
+            let subscribers = inner.subscribers.clone();
+            drop(inner);
+            subscribers.notify();
+        }
+        output
+    }
+
+    /// Undo the last step. Returns false if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        let Some(step) = self.inner.write_unchecked().undo.pop_back() else {
+            return false;
+        };
+        let redo = self.restore(step);
+        self.inner.write_unchecked().redo.push(redo);
+        self.inner.peek().subscribers.notify();
+        true
+    }
+
+    /// Redo the last step that was undone. Returns false if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        let Some(step) = self.inner.write_unchecked().redo.pop() else {
+            return false;
+        };
+        let undo = self.restore(step);
+        self.inner.write_unchecked().undo.push_back(undo);
+        self.inner.peek().subscribers.notify();
+        true
+    }
+
+    /// Check if there is a step to undo. This subscribes to the history.
+    #[track_caller]
+    pub fn can_undo(&self) -> bool {
+        let inner = self.inner.read();
+        inner.subscribers.subscribe();
+        !inner.undo.is_empty()
+    }
+
+    /// Check if there is a step to redo. This subscribes to the history.
+    #[track_caller]
+    pub fn can_redo(&self) -> bool {
+        let inner = self.inner.read();
+        inner.subscribers.subscribe();
+        !inner.redo.is_empty()
+    }
+
+    /// Forget every step in the history.
+    pub fn clear(&self) {
+        let mut inner = self.inner.write_unchecked();
+        inner.undo.clear();
+        inner.redo.clear();
+        inner.last_write = None;
+        let subscribers = inner.subscribers.clone();
+        drop(inner);
+        subscribers.notify();
+    }
+
+    fn restore(&self, step: Step) -> Step {
+        self.inner.write_unchecked().restoring = true;
+        let reverse = step.restore();
+        let mut inner = self.inner.write_unchecked();
+        inner.restoring = false;
+        inner.last_write = None;
+        reverse
+    }
+
+    /// Record a write to a tracked signal. Returns a value that notifies the subscribers of the history when it is
+    /// dropped after the write finishes
+    fn record(&self, change: Change) -> Option<Box<dyn Any>> {
+        let mut inner = self.inner.try_write_unchecked().ok()?;
+        if inner.restoring {
+            return None;
+        }
+        if let Some(batch) = &mut inner.batch {
+            batch.record(change);
+            return None;
+        }
+
+        let now = Instant::now();
+        let coalesce = matches!(
+            inner.last_write,
+            Some((signal, at)) if signal == change.signal
+                && !inner.coalesce.is_zero()
+                && now.duration_since(at) <= inner.coalesce
+        );
+        inner.last_write = Some((change.signal, now));
+        if coalesce && inner.redo.is_empty() {
+            if let Some(last) = inner.undo.back_mut() {
+                last.record(change);
+                return None;
+            }
+        }
+
+        inner.push(Step {
+            changes: vec![change],
+        });
+        Some(Box::new(NotifyOnDrop(inner.subscribers.clone())))
+    }
+}
+
+impl HistoryData {
+    /// Add a new step and forget the steps that were undone
+    fn push(&mut self, step: Step) {
+        self.redo.clear();
+        self.undo.push_back(step);
+        while self.undo.len() > self.max_depth {
+            self.undo.pop_front();
+        }
+    }
+}
+
+impl Drop for HistoryData {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            for signal in &self.tracked {
+                untrack(*signal, id);
+            }
+        }
+    }
+}
+
+fn untrack(signal: GenerationalBoxId, history: GenerationalBoxId) {
+    _ = TRACKED.try_with(|tracked| {
+        let mut tracked = tracked.borrow_mut();
+        if let Some(hooks) = tracked.get_mut(&signal) {
+            let before = hooks.len();
+            hooks.retain(|(id, _)| *id != history);
+            TRACKED_COUNT.fetch_sub(before - hooks.len(), Ordering::Relaxed);
+            if hooks.is_empty() {
+                tracked.remove(&signal);
+            }
+        }
+    });
+}
+
+/// Notifies the subscribers of a history after the write that changed it finishes
+struct NotifyOnDrop(Subscribers);
+
+impl Drop for NotifyOnDrop {
+    fn drop(&mut self) {
+        self.0.notify();
+    }
+}
+
+impl Default for History {
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for History {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for History {}
+
+impl PartialEq for History {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
@@ -22,9 +22,33 @@ pub use set_compare::*;
 mod memo;
 pub use memo::*;
 
+mod signal_vec;
+pub use signal_vec::*;
+
+mod signal_map;
+pub use signal_map::*;
+
+#[cfg(feature = "persistent")]
+mod persistent;
+#[cfg(feature = "persistent")]
+pub use persistent::*;
+
+mod history;
+pub use history::*;
+
+mod store;
+pub use store::*;
+
+mod subscribers;
+
 mod global;
 pub use global::*;
 
+mod graph;
+pub use graph::*;
+
+mod stream;
+
 mod impls;
 
 pub use generational_box::{
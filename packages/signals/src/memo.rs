@@ -75,6 +75,8 @@ impl<T: 'static> Memo<T> {
             callback: recompute,
         });
         let state: Signal<T> = Signal::new_with_caller(value, location);
+        #[cfg(debug_assertions)]
+        crate::graph::register_memo(state.id(), rc);
 
         let memo = Memo {
             inner: state,
@@ -136,6 +138,8 @@ where
     ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
         // Read the inner generational box instead of the signal so we have more fine grained control over exactly when the subscription happens
         let read = self.inner.inner.try_read_unchecked()?;
+        #[cfg(debug_assertions)]
+        crate::graph::memo_read(self.id());
 
         let needs_update = self
             .update
@@ -165,6 +169,8 @@ where
     /// If the signal has been dropped, this will panic.
     #[track_caller]
     fn try_peek_unchecked(&self) -> BorrowResult<ReadableRef<'static, Self>> {
+        #[cfg(debug_assertions)]
+        crate::graph::memo_read(self.id());
         self.inner.try_peek_unchecked()
     }
 }
@@ -0,0 +1,398 @@
+use crate::{read::Readable, read_impls, write::Writable, write_impls, CopyValue, ReadableRef};
+use crate::{Signal, WritableRef, Write};
+use dioxus_core::prelude::*;
+use generational_box::{BorrowError, BorrowMutError, UnsyncStorage};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{cell::RefCell, collections::HashMap, ops::Deref, rc::Rc};
+
+/// A key-value store that [`PersistentSignal`]s save their values to.
+///
+/// Renderers provide the default storage for their platform as a root context of type `Rc<dyn PersistentStorage>`:
+/// - `dioxus-web` saves values in `localStorage` and keeps signals in sync with other tabs.
+/// - `dioxus-desktop` saves values in a JSON file inside the data directory set with `Config::with_data_directory`.
+///
+/// You can replace the default storage for the whole app with [`provide_persistent_storage`], or pick the storage
+/// of a single signal with [`PersistentSignal::new_with_storage`]. If there is no storage (like when rendering on the
+/// server), persistent signals behave like normal signals.
+pub trait PersistentStorage {
+    /// Get the value saved for a key.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Save the value for a key.
+    fn set(&self, key: &str, value: String);
+
+    /// Remove the value saved for a key.
+    fn remove(&self, key: &str);
+
+    /// Watch for changes to a key made outside of this app, like in another browser tab. `on_change` is called with
+    /// the new value of the key until the returned subscription is dropped.
+    ///
+    /// The default implementation never calls `on_change`.
+    fn subscribe(&self, key: &str, on_change: Box<dyn Fn(Option<String>)>) -> StorageSubscription {
+        let _ = (key, on_change);
+        StorageSubscription::default()
+    }
+
+    /// If this returns true, signals start with their initial value and only load the saved value after the first
+    /// render. Storage that is used while hydrating html rendered on the server should return true so the first
+    /// render on the client matches the server.
+    fn load_after_hydration(&self) -> bool {
+        false
+    }
+}
+
+/// A subscription to changes in a [`PersistentStorage`]. The subscription is cancelled when it is dropped.
+#[derive(Default)]
+pub struct StorageSubscription {
+    on_drop: Option<Box<dyn FnOnce()>>,
+}
+
+impl StorageSubscription {
+    /// Create a subscription that runs `on_drop` when it is cancelled.
+    pub fn new(on_drop: impl FnOnce() + 'static) -> Self {
+        Self {
+            on_drop: Some(Box::new(on_drop)),
+        }
+    }
+}
+
+impl Drop for StorageSubscription {
+    fn drop(&mut self) {
+        if let Some(on_drop) = self.on_drop.take() {
+            on_drop();
+        }
+    }
+}
+
+/// Use a different [`PersistentStorage`] for every persistent signal in the app.
+///
+/// This should be called in the root component before any persistent signals are created.
+pub fn provide_persistent_storage(storage: impl PersistentStorage + 'static) {
+    provide_root_context(Rc::new(storage) as Rc<dyn PersistentStorage>);
+}
+
+/// A [`PersistentStorage`] that keeps values in memory. This is useful for tests.
+///
+/// Clones of the storage share the same values.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    values: Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl PersistentStorage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: String) {
+        self.values.borrow_mut().insert(key.to_string(), value);
+    }
+
+    fn remove(&self, key: &str) {
+        self.values.borrow_mut().remove(key);
+    }
+}
+
+/// A [`PersistentStorage`] that saves every value in a single JSON file.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    path: std::path::PathBuf,
+    values: RefCell<Option<HashMap<String, String>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// Create a storage that saves values to the file at `path`. The file and its parent directories are created when
+    /// the first value is saved.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            values: RefCell::new(None),
+        }
+    }
+
+    /// The values in the file. The file is read the first time a value is needed
+    fn with_values<O>(&self, f: impl FnOnce(&mut HashMap<String, String>) -> O) -> O {
+        let mut values = self.values.borrow_mut();
+        let values = values.get_or_insert_with(|| match std::fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|err| {
+                tracing::error!(
+                    "Failed to parse persistent storage file {:?}: {err}",
+                    self.path
+                );
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        });
+        f(values)
+    }
+
+    fn save(&self, values: &HashMap<String, String>) {
+        if let Some(parent) = self.path.parent() {
+            _ = std::fs::create_dir_all(parent);
+        }
+        let result = serde_json::to_string_pretty(values)
+            .map_err(std::io::Error::from)
+            .and_then(|contents| std::fs::write(&self.path, contents));
+        if let Err(err) = result {
+            tracing::error!(
+                "Failed to write persistent storage file {:?}: {err}",
+                self.path
+            );
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PersistentStorage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.with_values(|values| values.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: String) {
+        self.with_values(|values| {
+            values.insert(key.to_string(), value);
+            self.save(values);
+        })
+    }
+
+    fn remove(&self, key: &str) {
+        self.with_values(|values| {
+            if values.remove(key).is_some() {
+                self.save(values);
+            }
+        })
+    }
+}
+
+/// A signal that saves its value to a [`PersistentStorage`] every time it is written, and loads the saved value the
+/// next time the app starts.
+///
+/// Values are encoded as JSON with serde. If the saved value cannot be decoded (for example because the type changed),
+/// the signal starts with its initial value instead.
+///
+/// ```rust, no_run
+/// use dioxus::prelude::*;
+///
+/// fn App() -> Element {
+///     // The theme is remembered after the app restarts
+///     let mut dark_mode = use_hook(|| PersistentSignal::new("dark_mode", || false));
+///
+///     rsx! {
+///         button { onclick: move |_| dark_mode.toggle(), "Dark mode: {dark_mode}" }
+///     }
+/// }
+/// ```
+pub struct PersistentSignal<T: 'static> {
+    signal: Signal<T>,
+    persisted: CopyValue<Option<Persisted<T>>>,
+}
+
+struct Persisted<T> {
+    key: String,
+    storage: Rc<dyn PersistentStorage>,
+    encode: fn(&T) -> serde_json::Result<String>,
+    _subscription: StorageSubscription,
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> PersistentSignal<T> {
+    /// Create a new persistent signal owned by the current scope that uses the [`PersistentStorage`] from the context.
+    ///
+    /// If a value was saved for `key`, the signal starts with that value. Otherwise it starts with the value from `init`.
+    #[track_caller]
+    pub fn new(key: impl Into<String>, init: impl FnOnce() -> T) -> Self {
+        Self::new_inner(key.into(), init, try_consume_context())
+    }
+
+    /// Create a new persistent signal owned by the current scope that saves its value to a specific storage.
+    #[track_caller]
+    pub fn new_with_storage(
+        key: impl Into<String>,
+        init: impl FnOnce() -> T,
+        storage: impl PersistentStorage + 'static,
+    ) -> Self {
+        Self::new_inner(key.into(), init, Some(Rc::new(storage)))
+    }
+
+    #[track_caller]
+    fn new_inner(
+        key: String,
+        init: impl FnOnce() -> T,
+        storage: Option<Rc<dyn PersistentStorage>>,
+    ) -> Self {
+        let owner = current_scope_id().expect("in a virtual dom");
+        let Some(storage) = storage else {
+            return Self {
+                signal: Signal::new_in_scope(init(), owner),
+                persisted: CopyValue::new_in_scope(None, owner),
+            };
+        };
+
+        let load_later = storage.load_after_hydration();
+        let saved = match load_later {
+            true => None,
+            false => load(&*storage, &key),
+        };
+        let signal = Signal::new_in_scope(saved.unwrap_or_else(init), owner);
+
+        // Loading the saved value doesn't write it back to the storage, so we write to the inner signal directly
+        if load_later {
+            let storage = storage.clone();
+            let key = key.clone();
+            queue_effect(move || {
+                if let Some(value) = load(&*storage, &key) {
+                    let mut signal = signal;
+                    signal.set(value);
+                }
+            });
+        }
+        let subscription = storage.subscribe(&key, {
+            let key = key.clone();
+            Box::new(move |value| {
+                let Some(value) = value.and_then(|value| decode(&key, &value)) else {
+                    return;
+                };
+                let mut signal = signal;
+                if let Ok(mut write) = signal.try_write() {
+                    *write = value;
+                };
+            })
+        });
+
+        Self {
+            signal,
+            persisted: CopyValue::new_in_scope(
+                Some(Persisted {
+                    key,
+                    storage,
+                    encode: |value| serde_json::to_string(value),
+                    _subscription: subscription,
+                }),
+                owner,
+            ),
+        }
+    }
+
+    /// Remove the saved value from the storage. The current value of the signal is not changed, but it won't be
+    /// restored the next time the app starts unless the signal is written again.
+    pub fn forget(&self) {
+        if let Some(persisted) = &*self.persisted.peek() {
+            persisted.storage.remove(&persisted.key);
+        }
+    }
+}
+
+impl<T: 'static> PersistentSignal<T> {
+    /// Save the current value to the storage
+    fn save(&self) {
+        let persisted = self.persisted.peek();
+        let Some(persisted) = &*persisted else {
+            return;
+        };
+        match (persisted.encode)(&*self.signal.peek()) {
+            Ok(value) => persisted.storage.set(&persisted.key, value),
+            Err(err) => tracing::error!(
+                "Failed to encode persistent signal {:?}: {err}",
+                persisted.key
+            ),
+        }
+    }
+}
+
+fn load<T: DeserializeOwned>(storage: &dyn PersistentStorage, key: &str) -> Option<T> {
+    decode(key, &storage.get(key)?)
+}
+
+fn decode<T: DeserializeOwned>(key: &str, value: &str) -> Option<T> {
+    match serde_json::from_str(value) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            tracing::warn!("Failed to decode the saved value of persistent signal {key:?}: {err}");
+            None
+        }
+    }
+}
+
+impl<T: 'static> Readable for PersistentSignal<T> {
+    type Target = T;
+    type Storage = UnsyncStorage;
+
+    #[track_caller]
+    fn try_read_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        self.signal.try_read_unchecked()
+    }
+
+    #[track_caller]
+    fn try_peek_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        self.signal.try_peek_unchecked()
+    }
+}
+
+impl<T: 'static> Writable for PersistentSignal<T> {
+    type Mut<'a, R: ?Sized + 'static> = Write<'a, R, UnsyncStorage>;
+
+    fn map_mut<I: ?Sized, U: ?Sized + 'static, F: FnOnce(&mut I) -> &mut U>(
+        ref_: Self::Mut<'_, I>,
+        f: F,
+    ) -> Self::Mut<'_, U> {
+        Write::map(ref_, f)
+    }
+
+    fn try_map_mut<
+        I: ?Sized + 'static,
+        U: ?Sized + 'static,
+        F: FnOnce(&mut I) -> Option<&mut U>,
+    >(
+        ref_: Self::Mut<'_, I>,
+        f: F,
+    ) -> Option<Self::Mut<'_, U>> {
+        Write::filter_map(ref_, f)
+    }
+
+    fn downcast_lifetime_mut<'a: 'b, 'b, R: ?Sized + 'static>(
+        mut_: Self::Mut<'a, R>,
+    ) -> Self::Mut<'b, R> {
+        Write::downcast_lifetime(mut_)
+    }
+
+    #[track_caller]
+    fn try_write_unchecked(&self) -> Result<WritableRef<'static, Self>, BorrowMutError> {
+        self.signal
+            .try_write_unchecked()
+            .map(|write| Write::with_drop(write, Box::new(SaveOnDrop(*self))))
+    }
+}
+
+struct SaveOnDrop<T: 'static>(PersistentSignal<T>);
+
+impl<T: 'static> Drop for SaveOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.save();
+    }
+}
+
+impl<T: 'static> Clone for PersistentSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for PersistentSignal<T> {}
+
+impl<T: 'static> PartialEq for PersistentSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.signal == other.signal
+    }
+}
+
+/// Allow calling a persistent signal with signal() syntax
+impl<T: Clone + 'static> Deref for PersistentSignal<T> {
+    type Target = dyn Fn() -> T;
+
+    fn deref(&self) -> &Self::Target {
+        Readable::deref_impl(self)
+    }
+}
+
+read_impls!(PersistentSignal<T>);
+write_impls!(PersistentSignal<T>);
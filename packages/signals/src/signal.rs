@@ -146,12 +146,15 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
     #[track_caller]
     #[tracing::instrument(skip(value))]
     pub fn new_maybe_sync(value: T) -> Self {
-        Self {
+        let signal = Self {
             inner: CopyValue::<SignalData<T>, S>::new_maybe_sync(SignalData {
                 subscribers: Default::default(),
                 value,
             }),
-        }
+        };
+        #[cfg(debug_assertions)]
+        crate::graph::register_signal(signal);
+        signal
     }
 
     /// Creates a new Signal with an explicit caller. Signals are a Copy state management solution with automatic dependency tracking.
@@ -168,7 +171,7 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
     /// }
     /// ```
     pub fn new_with_caller(value: T, caller: &'static std::panic::Location<'static>) -> Self {
-        Self {
+        let signal = Self {
             inner: CopyValue::new_with_caller(
                 SignalData {
                     subscribers: Default::default(),
@@ -176,7 +179,10 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
                 },
                 caller,
             ),
-        }
+        };
+        #[cfg(debug_assertions)]
+        crate::graph::register_signal(signal);
+        signal
     }
 
     /// Create a new signal with a custom owner scope. The signal will be dropped when the owner scope is dropped instead of the current scope.
@@ -193,7 +199,7 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
         owner: ScopeId,
         caller: &'static std::panic::Location<'static>,
     ) -> Self {
-        Self {
+        let signal = Self {
             inner: CopyValue::<SignalData<T>, S>::new_maybe_sync_in_scope_with_caller(
                 SignalData {
                     subscribers: Default::default(),
@@ -202,7 +208,10 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
                 owner,
                 caller,
             ),
-        }
+        };
+        #[cfg(debug_assertions)]
+        crate::graph::register_signal(signal);
+        signal
     }
 
     /// Drop the value out of the signal, invalidating the signal in the process.
@@ -222,7 +231,8 @@ impl<T: 'static, S: Storage<SignalData<T>>> Signal<T, S> {
             // We cannot hold the subscribers lock while calling mark_dirty, because mark_dirty can run user code which may cause a new subscriber to be added. If we hold the lock, we will deadlock.
             #[allow(clippy::mutable_key_type)]
             let mut subscribers = std::mem::take(&mut *inner.subscribers.lock().unwrap());
-            subscribers.retain(|reactive_context| reactive_context.mark_dirty());
+            let source = self.inner.value.created_at();
+            subscribers.retain(|reactive_context| reactive_context.mark_dirty_with_source(source));
             // Extend the subscribers list instead of overwriting it in case a subscriber is added while reactive contexts are marked dirty
             inner.subscribers.lock().unwrap().extend(subscribers);
         }
@@ -416,14 +426,20 @@ impl<T: 'static, S: Storage<SignalData<T>>> Writable for Signal<T, S> {
         #[cfg(debug_assertions)]
         let origin = std::panic::Location::caller();
         self.inner.try_write_unchecked().map(|inner| {
+            // Let any history that tracks this signal record the value before the write
+            let history = crate::history::before_write(self.id(), &inner.value);
             let borrow = S::map_mut(inner, |v| &mut v.value);
-            Write {
+            let write = Write {
                 write: borrow,
                 drop_signal: Box::new(SignalSubscriberDrop {
                     signal: *self,
                     #[cfg(debug_assertions)]
                     origin,
                 }),
+            };
+            match history {
+                Some(history) => Write::with_drop(write, history),
+                None => write,
             }
         })
     }
@@ -494,6 +510,19 @@ pub struct Write<'a, T: ?Sized + 'static, S: AnyStorage = UnsyncStorage> {
 }
 
 impl<'a, T: ?Sized + 'static, S: AnyStorage> Write<'a, T, S> {
+    /// Create a new write guard that runs `drop_signal`'s drop logic after the borrow is released.
+    pub(crate) fn new(write: S::Mut<'a, T>, drop_signal: Box<dyn Any>) -> Self {
+        Self { write, drop_signal }
+    }
+
+    /// Run some extra drop logic after the borrow is released and the existing drop logic runs.
+    pub(crate) fn with_drop(myself: Self, extra: Box<dyn Any>) -> Self {
+        Self {
+            write: myself.write,
+            drop_signal: Box::new((myself.drop_signal, extra)),
+        }
+    }
+
     /// Map the mutable reference to the signal's value to a new type.
     pub fn map<O: ?Sized>(myself: Self, f: impl FnOnce(&mut T) -> &mut O) -> Write<'a, O, S> {
         let Self {
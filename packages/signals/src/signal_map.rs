@@ -0,0 +1,266 @@
+use crate::{subscribers::Subscribers, CopyValue, Readable, Signal, Writable};
+use dioxus_core::prelude::*;
+use std::{collections::HashMap, hash::Hash};
+
+/// A map of signals that tracks reads more precisely than a `Signal<HashMap<K, V>>`.
+///
+/// Every value in the map is its own [`Signal`]:
+/// - Reading a key with [`SignalMap::get`] only subscribes to that key. Writing to the value only reruns the
+///   components that read that key.
+/// - Reading the length or iterating over the map only subscribes to the structure of the map. Inserting or removing
+///   keys reruns those subscribers, but changing the value of an existing key does not.
+///
+/// Like [`HashMap`], the order of iteration is unspecified.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// fn Users() -> Element {
+///     let mut users = use_signal_map(|| [(1, "Alice".to_string()), (2, "Bob".to_string())]);
+///
+///     rsx! {
+///         button { onclick: move |_| { users.remove(&1); }, "Remove Alice" }
+///         // This component only reruns when a user is added or removed
+///         for (id, name) in users.iter() {
+///             User { key: "{id}", name }
+///         }
+///     }
+/// }
+///
+/// #[component]
+/// fn User(name: Signal<String>) -> Element {
+///     // Only this user reruns when their name changes
+///     rsx! { "{name}" }
+/// }
+/// ```
+pub struct SignalMap<K: 'static, V: 'static> {
+    inner: CopyValue<SignalMapData<K, V>>,
+}
+
+struct SignalMapData<K: 'static, V: 'static> {
+    entries: HashMap<K, MapEntry<V>>,
+    // Subscribers that read the length, iterate over the map or read a missing key
+    structure: Subscribers,
+}
+
+struct MapEntry<V: 'static> {
+    value: Signal<V>,
+    // Subscribers that read the key. They are notified when the key is removed
+    key: Subscribers,
+}
+
+impl<V: 'static> MapEntry<V> {
+    fn new(value: Signal<V>) -> Self {
+        Self {
+            value,
+            key: Subscribers::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone + 'static, V: 'static> SignalMap<K, V> {
+    /// Create a new map owned by the current scope.
+    #[track_caller]
+    pub fn new(entries: impl IntoIterator<Item = (K, V)>) -> Self {
+        Self::new_in_scope(entries, current_scope_id().expect("in a virtual dom"))
+    }
+
+    /// Create a new map with a custom owner scope. The map and all of its values will be dropped when the owner scope is dropped.
+    #[track_caller]
+    pub fn new_in_scope(entries: impl IntoIterator<Item = (K, V)>, owner: ScopeId) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key, MapEntry::new(Signal::new_in_scope(value, owner))))
+            .collect();
+        Self {
+            inner: CopyValue::new_in_scope(
+                SignalMapData {
+                    entries,
+                    structure: Subscribers::default(),
+                },
+                owner,
+            ),
+        }
+    }
+
+    /// Get the number of entries in the map. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn len(&self) -> usize {
+        let inner = self.inner.read();
+        inner.structure.subscribe();
+        inner.entries.len()
+    }
+
+    /// Check if the map is empty. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the map contains a key. See [`SignalMap::get`] for what this subscribes to.
+    #[track_caller]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the signal of the value for a key. This subscribes to the key, but not to the rest of the map. If the key
+    /// is missing, this subscribes to the structure of the map instead so the reader reruns when the key is inserted.
+    ///
+    /// Reading the returned signal subscribes to the value.
+    #[track_caller]
+    pub fn get(&self, key: &K) -> Option<Signal<V>> {
+        let inner = self.inner.read();
+        match inner.entries.get(key) {
+            Some(entry) => {
+                entry.key.subscribe();
+                Some(entry.value)
+            }
+            None => {
+                inner.structure.subscribe();
+                None
+            }
+        }
+    }
+
+    /// Iterate over the keys in the map. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn keys(&self) -> std::vec::IntoIter<K> {
+        let inner = self.inner.read();
+        inner.structure.subscribe();
+        inner
+            .entries
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Iterate over every key and the signal of its value. This subscribes to the structure of the map, but not to
+    /// the values.
+    #[track_caller]
+    pub fn iter(&self) -> std::vec::IntoIter<(K, Signal<V>)> {
+        let inner = self.inner.read();
+        inner.structure.subscribe();
+        inner
+            .entries
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.value))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Insert a value into the map, returning the old value if the key was already present.
+    ///
+    /// Replacing the value of an existing key only notifies the subscribers of that value.
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(mut entry) = self.inner.peek().entries.get(&key).map(|entry| entry.value) {
+            return Some(entry.replace(value));
+        }
+
+        let entry = MapEntry::new(Signal::new_in_scope(value, self.inner.origin_scope()));
+        let notify = {
+            let mut inner = self.inner.write();
+            inner.entries.insert(key, entry);
+            vec![inner.structure.clone()]
+        };
+        Subscribers::notify_all(notify);
+        None
+    }
+
+    /// Remove a key from the map, returning its value if it was present.
+    ///
+    /// The signal of the removed value is dropped, so it can no longer be read. Components that read the key are
+    /// notified so they can stop using it.
+    #[track_caller]
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (entry, notify) = {
+            let mut inner = self.inner.write();
+            let entry = inner.entries.remove(key)?;
+            let notify = vec![inner.structure.clone(), entry.key.clone()];
+            (entry, notify)
+        };
+        Subscribers::notify_all(notify);
+        entry.value.manually_drop()
+    }
+
+    /// Only keep the entries that match a predicate. Like [`SignalMap::remove`], the signals of removed values are
+    /// dropped.
+    #[track_caller]
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &V) -> bool) {
+        let removed: Vec<K> = {
+            let inner = self.inner.peek();
+            inner
+                .entries
+                .iter()
+                .filter(|(key, entry)| !f(key, &entry.value.peek()))
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        if removed.is_empty() {
+            return;
+        }
+
+        let (removed, notify) = {
+            let mut inner = self.inner.write();
+            let mut notify = vec![inner.structure.clone()];
+            let removed: Vec<_> = removed
+                .iter()
+                .filter_map(|key| inner.entries.remove(key))
+                .collect();
+            notify.extend(removed.iter().map(|entry| entry.key.clone()));
+            (removed, notify)
+        };
+        Subscribers::notify_all(notify);
+        for entry in removed {
+            entry.value.manually_drop();
+        }
+    }
+
+    /// Remove every entry from the map.
+    #[track_caller]
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false);
+    }
+
+    /// Clone every entry into a `HashMap`. This subscribes to the structure of the map and every value.
+    #[track_caller]
+    pub fn to_hash_map(&self) -> HashMap<K, V>
+    where
+        V: Clone,
+    {
+        self.iter()
+            .map(|(key, value)| (key, value.cloned()))
+            .collect()
+    }
+}
+
+impl<K: 'static, V: 'static> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: 'static, V: 'static> Copy for SignalMap<K, V> {}
+
+impl<K: 'static, V: 'static> PartialEq for SignalMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<K: std::fmt::Debug + 'static, V: std::fmt::Debug + 'static> std::fmt::Debug
+    for SignalMap<K, V>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.peek();
+        f.debug_map()
+            .entries(
+                inner
+                    .entries
+                    .iter()
+                    .map(|(key, entry)| (key, entry.value.peek())),
+            )
+            .finish()
+    }
+}
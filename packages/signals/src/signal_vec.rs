@@ -0,0 +1,294 @@
+use crate::{subscribers::Subscribers, CopyValue, Readable, Signal, Writable};
+use dioxus_core::prelude::*;
+
+/// A list of signals that tracks reads more precisely than a `Signal<Vec<T>>`.
+///
+/// Every entry in the list is its own [`Signal`]:
+/// - Reading an index with [`SignalVec::get`] only subscribes to that index. Writing to the entry only reruns the
+///   components that read that entry.
+/// - Reading the length or iterating over the list only subscribes to the structure of the list. Pushing, inserting
+///   or removing entries reruns those subscribers, but changing the value of an entry does not.
+///
+/// Iterating returns the [`Signal`] of every entry, so a `for` loop in `rsx!` can pass each entry to a child component
+/// without cloning the values in the list:
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// fn TodoList() -> Element {
+///     let mut todos = use_signal_vec(|| vec!["Buy milk".to_string()]);
+///
+///     rsx! {
+///         button { onclick: move |_| todos.push("New todo".to_string()), "Add todo" }
+///         // This component only reruns when a todo is added or removed
+///         for todo in todos.iter() {
+///             Todo { key: "{todo.id():?}", todo }
+///         }
+///     }
+/// }
+///
+/// #[component]
+/// fn Todo(todo: Signal<String>) -> Element {
+///     // Only this todo reruns when it is edited
+///     rsx! {
+///         input {
+///             value: "{todo}",
+///             oninput: move |event| todo.set(event.value()),
+///         }
+///     }
+/// }
+/// ```
+pub struct SignalVec<T: 'static> {
+    inner: CopyValue<SignalVecData<T>>,
+}
+
+struct SignalVecData<T: 'static> {
+    entries: Vec<Signal<T>>,
+    // Subscribers that read an index. They are notified when a different entry moves into the index
+    indexes: Vec<Subscribers>,
+    // Subscribers that read the length or iterate over the list
+    structure: Subscribers,
+}
+
+impl<T: 'static> SignalVec<T> {
+    /// Create a new list owned by the current scope.
+    #[track_caller]
+    pub fn new(values: impl IntoIterator<Item = T>) -> Self {
+        Self::new_in_scope(values, current_scope_id().expect("in a virtual dom"))
+    }
+
+    /// Create a new list with a custom owner scope. The list and all of its entries will be dropped when the owner scope is dropped.
+    #[track_caller]
+    pub fn new_in_scope(values: impl IntoIterator<Item = T>, owner: ScopeId) -> Self {
+        let entries: Vec<_> = values
+            .into_iter()
+            .map(|value| Signal::new_in_scope(value, owner))
+            .collect();
+        let indexes = entries.iter().map(|_| Subscribers::default()).collect();
+        Self {
+            inner: CopyValue::new_in_scope(
+                SignalVecData {
+                    entries,
+                    indexes,
+                    structure: Subscribers::default(),
+                },
+                owner,
+            ),
+        }
+    }
+
+    /// Get the number of entries in the list. This subscribes to the structure of the list.
+    #[track_caller]
+    pub fn len(&self) -> usize {
+        let inner = self.inner.read();
+        inner.structure.subscribe();
+        inner.entries.len()
+    }
+
+    /// Check if the list is empty. This subscribes to the structure of the list.
+    #[track_caller]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the signal of the entry at an index. This subscribes to the index, but not to the rest of the list.
+    ///
+    /// Reading the returned signal subscribes to the value of the entry.
+    #[track_caller]
+    pub fn get(&self, index: usize) -> Option<Signal<T>> {
+        let inner = self.inner.read();
+        match inner.entries.get(index) {
+            Some(entry) => {
+                inner.indexes[index].subscribe();
+                Some(*entry)
+            }
+            // The index only exists once the list grows, so track the structure instead
+            None => {
+                inner.structure.subscribe();
+                None
+            }
+        }
+    }
+
+    /// Iterate over the signal of every entry in the list. This subscribes to the structure of the list, but not to
+    /// the values of the entries.
+    #[track_caller]
+    pub fn iter(&self) -> std::vec::IntoIter<Signal<T>> {
+        let inner = self.inner.read();
+        inner.structure.subscribe();
+        inner.entries.clone().into_iter()
+    }
+
+    /// Set the value of the entry at an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn set(&mut self, index: usize, value: T) {
+        let mut entry = self.inner.peek().entries[index];
+        entry.set(value);
+    }
+
+    /// Add an entry to the end of the list.
+    #[track_caller]
+    pub fn push(&mut self, value: T) {
+        let entry = Signal::new_in_scope(value, self.inner.origin_scope());
+        let notify = {
+            let mut inner = self.inner.write();
+            inner.entries.push(entry);
+            inner.indexes.push(Subscribers::default());
+            vec![inner.structure.clone()]
+        };
+        Subscribers::notify_all(notify);
+    }
+
+    /// Insert an entry at an index, shifting every entry after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, value: T) {
+        let entry = Signal::new_in_scope(value, self.inner.origin_scope());
+        let notify = {
+            let mut inner = self.inner.write();
+            inner.entries.insert(index, entry);
+            inner.indexes.push(Subscribers::default());
+            inner.moved(index..inner.entries.len())
+        };
+        Subscribers::notify_all(notify);
+    }
+
+    /// Remove the entry at an index, shifting every entry after it to the left. Returns the value of the removed entry.
+    ///
+    /// The signal of the removed entry is dropped, so it can no longer be read. Components that read the index are
+    /// notified so they can stop using it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> T {
+        let (entry, notify) = {
+            let mut inner = self.inner.write();
+            let entry = inner.entries.remove(index);
+            let notify = inner.moved(index..inner.entries.len() + 1);
+            inner.indexes.pop();
+            (entry, notify)
+        };
+        Subscribers::notify_all(notify);
+        take(entry)
+    }
+
+    /// Remove the last entry in the list. Returns the value of the removed entry, see [`SignalVec::remove`].
+    #[track_caller]
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.inner.peek().entries.len();
+        (len > 0).then(|| self.remove(len - 1))
+    }
+
+    /// Swap two entries in the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let notify = {
+            let mut inner = self.inner.write();
+            inner.entries.swap(a, b);
+            vec![
+                inner.indexes[a].clone(),
+                inner.indexes[b].clone(),
+                inner.structure.clone(),
+            ]
+        };
+        Subscribers::notify_all(notify);
+    }
+
+    /// Only keep the entries that match a predicate. Like [`SignalVec::remove`], the signals of removed entries are
+    /// dropped.
+    #[track_caller]
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        // Run the predicate before borrowing the list mutably so it can read the list
+        let keep: Vec<bool> = {
+            let inner = self.inner.peek();
+            inner.entries.iter().map(|entry| f(&entry.peek())).collect()
+        };
+        let (removed, notify) = {
+            let mut inner = self.inner.write();
+            let old_len = inner.entries.len();
+            let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut inner.entries)
+                .into_iter()
+                .enumerate()
+                .partition(|(index, _)| keep[*index]);
+            inner.entries = kept.into_iter().map(|(_, entry)| entry).collect();
+            let notify = match removed.first() {
+                Some((first, _)) => inner.moved(*first..old_len),
+                None => Vec::new(),
+            };
+            let new_len = inner.entries.len();
+            inner.indexes.truncate(new_len);
+            (removed, notify)
+        };
+        Subscribers::notify_all(notify);
+        for (_, entry) in removed {
+            take(entry);
+        }
+    }
+
+    /// Remove every entry from the list.
+    #[track_caller]
+    pub fn clear(&mut self) {
+        self.retain(|_| false);
+    }
+
+    /// Clone the value of every entry into a `Vec`. This subscribes to the structure of the list and the value of every entry.
+    #[track_caller]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().map(|entry| entry.cloned()).collect()
+    }
+}
+
+/// Drop the signal of an entry that was removed from the list and return its value
+fn take<T: 'static>(entry: Signal<T>) -> T {
+    entry
+        .manually_drop()
+        .expect("entries are only dropped when they are removed from the list")
+}
+
+impl<T: 'static> SignalVecData<T> {
+    /// Get the subscribers to notify when the entries in a range of indexes changed
+    fn moved(&self, range: std::ops::Range<usize>) -> Vec<Subscribers> {
+        let mut notify = self.indexes[range].to_vec();
+        notify.push(self.structure.clone());
+        notify
+    }
+}
+
+impl<T: 'static> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for SignalVec<T> {}
+
+impl<T: 'static> PartialEq for SignalVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T: std::fmt::Debug + 'static> std::fmt::Debug for SignalVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.peek();
+        f.debug_list()
+            .entries(inner.entries.iter().map(|entry| entry.peek()))
+            .finish()
+    }
+}
@@ -0,0 +1,727 @@
+use crate::{
+    read::Readable, signal::Write, subscribers::Subscribers, write::Writable, CopyValue,
+    ReadableRef, WritableRef,
+};
+use dioxus_core::prelude::*;
+use generational_box::{AnyStorage, BorrowError, BorrowMutError, UnsyncStorage};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+type Ref<T> = <UnsyncStorage as AnyStorage>::Ref<'static, T>;
+type Mut<T> = <UnsyncStorage as AnyStorage>::Mut<'static, T>;
+
+/// State that tracks reads of each part of the value separately.
+///
+/// A `Store` is a view into part of a value, described by a [`Lens`]. Every part of the value has its own set of
+/// subscribers:
+/// - Reading a store subscribes to that part of the value. Writing to it reruns the components that read the same
+///   part, any part inside of it, or any value that contains it.
+/// - Reading the length of a `Vec` or `HashMap`, iterating over it, or checking if an `Option` is `Some` only
+///   subscribes to the structure of the value.
+///
+/// Use `#[derive(Store)]` on a struct to add a method for each field that returns the store of that field:
+///
+/// ```rust
+/// use dioxus::prelude::*;
+///
+/// #[derive(Store)]
+/// struct AppState {
+///     user: User,
+///     todos: Vec<String>,
+/// }
+///
+/// #[derive(Store)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// fn App() -> Element {
+///     let state = use_store(|| AppState {
+///         user: User { name: "Alice".to_string(), age: 30 },
+///         todos: Vec::new(),
+///     });
+///
+///     rsx! {
+///         Name { state }
+///         button { onclick: move |_| *state.user().age().write() += 1, "Birthday" }
+///         button { onclick: move |_| state.todos().push("New todo".to_string()), "Add todo" }
+///     }
+/// }
+///
+/// #[component]
+/// fn Name(state: Store<AppState>) -> Element {
+///     // This component only reruns when the name changes
+///     rsx! { "{state.user().name()}" }
+/// }
+/// ```
+pub struct Store<T: 'static, L: Lens<Target = T> = RootLens<T>> {
+    lens: L,
+    tree: CopyValue<StoreTree>,
+    node: usize,
+}
+
+impl<T: 'static> Store<T> {
+    /// Create a new store owned by the current scope.
+    #[track_caller]
+    pub fn new(value: T) -> Self {
+        Self::new_in_scope(value, current_scope_id().expect("in a virtual dom"))
+    }
+
+    /// Create a new store with a custom owner scope. The store will be dropped when the owner scope is dropped.
+    #[track_caller]
+    pub fn new_in_scope(value: T, owner: ScopeId) -> Self {
+        Self {
+            lens: RootLens {
+                value: CopyValue::new_in_scope(value, owner),
+            },
+            tree: CopyValue::new_in_scope(StoreTree::default(), owner),
+            node: 0,
+        }
+    }
+}
+
+impl<T: 'static, L: Lens<Target = T>> Store<T, L> {
+    /// Get the store for part of this value. Every part of the value must use a different `id`.
+    ///
+    /// This is used by the methods `#[derive(Store)]` generates for each field.
+    pub fn field<U: 'static>(
+        &self,
+        id: u64,
+        read: fn(&T) -> &U,
+        write: fn(&mut T) -> &mut U,
+    ) -> Store<U, FieldLens<L, U>> {
+        self.child(
+            id,
+            FieldLens {
+                parent: self.lens.clone(),
+                read,
+                write,
+            },
+        )
+    }
+
+    fn child<U: 'static, C: Lens<Target = U>>(&self, id: u64, lens: C) -> Store<U, C> {
+        let node = self.tree.write_unchecked().child(self.node, id);
+        Store {
+            lens,
+            tree: self.tree,
+            node,
+        }
+    }
+
+    /// Notify everything that read a part of this value that was removed and forget its subscribers
+    fn remove_child(&self, id: u64) {
+        let removed = self.tree.write_unchecked().remove_child(self.node, id);
+        Subscribers::notify_all(removed);
+    }
+
+    /// Subscribe to the structure of this value, but not the values inside of it
+    fn track_structure(&self) {
+        if let Some(node) = self.tree.peek().nodes.get(&self.node) {
+            node.structure.subscribe();
+        }
+    }
+
+    /// Change the structure of the value without changing the parts of the value that already exist
+    #[track_caller]
+    fn write_structure<O>(&self, f: impl FnOnce(&mut T) -> O) -> O {
+        let output = f(&mut *self.lens.try_write().unwrap());
+        Subscribers::notify_all(self.tree.peek().structure_changed(self.node));
+        output
+    }
+}
+
+impl<T: 'static, L: Lens<Target = Vec<T>>> Store<Vec<T>, L> {
+    /// Get the number of items in the list. This subscribes to the structure of the list.
+    #[track_caller]
+    pub fn len(&self) -> usize {
+        self.track_structure();
+        self.peek().len()
+    }
+
+    /// Check if the list is empty. This subscribes to the structure of the list.
+    #[track_caller]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the store of the item at an index. If the index is out of bounds, this subscribes to the structure of
+    /// the list.
+    #[track_caller]
+    pub fn get(&self, index: usize) -> Option<Store<T, IndexLens<L, T>>> {
+        if index < self.peek().len() {
+            Some(self.child(
+                index as u64,
+                IndexLens {
+                    parent: self.lens.clone(),
+                    index,
+                    _marker: PhantomData,
+                },
+            ))
+        } else {
+            self.track_structure();
+            None
+        }
+    }
+
+    /// Iterate over the store of every item in the list. This subscribes to the structure of the list, but not to
+    /// the items.
+    #[track_caller]
+    pub fn iter(&self) -> impl Iterator<Item = Store<T, IndexLens<L, T>>> {
+        let store = self.clone();
+        (0..self.len()).filter_map(move |index| store.get(index))
+    }
+
+    /// Add an item to the end of the list. This does not rerun the components that read the existing items.
+    #[track_caller]
+    pub fn push(&mut self, value: T) {
+        self.write_structure(|list| list.push(value));
+    }
+
+    /// Remove the last item in the list.
+    #[track_caller]
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.peek().len().checked_sub(1)?;
+        let value = self.write_structure(|list| list.pop());
+        self.remove_child(last as u64);
+        value
+    }
+}
+
+impl<T: 'static, L: Lens<Target = Option<T>>> Store<Option<T>, L> {
+    /// Check if the value is `Some`. This subscribes to the structure of the option, but not to the value inside of it.
+    #[track_caller]
+    pub fn is_some(&self) -> bool {
+        self.track_structure();
+        self.peek().is_some()
+    }
+
+    /// Check if the value is `None`. This subscribes to the structure of the option, but not to the value inside of it.
+    #[track_caller]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /// Get the store of the value inside the option if it is `Some`. This subscribes to the structure of the
+    /// option, but not to the value inside of it.
+    #[track_caller]
+    pub fn transpose(&self) -> Option<Store<T, SomeLens<L, T>>> {
+        self.is_some().then(|| {
+            self.child(
+                0,
+                SomeLens {
+                    parent: self.lens.clone(),
+                    _marker: PhantomData,
+                },
+            )
+        })
+    }
+}
+
+impl<K: Hash + Eq + Clone + 'static, V: 'static, L: Lens<Target = HashMap<K, V>>>
+    Store<HashMap<K, V>, L>
+{
+    /// Get the number of entries in the map. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn len(&self) -> usize {
+        self.track_structure();
+        self.peek().len()
+    }
+
+    /// Check if the map is empty. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the map contains a key. If the key is present, this subscribes to the key, but not to the rest of
+    /// the map. If it is missing, this subscribes to the structure of the map.
+    #[track_caller]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the store of the value for a key. If the key is present, this subscribes to the key, but not to the rest
+    /// of the map. If it is missing, this subscribes to the structure of the map so the caller reruns when the key
+    /// is inserted.
+    #[track_caller]
+    pub fn get(&self, key: &K) -> Option<Store<V, KeyLens<L, K, V>>> {
+        if !self.peek().contains_key(key) {
+            self.track_structure();
+            return None;
+        }
+        let store = self.key(key.clone());
+        store.track_structure();
+        Some(store)
+    }
+
+    /// Iterate over the keys in the map. This subscribes to the structure of the map.
+    #[track_caller]
+    pub fn keys(&self) -> std::vec::IntoIter<K> {
+        self.track_structure();
+        self.peek().keys().cloned().collect::<Vec<_>>().into_iter()
+    }
+
+    /// Iterate over every key and the store of its value. This subscribes to the structure of the map, but not to
+    /// the values.
+    #[track_caller]
+    pub fn iter(&self) -> std::vec::IntoIter<(K, Store<V, KeyLens<L, K, V>>)> {
+        self.keys()
+            .map(|key| (key.clone(), self.key(key)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Insert a value into the map, returning the old value if the key was already present.
+    ///
+    /// Replacing the value of an existing key only reruns the components that read that key.
+    #[track_caller]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let entry = self.key(key.clone());
+        if self.peek().contains_key(&key) {
+            let old = std::mem::replace(&mut *entry.lens.try_write().unwrap(), value);
+            Subscribers::notify_all(self.tree.peek().written(entry.node));
+            return Some(old);
+        }
+        self.write_structure(|map| map.insert(key, value));
+        Subscribers::notify_all(self.tree.peek().written(entry.node));
+        None
+    }
+
+    /// Remove a key from the map, returning its value if it was present.
+    #[track_caller]
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if !self.peek().contains_key(key) {
+            return None;
+        }
+        let value = self.write_structure(|map| map.remove(key));
+        self.remove_child(key_id(key));
+        value
+    }
+
+    fn key(&self, key: K) -> Store<V, KeyLens<L, K, V>> {
+        self.child(
+            key_id(&key),
+            KeyLens {
+                parent: self.lens.clone(),
+                key,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T: 'static, L: Lens<Target = T>> Readable for Store<T, L> {
+    type Target = T;
+    type Storage = UnsyncStorage;
+
+    #[track_caller]
+    fn try_read_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        if let Some(node) = self.tree.try_read_unchecked()?.nodes.get(&self.node) {
+            node.value.subscribe();
+        }
+        self.lens.try_read()
+    }
+
+    #[track_caller]
+    fn try_peek_unchecked(&self) -> Result<ReadableRef<'static, Self>, BorrowError> {
+        self.lens.try_read()
+    }
+}
+
+impl<T: 'static, L: Lens<Target = T>> Writable for Store<T, L> {
+    type Mut<'a, R: ?Sized + 'static> = Write<'a, R, UnsyncStorage>;
+
+    fn map_mut<I: ?Sized, U: ?Sized + 'static, F: FnOnce(&mut I) -> &mut U>(
+        ref_: Self::Mut<'_, I>,
+        f: F,
+    ) -> Self::Mut<'_, U> {
+        Write::map(ref_, f)
+    }
+
+    fn try_map_mut<
+        I: ?Sized + 'static,
+        U: ?Sized + 'static,
+        F: FnOnce(&mut I) -> Option<&mut U>,
+    >(
+        ref_: Self::Mut<'_, I>,
+        f: F,
+    ) -> Option<Self::Mut<'_, U>> {
+        Write::filter_map(ref_, f)
+    }
+
+    fn downcast_lifetime_mut<'a: 'b, 'b, R: ?Sized + 'static>(
+        mut_: Self::Mut<'a, R>,
+    ) -> Self::Mut<'b, R> {
+        Write::downcast_lifetime(mut_)
+    }
+
+    #[track_caller]
+    fn try_write_unchecked(&self) -> Result<WritableRef<'static, Self>, BorrowMutError> {
+        let write = self.lens.try_write()?;
+        Ok(Write::new(
+            write,
+            Box::new(StoreWriteDrop {
+                tree: self.tree,
+                node: self.node,
+            }),
+        ))
+    }
+}
+
+struct StoreWriteDrop {
+    tree: CopyValue<StoreTree>,
+    node: usize,
+}
+
+impl Drop for StoreWriteDrop {
+    fn drop(&mut self) {
+        Subscribers::notify_all(self.tree.peek().written(self.node));
+    }
+}
+
+impl<T: 'static, L: Lens<Target = T>> Clone for Store<T, L> {
+    fn clone(&self) -> Self {
+        Self {
+            lens: self.lens.clone(),
+            tree: self.tree,
+            node: self.node,
+        }
+    }
+}
+
+impl<T: 'static, L: Lens<Target = T> + Copy> Copy for Store<T, L> {}
+
+impl<T: 'static, L: Lens<Target = T>> PartialEq for Store<T, L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree && self.node == other.node
+    }
+}
+
+impl<T: std::fmt::Debug + 'static, L: Lens<Target = T>> std::fmt::Debug for Store<T, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.peek().fmt(f)
+    }
+}
+
+impl<T: std::fmt::Display + 'static, L: Lens<Target = T>> std::fmt::Display for Store<T, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| std::fmt::Display::fmt(value, f))
+    }
+}
+
+/// Allow calling a store with store() syntax
+impl<T: Clone + 'static, L: Lens<Target = T>> Deref for Store<T, L> {
+    type Target = dyn Fn() -> T;
+
+    fn deref(&self) -> &Self::Target {
+        Readable::deref_impl(self)
+    }
+}
+
+/// The subscribers of every part of a store that has been read. The node with id 0 is the root of the store.
+///
+/// Nodes are removed when their part of the value is removed. Ids are never reused, so a store of a removed part
+/// never subscribes to a different part of the value.
+struct StoreTree {
+    nodes: HashMap<usize, StoreNode>,
+    next_id: usize,
+}
+
+impl Default for StoreTree {
+    fn default() -> Self {
+        Self {
+            nodes: HashMap::from([(0, StoreNode::default())]),
+            next_id: 1,
+        }
+    }
+}
+
+#[derive(Default)]
+struct StoreNode {
+    parent: Option<usize>,
+    children: HashMap<u64, usize>,
+    // Subscribers that read the whole value
+    value: Subscribers,
+    // Subscribers that read the length of the value or if it is `Some`
+    structure: Subscribers,
+}
+
+impl StoreTree {
+    /// Get the node of a part of a value, creating it if nothing has read it yet
+    fn child(&mut self, node: usize, id: u64) -> usize {
+        let child = self.next_id;
+        let Some(parent) = self.nodes.get_mut(&node) else {
+            // The parent was removed, so the part can't be read anymore. The id doesn't belong to any node
+            self.next_id += 1;
+            return child;
+        };
+        if let Some(existing) = parent.children.get(&id) {
+            return *existing;
+        }
+        parent.children.insert(id, child);
+        self.nodes.insert(
+            child,
+            StoreNode {
+                parent: Some(node),
+                ..Default::default()
+            },
+        );
+        self.next_id += 1;
+        child
+    }
+
+    /// Remove the node of a part of a value and every node inside of it. Returns the subscribers of the removed nodes
+    fn remove_child(&mut self, node: usize, id: u64) -> Vec<Subscribers> {
+        let Some(child) = self
+            .nodes
+            .get_mut(&node)
+            .and_then(|node| node.children.remove(&id))
+        else {
+            return Vec::new();
+        };
+        let mut notify = Vec::new();
+        let mut stack = vec![child];
+        while let Some(node) = stack.pop() {
+            if let Some(node) = self.nodes.remove(&node) {
+                notify.push(node.value);
+                notify.push(node.structure);
+                stack.extend(node.children.into_values());
+            }
+        }
+        notify
+    }
+
+    /// Get the subscribers to notify when the value of a node was replaced
+    fn written(&self, node: usize) -> Vec<Subscribers> {
+        let mut notify = self.ancestors(node);
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            let Some(node) = self.nodes.get(&node) else {
+                continue;
+            };
+            notify.push(node.value.clone());
+            notify.push(node.structure.clone());
+            stack.extend(node.children.values());
+        }
+        notify
+    }
+
+    /// Get the subscribers to notify when the structure of a node changed, but the existing parts of the value did not
+    fn structure_changed(&self, node: usize) -> Vec<Subscribers> {
+        let mut notify = self.ancestors(node);
+        if let Some(node) = self.nodes.get(&node) {
+            notify.push(node.value.clone());
+            notify.push(node.structure.clone());
+        }
+        notify
+    }
+
+    /// Every value that contains a node changes when the node changes
+    fn ancestors(&self, node: usize) -> Vec<Subscribers> {
+        let mut notify = Vec::new();
+        let mut parent = self.nodes.get(&node).and_then(|node| node.parent);
+        while let Some(node) = parent.and_then(|node| self.nodes.get(&node)) {
+            notify.push(node.value.clone());
+            parent = node.parent;
+        }
+        notify
+    }
+}
+
+fn key_id<K: Hash>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A path from the value at the root of a [`Store`] to part of the value.
+pub trait Lens: Clone + 'static {
+    /// The type of the part of the value the lens points to.
+    type Target: 'static;
+
+    /// Try to borrow the part of the value.
+    fn try_read(&self) -> Result<Ref<Self::Target>, BorrowError>;
+
+    /// Try to borrow the part of the value mutably.
+    fn try_write(&self) -> Result<Mut<Self::Target>, BorrowMutError>;
+}
+
+/// The lens of the value at the root of a [`Store`].
+pub struct RootLens<T: 'static> {
+    value: CopyValue<T>,
+}
+
+impl<T: 'static> Clone for RootLens<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for RootLens<T> {}
+
+impl<T: 'static> Lens for RootLens<T> {
+    type Target = T;
+
+    fn try_read(&self) -> Result<Ref<T>, BorrowError> {
+        self.value.try_read_unchecked()
+    }
+
+    fn try_write(&self) -> Result<Mut<T>, BorrowMutError> {
+        self.value.try_write_unchecked()
+    }
+}
+
+/// The lens of a field of a struct. See [`Store::field`].
+pub struct FieldLens<L: Lens, T: 'static> {
+    parent: L,
+    read: fn(&L::Target) -> &T,
+    write: fn(&mut L::Target) -> &mut T,
+}
+
+impl<L: Lens, T: 'static> Clone for FieldLens<L, T> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            read: self.read,
+            write: self.write,
+        }
+    }
+}
+
+impl<L: Lens + Copy, T: 'static> Copy for FieldLens<L, T> {}
+
+impl<L: Lens, T: 'static> Lens for FieldLens<L, T> {
+    type Target = T;
+
+    fn try_read(&self) -> Result<Ref<T>, BorrowError> {
+        Ok(UnsyncStorage::map(self.parent.try_read()?, self.read))
+    }
+
+    fn try_write(&self) -> Result<Mut<T>, BorrowMutError> {
+        Ok(UnsyncStorage::map_mut(self.parent.try_write()?, self.write))
+    }
+}
+
+/// The lens of an item in a `Vec`. See [`Store::get`].
+pub struct IndexLens<L: Lens<Target = Vec<T>>, T: 'static> {
+    parent: L,
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<L: Lens<Target = Vec<T>>, T: 'static> Clone for IndexLens<L, T> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            index: self.index,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L: Lens<Target = Vec<T>> + Copy, T: 'static> Copy for IndexLens<L, T> {}
+
+impl<L: Lens<Target = Vec<T>>, T: 'static> Lens for IndexLens<L, T> {
+    type Target = T;
+
+    #[track_caller]
+    fn try_read(&self) -> Result<Ref<T>, BorrowError> {
+        let index = self.index;
+        Ok(UnsyncStorage::map(self.parent.try_read()?, |list| {
+            &list[index]
+        }))
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<Mut<T>, BorrowMutError> {
+        let index = self.index;
+        Ok(UnsyncStorage::map_mut(self.parent.try_write()?, |list| {
+            &mut list[index]
+        }))
+    }
+}
+
+/// The lens of the value inside an `Option`. See [`Store::transpose`].
+pub struct SomeLens<L: Lens<Target = Option<T>>, T: 'static> {
+    parent: L,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<L: Lens<Target = Option<T>>, T: 'static> Clone for SomeLens<L, T> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L: Lens<Target = Option<T>> + Copy, T: 'static> Copy for SomeLens<L, T> {}
+
+impl<L: Lens<Target = Option<T>>, T: 'static> Lens for SomeLens<L, T> {
+    type Target = T;
+
+    #[track_caller]
+    fn try_read(&self) -> Result<Ref<T>, BorrowError> {
+        Ok(UnsyncStorage::map(self.parent.try_read()?, |option| {
+            option.as_ref().expect("the option store is None")
+        }))
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<Mut<T>, BorrowMutError> {
+        Ok(UnsyncStorage::map_mut(self.parent.try_write()?, |option| {
+            option.as_mut().expect("the option store is None")
+        }))
+    }
+}
+
+/// The lens of the value of a key in a `HashMap`. See [`Store::get`].
+pub struct KeyLens<L: Lens<Target = HashMap<K, V>>, K: 'static, V: 'static> {
+    parent: L,
+    key: K,
+    _marker: PhantomData<fn() -> V>,
+}
+
+impl<L: Lens<Target = HashMap<K, V>>, K: Clone + 'static, V: 'static> Clone for KeyLens<L, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent.clone(),
+            key: self.key.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L: Lens<Target = HashMap<K, V>> + Copy, K: Copy + 'static, V: 'static> Copy
+    for KeyLens<L, K, V>
+{
+}
+
+impl<L: Lens<Target = HashMap<K, V>>, K: Hash + Eq + Clone + 'static, V: 'static> Lens
+    for KeyLens<L, K, V>
+{
+    type Target = V;
+
+    #[track_caller]
+    fn try_read(&self) -> Result<Ref<V>, BorrowError> {
+        Ok(UnsyncStorage::map(self.parent.try_read()?, |map| {
+            map.get(&self.key)
+                .expect("the key was removed from the map store")
+        }))
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<Mut<V>, BorrowMutError> {
+        Ok(UnsyncStorage::map_mut(self.parent.try_write()?, |map| {
+            map.get_mut(&self.key)
+                .expect("the key was removed from the map store")
+        }))
+    }
+}
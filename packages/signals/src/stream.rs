@@ -0,0 +1,163 @@
+use crate::{read::Readable, write::Writable, Signal, SignalData};
+use dioxus_core::prelude::*;
+use futures_util::{pin_mut, stream, FutureExt, Stream, StreamExt};
+use generational_box::Storage;
+use std::task::{ready, Poll};
+
+impl<T: 'static> Signal<Option<T>> {
+    /// Create a signal that holds the latest item of a stream. The signal is `None` until the stream yields its first
+    /// item.
+    ///
+    /// The stream is driven by a task in the current component, so it stops when the component is dropped. If the
+    /// stream yields several items before the task is polled, only the newest item of each batch is written to the
+    /// signal. Use
+    /// [`Signal::from_stream_accumulate`] to keep every item instead.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use futures_util::Stream;
+    /// # fn temperatures() -> impl Stream<Item = f32> { futures_util::stream::empty() }
+    /// fn Thermometer() -> Element {
+    ///     let temperature = use_hook(|| Signal::from_stream(temperatures()));
+    ///
+    ///     rsx! {
+    ///         match temperature() {
+    ///             Some(temperature) => rsx! { "{temperature}°C" },
+    ///             None => rsx! { "Waiting for the first reading..." },
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn from_stream(stream: impl Stream<Item = T> + 'static) -> Self {
+        let signal = Signal::new(None);
+        drive_stream(signal, stream, |value, mut items| *value = items.pop());
+        signal
+    }
+}
+
+impl<T: 'static> Signal<Vec<T>> {
+    /// Create a signal that collects every item of a stream. Items that arrive together are pushed to the signal in
+    /// one write.
+    ///
+    /// The stream is driven by a task in the current component, so it stops when the component is dropped. The items
+    /// are kept until you remove them, so streams that never end should be drained regularly:
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use futures_util::Stream;
+    /// # fn messages() -> impl Stream<Item = String> { futures_util::stream::empty() }
+    /// fn Chat() -> Element {
+    ///     let mut messages = use_hook(|| Signal::from_stream_accumulate(messages()));
+    ///
+    ///     rsx! {
+    ///         for message in messages.iter() {
+    ///             p { "{message}" }
+    ///         }
+    ///         button { onclick: move |_| messages.write().clear(), "Clear" }
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn from_stream_accumulate(stream: impl Stream<Item = T> + 'static) -> Self {
+        let signal = Signal::new(Vec::new());
+        drive_stream(signal, stream, |value, items| value.extend(items));
+        signal
+    }
+}
+
+/// The most items that are written to the signal at once. Streams that are always ready would otherwise block the
+/// thread forever
+const MAX_BATCH: usize = 256;
+
+/// Spawn a task that applies every batch of ready items in the stream to the signal
+fn drive_stream<T: 'static, V: 'static>(
+    mut signal: Signal<V>,
+    stream: impl Stream<Item = T> + 'static,
+    mut apply: impl FnMut(&mut V, Vec<T>) + 'static,
+) {
+    spawn(async move {
+        pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            // Collect any items that are already waiting so they are written at once
+            let mut items = vec![item];
+            let mut finished = false;
+            while items.len() < MAX_BATCH {
+                let Some(item) = stream.next().now_or_never() else {
+                    break;
+                };
+                match item {
+                    Some(item) => items.push(item),
+                    None => {
+                        finished = true;
+                        break;
+                    }
+                }
+            }
+            let items_len = items.len();
+            match signal.try_write() {
+                Ok(mut value) => apply(&mut value, items),
+                Err(_) => return,
+            }
+            if finished {
+                return;
+            }
+            // More items may be ready, so let the rest of the app run before collecting the next batch
+            if items_len == MAX_BATCH {
+                wait_for_render().await;
+            }
+        }
+    });
+}
+
+/// Wait until the effects after the next render run. The dom rerenders any components that read the last batch and
+/// runs other tasks before the next batch is collected
+async fn wait_for_render() {
+    let (tx, rx) = futures_channel::oneshot::channel();
+    queue_effect(move || _ = tx.send(()));
+    _ = rx.await;
+}
+
+impl<T: Clone + 'static, S: Storage<SignalData<T>>> Signal<T, S> {
+    /// Create a stream that yields the current value of the signal, and then the new value every time the signal
+    /// changes. If the signal is written several times before the stream is polled, only the newest value is yielded.
+    ///
+    /// The stream ends when the component the stream was created in is dropped. This must be called inside of the
+    /// dioxus runtime.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use futures_util::StreamExt;
+    /// # async fn save(query: String) {}
+    /// fn Search() -> Element {
+    ///     let mut query = use_signal(String::new);
+    ///
+    ///     use_future(move || async move {
+    ///         let mut queries = query.to_stream().skip(1);
+    ///         while let Some(query) = queries.next().await {
+    ///             save(query).await;
+    ///         }
+    ///     });
+    ///
+    ///     rsx! {
+    ///         input { value: "{query}", oninput: move |event| query.set(event.value()) }
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn to_stream(&self) -> impl Stream<Item = T> {
+        let (rc, mut changed) = ReactiveContext::new();
+        let signal = *self;
+        let mut first = true;
+        stream::poll_fn(move |cx| {
+            if !std::mem::take(&mut first) {
+                if ready!(changed.poll_next_unpin(cx)).is_none() {
+                    return Poll::Ready(None);
+                }
+                // Any writes while waiting are covered by the value we read now
+                while let Ok(Some(())) = changed.try_next() {}
+            }
+            Poll::Ready(rc.run_in(|| signal.try_read().map(|value| value.clone()).ok()))
+        })
+    }
+}
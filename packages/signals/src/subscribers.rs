@@ -0,0 +1,35 @@
+use dioxus_core::prelude::*;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+/// A set of reactive contexts that read some part of a value. This is used by collections that track reads more
+/// precisely than a single [`Signal`](crate::Signal).
+#[derive(Clone, Default)]
+pub(crate) struct Subscribers(Arc<Mutex<HashSet<ReactiveContext>>>);
+
+impl Subscribers {
+    /// Subscribe the current reactive context to this set
+    pub(crate) fn subscribe(&self) {
+        if let Some(reactive_context) = ReactiveContext::current() {
+            reactive_context.subscribe(self.0.clone());
+        }
+    }
+
+    /// Mark every subscriber as dirty
+    pub(crate) fn notify(&self) {
+        // We cannot hold the subscribers lock while calling mark_dirty, because mark_dirty can run user code which may cause a new subscriber to be added
+        #[allow(clippy::mutable_key_type)]
+        let mut subscribers = std::mem::take(&mut *self.0.lock().unwrap());
+        subscribers.retain(|reactive_context| reactive_context.mark_dirty());
+        self.0.lock().unwrap().extend(subscribers);
+    }
+
+    /// Mark every subscriber in a list of sets as dirty
+    pub(crate) fn notify_all(subscribers: impl IntoIterator<Item = Self>) {
+        for subscribers in subscribers {
+            subscribers.notify();
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// A reactive context that records when it is marked dirty
+struct Tracker {
+    updates: UnboundedReceiver<()>,
+}
+
+impl Tracker {
+    fn new(read: impl FnOnce()) -> Self {
+        let (context, updates) = ReactiveContext::new();
+        context.run_in(read);
+        Self { updates }
+    }
+
+    /// Check if the context was marked dirty since the last check
+    fn dirty(&mut self) -> bool {
+        self.updates.try_next().is_ok()
+    }
+}
+
+fn in_dom(f: impl FnOnce()) {
+    let dom = VirtualDom::new(|| rsx! {});
+    dom.in_runtime(|| ScopeId::ROOT.in_runtime(f));
+}
+
+#[test]
+fn batch_defers_notifications() {
+    in_dom(|| {
+        let mut first = Signal::new(0);
+        let mut second = Signal::new(0);
+        let mut both = Tracker::new(|| {
+            first.read();
+            second.read();
+        });
+
+        let output = batch(|| {
+            first.set(1);
+            second.set(2);
+            // Nested batches are flushed by the outermost batch
+            batch(|| second += 1);
+            assert!(!both.dirty());
+            first() + second()
+        });
+        assert_eq!(output, 4);
+        assert!(both.dirty());
+        // The context is only marked dirty once
+        assert!(!both.dirty());
+
+        // Outside of a batch, writes notify immediately
+        first.set(5);
+        assert!(both.dirty());
+    });
+}
+
+#[test]
+fn memos_see_a_consistent_snapshot_in_a_batch() {
+    in_dom(|| {
+        let mut first = Signal::new(1);
+        let mut second = Signal::new(1);
+        let runs = Rc::new(Cell::new(0));
+        let sum = Memo::new({
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                first() + second()
+            }
+        });
+        assert_eq!(sum(), 2);
+
+        batch(|| {
+            first.set(10);
+            // The memo doesn't recompute with half of the new state
+            assert_eq!(sum(), 2);
+            second.set(20);
+        });
+        assert_eq!(sum(), 30);
+        assert_eq!(runs.get(), 2);
+    });
+}
+
+#[test]
+fn batch_sync_signals_on_other_threads() {
+    in_dom(|| {
+        let mut signal: SyncSignal<i32> = Signal::new_maybe_sync(0);
+        let marked = Arc::new(AtomicUsize::new(0));
+        let context = ReactiveContext::new_with_callback(
+            {
+                let marked = marked.clone();
+                move || {
+                    marked.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+            ScopeId::ROOT,
+            std::panic::Location::caller(),
+        );
+        context.run_in(|| {
+            signal.read();
+        });
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                batch(|| {
+                    signal.set(1);
+                    signal.set(2);
+                    assert_eq!(marked.load(Ordering::SeqCst), 0);
+                });
+                assert_eq!(marked.load(Ordering::SeqCst), 1);
+            });
+        });
+        assert_eq!(signal(), 2);
+    });
+}
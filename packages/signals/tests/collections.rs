@@ -0,0 +1,180 @@
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+
+/// A reactive context that records when it is marked dirty
+struct Tracker {
+    updates: UnboundedReceiver<()>,
+}
+
+impl Tracker {
+    fn new(read: impl FnOnce()) -> Self {
+        let (context, updates) = ReactiveContext::new();
+        context.run_in(read);
+        Self { updates }
+    }
+
+    /// Check if the context was marked dirty since the last check
+    fn dirty(&mut self) -> bool {
+        self.updates.try_next().is_ok()
+    }
+}
+
+fn in_dom(f: impl FnOnce()) {
+    let dom = VirtualDom::new(|| rsx! {});
+    dom.in_runtime(|| ScopeId::ROOT.in_runtime(f));
+}
+
+#[test]
+fn signal_vec_tracks_indexes_and_structure() {
+    in_dom(|| {
+        let mut list = SignalVec::new(["a", "b", "c"]);
+
+        let mut len = Tracker::new(|| {
+            list.len();
+        });
+        let mut first = Tracker::new(|| {
+            list.get(0).unwrap().read();
+        });
+        let mut second = Tracker::new(|| {
+            list.get(1).unwrap().read();
+        });
+
+        // Changing a value only updates the readers of that entry
+        list.set(1, "B");
+        assert!(!len.dirty());
+        assert!(!first.dirty());
+        assert!(second.dirty());
+
+        // Pushing doesn't move any existing entries
+        list.push("d");
+        assert!(len.dirty());
+        assert!(!first.dirty());
+        assert!(!second.dirty());
+
+        // Inserting at the start moves every entry
+        list.insert(0, "z");
+        assert!(len.dirty());
+        assert!(first.dirty());
+        assert!(second.dirty());
+        assert_eq!(list.to_vec(), ["z", "a", "B", "c", "d"]);
+
+        // Removing an entry only moves the entries after it
+        let mut last = Tracker::new(|| {
+            list.get(4).unwrap().read();
+        });
+        let entry = list.get(3).unwrap();
+        assert_eq!(list.remove(3), "c");
+        assert!(len.dirty());
+        assert!(!first.dirty());
+        assert!(!second.dirty());
+        assert!(last.dirty());
+        // The signal of the removed entry is dropped
+        assert!(entry.try_read().is_err());
+
+        list.swap(0, 1);
+        assert!(first.dirty());
+        assert!(second.dirty());
+
+        // The predicate can read the list
+        let reader = list;
+        list.retain(|value| *value != "z" && !reader.is_empty());
+        assert_eq!(list.to_vec(), ["a", "B", "d"]);
+        assert_eq!(list.pop(), Some("d"));
+        list.clear();
+        assert!(list.is_empty());
+        assert!(list.pop().is_none());
+    });
+}
+
+#[test]
+fn signal_vec_iteration_does_not_track_values() {
+    in_dom(|| {
+        let mut list = SignalVec::new([1, 2, 3]);
+        let mut iterate = Tracker::new(|| {
+            for entry in list.iter() {
+                let _ = entry.id();
+            }
+        });
+
+        list.set(0, 10);
+        assert!(!iterate.dirty());
+
+        list.push(4);
+        assert!(iterate.dirty());
+        assert_eq!(list.to_vec(), [10, 2, 3, 4]);
+    });
+}
+
+#[test]
+fn signal_map_tracks_keys_and_structure() {
+    in_dom(|| {
+        let mut map = SignalMap::new([("a", 1), ("b", 2)]);
+
+        let mut len = Tracker::new(|| {
+            map.len();
+        });
+        let mut a = Tracker::new(|| {
+            map.get(&"a").unwrap().read();
+        });
+        // Missing keys don't have their own subscribers, so reading one subscribes to the structure of the map
+        let mut c = Tracker::new(|| {
+            assert!(!map.contains_key(&"c"));
+        });
+
+        // Replacing a value only updates the readers of that value
+        assert_eq!(map.insert("a", 10), Some(1));
+        assert!(!len.dirty());
+        assert!(a.dirty());
+        assert!(!c.dirty());
+
+        assert_eq!(map.insert("c", 3), None);
+        assert!(len.dirty());
+        assert!(!a.dirty());
+        assert!(c.dirty());
+        let mut c = Tracker::new(|| {
+            map.get(&"c").unwrap().read();
+        });
+
+        let b = map.get(&"b").unwrap();
+        assert_eq!(map.remove(&"b"), Some(2));
+        assert!(b.try_read().is_err());
+        assert!(len.dirty());
+        assert!(!a.dirty());
+        assert!(!c.dirty());
+        assert!(map.remove(&"b").is_none());
+
+        let mut keys: Vec<_> = map.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["a", "c"]);
+
+        // The predicate can read the map
+        let reader = map;
+        map.retain(|_, value| *value > 5 && reader.contains_key(&"a"));
+        assert!(len.dirty());
+        assert!(c.dirty());
+        assert_eq!(map.to_hash_map(), [("a", 10)].into_iter().collect());
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(a.dirty());
+    });
+}
+
+#[test]
+fn removed_entries_are_recycled() {
+    in_dom(|| {
+        let mut list = SignalVec::new(0..100);
+        let mut map = SignalMap::new((0..100).map(|i| (i, i)));
+        let live = UnsyncStorage::stats().live;
+
+        for _ in 0..50 {
+            list.pop();
+        }
+        list.retain(|value| value % 2 == 0);
+        for i in 0..50 {
+            map.remove(&i);
+        }
+        map.clear();
+        assert_eq!(UnsyncStorage::stats().live, live - 175);
+    });
+}
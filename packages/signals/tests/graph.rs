@@ -0,0 +1,116 @@
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Default)]
+struct State {
+    show: Cell<Option<Signal<bool>>>,
+    leaked: Cell<Option<Signal<i32>>>,
+    owned: Cell<Option<Signal<i32>>>,
+    owner: Cell<Option<Owner>>,
+}
+
+#[derive(Clone)]
+struct Shared(Rc<State>);
+
+impl PartialEq for Shared {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[component]
+fn Child(state: Shared) -> Element {
+    use_hook(|| {
+        // A signal with an owner that outlives the component
+        let owner = UnsyncStorage::owner();
+        let leaked = with_owner(owner.clone(), || Signal::new(0));
+        state.0.owner.set(Some(owner));
+        state.0.leaked.set(Some(leaked));
+    });
+    let owned = use_signal(|| 0);
+    state.0.owned.set(Some(owned));
+    rsx! {}
+}
+
+#[test]
+fn signals_that_outlive_their_scope_are_leaks() {
+    let state = Rc::new(State::default());
+    let mut dom = VirtualDom::new_with_props(
+        |state: Shared| {
+            let show = use_signal(|| true);
+            state.0.show.set(Some(show));
+            rsx! {
+                if show() {
+                    Child { state: state.clone() }
+                }
+            }
+        },
+        Shared(state.clone()),
+    );
+    dom.rebuild_in_place();
+    let leaked = state.leaked.get().unwrap();
+    let owned = state.owned.get().unwrap();
+
+    let graph = dom.in_runtime(SignalGraph::capture);
+    assert!(graph.leaks().next().is_none());
+    let signals: Vec<_> = graph.signals.iter().map(|signal| signal.id).collect();
+    assert!(signals.contains(&leaked.id()));
+    assert!(signals.contains(&owned.id()));
+
+    // Unmount the child. The signal owned by the child is dropped, but the leaked signal is still alive
+    let mut show = state.show.get().unwrap();
+    dom.in_runtime(|| show.set(false));
+    dom.render_immediate(&mut NoOpMutations);
+
+    let graph = dom.in_runtime(SignalGraph::capture);
+    let leaks: Vec<_> = graph.leaks().map(|signal| signal.id).collect();
+    assert_eq!(leaks, [leaked.id()]);
+    let signals: Vec<_> = graph.signals.iter().map(|signal| signal.id).collect();
+    assert!(!signals.contains(&owned.id()));
+}
+
+#[test]
+fn memos_that_are_never_read_are_reported() {
+    let state = Rc::new(State::default());
+    let mut dom = VirtualDom::new_with_props(
+        |state: Shared| {
+            let count = use_signal(|| 1);
+            let doubled = use_memo(move || count() * 2);
+            // The tripled memo is never read
+            let _tripled = use_memo(move || count() * 3);
+            state.0.owned.set(Some(count));
+            rsx! { "{doubled}" }
+        },
+        Shared(state.clone()),
+    );
+    dom.rebuild_in_place();
+    let count = state.owned.get().unwrap();
+
+    let graph = dom.in_runtime(SignalGraph::capture);
+    let unread = graph.unread_memos().collect::<Vec<_>>();
+    assert_eq!(unread.len(), 1);
+    assert_eq!(unread[0].kind, SignalKind::Memo);
+
+    // Both memos and the component subscribe to the count
+    let count = graph
+        .signals
+        .iter()
+        .find(|signal| signal.id == count.id())
+        .unwrap();
+    assert_eq!(count.kind, SignalKind::Signal);
+    assert_eq!(count.subscribers.len(), 2);
+    assert!(count
+        .subscribers
+        .iter()
+        .all(|subscriber| subscriber.memo.is_some()));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph signals {"));
+    assert!(dot.contains(&format!("\"{:?}\" -> \"{:?}\"", count.id, unread[0].id)));
+
+    let json = graph.to_json();
+    assert!(json.starts_with('[') && json.ends_with(']'));
+    assert!(json.contains(r#""kind":"Memo""#));
+    assert!(json.contains(r#""reads":0"#));
+}
@@ -0,0 +1,129 @@
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use std::time::Duration;
+
+/// A reactive context that records when it is marked dirty
+struct Tracker {
+    updates: UnboundedReceiver<()>,
+}
+
+impl Tracker {
+    fn new(read: impl FnOnce()) -> Self {
+        let (context, updates) = ReactiveContext::new();
+        context.run_in(read);
+        Self { updates }
+    }
+
+    /// Check if the context was marked dirty since the last check
+    fn dirty(&mut self) -> bool {
+        self.updates.try_next().is_ok()
+    }
+}
+
+fn in_dom(f: impl FnOnce()) {
+    let dom = VirtualDom::new(|| rsx! {});
+    dom.in_runtime(|| ScopeId::ROOT.in_runtime(f));
+}
+
+#[test]
+fn history_undoes_and_redoes_writes() {
+    in_dom(|| {
+        let mut count = Signal::new(0);
+        let mut untracked = Signal::new(0);
+        let history = History::new();
+        history.track(count);
+
+        let mut can_undo = Tracker::new(|| {
+            history.can_undo();
+        });
+        assert!(!history.can_undo());
+        assert!(!history.undo());
+
+        count.set(1);
+        count += 1;
+        untracked.set(1);
+        assert!(can_undo.dirty());
+        assert!(history.can_undo());
+
+        assert!(history.undo());
+        assert_eq!(count(), 1);
+        assert!(history.can_redo());
+        assert!(history.undo());
+        assert_eq!(count(), 0);
+        assert!(!history.can_undo());
+        assert_eq!(untracked(), 1);
+
+        assert!(history.redo());
+        assert_eq!(count(), 1);
+
+        // A new write forgets the steps that were undone
+        count.set(10);
+        assert!(!history.can_redo());
+        assert!(history.undo());
+        assert_eq!(count(), 1);
+
+        // Writes after the signal is untracked are not recorded
+        history.clear();
+        history.untrack(count);
+        count.set(20);
+        assert!(!history.can_undo());
+    });
+}
+
+#[test]
+fn history_batches_writes_to_several_signals() {
+    in_dom(|| {
+        let mut text = Signal::new(String::from("hello"));
+        let mut bold = Signal::new(true);
+        let history = History::new();
+        history.track(text);
+        history.track(bold);
+
+        history.batch(|| {
+            text.set(String::new());
+            text.set(String::from("cleared"));
+            bold.set(false);
+        });
+        assert!(history.undo());
+        assert_eq!(text(), "hello");
+        assert!(bold());
+        assert!(!history.can_undo());
+
+        assert!(history.redo());
+        assert_eq!(text(), "cleared");
+        assert!(!bold());
+    });
+}
+
+#[test]
+fn history_coalesces_writes_and_limits_depth() {
+    in_dom(|| {
+        let mut text = Signal::new(String::new());
+        let history = History::new()
+            .with_coalesce(Duration::from_secs(60))
+            .with_max_depth(2);
+        history.track(text);
+
+        // Typing quickly is undone in one step
+        for c in "abc".chars() {
+            text.write().push(c);
+        }
+        assert!(history.undo());
+        assert_eq!(text(), "");
+        assert!(!history.can_undo());
+    });
+
+    in_dom(|| {
+        let mut count = Signal::new(0);
+        let history = History::new().with_max_depth(2);
+        history.track(count);
+
+        for i in 1..=5 {
+            count.set(i);
+        }
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(!history.undo());
+        assert_eq!(count(), 3);
+    });
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "persistent")]
+
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use std::{cell::RefCell, rc::Rc};
+
+fn in_dom(f: impl FnOnce()) {
+    let dom = VirtualDom::new(|| rsx! {});
+    dom.in_runtime(|| ScopeId::ROOT.in_runtime(f));
+}
+
+#[test]
+fn persistent_signals_save_and_load() {
+    let storage = MemoryStorage::default();
+
+    in_dom(|| {
+        let mut count = PersistentSignal::new_with_storage("count", || 0, storage.clone());
+        assert_eq!(count(), 0);
+        assert_eq!(storage.get("count"), None);
+
+        count += 2;
+        assert_eq!(storage.get("count").as_deref(), Some("2"));
+        count.set(5);
+        assert_eq!(storage.get("count").as_deref(), Some("5"));
+    });
+
+    // The next time the app starts, the saved value is loaded
+    in_dom(|| {
+        let count = PersistentSignal::new_with_storage("count", || 0, storage.clone());
+        assert_eq!(count(), 5);
+
+        count.forget();
+        assert_eq!(storage.get("count"), None);
+        assert_eq!(count(), 5);
+    });
+
+    // Values that cannot be decoded are replaced with the initial value
+    storage.set("count", "\"not a number\"".to_string());
+    in_dom(|| {
+        let count = PersistentSignal::new_with_storage("count", || 1, storage.clone());
+        assert_eq!(count(), 1);
+    });
+}
+
+#[test]
+fn persistent_signals_use_the_storage_from_the_context() {
+    let storage = MemoryStorage::default();
+    storage.set("name", "\"Alice\"".to_string());
+
+    in_dom(|| {
+        provide_persistent_storage(storage.clone());
+        let mut name = PersistentSignal::new("name", String::new);
+        assert_eq!(name(), "Alice");
+        name.set("Bob".to_string());
+    });
+    assert_eq!(storage.get("name").as_deref(), Some("\"Bob\""));
+
+    // Without any storage, persistent signals are normal signals
+    in_dom(|| {
+        let mut name = PersistentSignal::new("name", || "Carol".to_string());
+        assert_eq!(name(), "Carol");
+        name.set("Dave".to_string());
+    });
+    assert_eq!(storage.get("name").as_deref(), Some("\"Bob\""));
+}
+
+/// A storage that is used while hydrating html from the server
+#[derive(Clone, Default)]
+struct HydratingStorage(MemoryStorage);
+
+impl PersistentStorage for HydratingStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.0.get(key)
+    }
+
+    fn set(&self, key: &str, value: String) {
+        self.0.set(key, value)
+    }
+
+    fn remove(&self, key: &str) {
+        self.0.remove(key)
+    }
+
+    fn load_after_hydration(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn persistent_signals_load_after_hydration() {
+    let storage = HydratingStorage::default();
+    storage.set("count", "10".to_string());
+    let renders = Rc::new(RefCell::new(Vec::new()));
+
+    let mut dom = VirtualDom::new_with_props(
+        |(storage, renders): (HydratingStorage, Rc<RefCell<Vec<i32>>>)| {
+            let count = use_hook(|| PersistentSignal::new_with_storage("count", || 0, storage));
+            renders.borrow_mut().push(count());
+            rsx! {}
+        },
+        (storage.clone(), renders.clone()),
+    );
+
+    // The first render matches the server, then the saved value is loaded
+    dom.rebuild_in_place();
+    assert_eq!(*renders.borrow(), [0]);
+    tokio::select! {
+        _ = dom.wait_for_work() => {}
+        _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => panic!("the saved value was never loaded"),
+    }
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(*renders.borrow(), [0, 10]);
+
+    // Loading the saved value doesn't write it back
+    assert_eq!(storage.get("count").as_deref(), Some("10"));
+}
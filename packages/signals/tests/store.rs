@@ -0,0 +1,198 @@
+use dioxus::prelude::*;
+use futures_channel::mpsc::UnboundedReceiver;
+use std::collections::HashMap;
+
+/// A reactive context that records when it is marked dirty
+struct Tracker {
+    updates: UnboundedReceiver<()>,
+}
+
+impl Tracker {
+    fn new(read: impl FnOnce()) -> Self {
+        let (context, updates) = ReactiveContext::new();
+        context.run_in(read);
+        Self { updates }
+    }
+
+    /// Check if the context was marked dirty since the last check
+    fn dirty(&mut self) -> bool {
+        self.updates.try_next().is_ok()
+    }
+}
+
+fn in_dom(f: impl FnOnce()) {
+    let dom = VirtualDom::new(|| rsx! {});
+    dom.in_runtime(|| ScopeId::ROOT.in_runtime(f));
+}
+
+#[derive(Store)]
+struct AppState {
+    user: User,
+    todos: Vec<String>,
+    scores: HashMap<String, u32>,
+}
+
+#[derive(Store)]
+struct User {
+    name: String,
+    email: Option<String>,
+}
+
+fn app_state() -> Store<AppState> {
+    Store::new(AppState {
+        user: User {
+            name: "Alice".to_string(),
+            email: None,
+        },
+        todos: vec!["a".to_string(), "b".to_string()],
+        scores: HashMap::new(),
+    })
+}
+
+#[test]
+fn store_fields_track_separately() {
+    in_dom(|| {
+        let state = app_state();
+
+        let mut whole = Tracker::new(|| {
+            state.read();
+        });
+        let mut user = Tracker::new(|| {
+            state.user().read();
+        });
+        let mut name = Tracker::new(|| {
+            state.user().name().read();
+        });
+        let mut email = Tracker::new(|| {
+            state.user().email().is_some();
+        });
+        let mut todos = Tracker::new(|| {
+            state.todos().read();
+        });
+
+        // Writing a field updates the field, the structs that contain it, but not its siblings
+        state.user().name().set("Bob".to_string());
+        assert!(whole.dirty());
+        assert!(user.dirty());
+        assert!(name.dirty());
+        assert!(!email.dirty());
+        assert!(!todos.dirty());
+        assert_eq!(state.user().name().cloned(), "Bob");
+
+        // Writing a struct updates every field inside of it
+        state.user().set(User {
+            name: "Carol".to_string(),
+            email: Some("carol@example.com".to_string()),
+        });
+        assert!(whole.dirty());
+        assert!(user.dirty());
+        assert!(name.dirty());
+        assert!(email.dirty());
+        assert!(!todos.dirty());
+
+        let email_value = state.user().email().transpose().unwrap();
+        assert_eq!(email_value.cloned(), "carol@example.com");
+    });
+}
+
+#[test]
+fn store_collections_track_items_and_structure() {
+    in_dom(|| {
+        let state = app_state();
+
+        let mut len = Tracker::new(|| {
+            state.todos().len();
+        });
+        let mut first = Tracker::new(|| {
+            state.todos().get(0).unwrap().read();
+        });
+        let mut second = Tracker::new(|| {
+            state.todos().get(1).unwrap().read();
+        });
+
+        // Writing an item only updates the readers of that item
+        state.todos().get(1).unwrap().set("B".to_string());
+        assert!(!len.dirty());
+        assert!(!first.dirty());
+        assert!(second.dirty());
+
+        // Pushing doesn't change the existing items
+        state.todos().push("c".to_string());
+        assert!(len.dirty());
+        assert!(!first.dirty());
+        assert!(!second.dirty());
+
+        // Removing the last item only updates the readers of that item
+        let mut last = Tracker::new(|| {
+            state.todos().get(2).unwrap().read();
+        });
+        assert_eq!(state.todos().pop(), Some("c".to_string()));
+        assert!(len.dirty());
+        assert!(!first.dirty());
+        assert!(last.dirty());
+
+        // Writing the whole list updates every item
+        state.todos().write().insert(0, "z".to_string());
+        assert!(len.dirty());
+        assert!(first.dirty());
+        assert!(second.dirty());
+        let items: Vec<_> = state.todos().iter().map(|item| item.cloned()).collect();
+        assert_eq!(items, ["z", "a", "B"]);
+
+        let mut alice = Tracker::new(|| {
+            assert!(state.scores().get(&"alice".to_string()).is_none());
+        });
+        let mut scores = Tracker::new(|| {
+            state.scores().len();
+        });
+        // Missing keys don't have their own subscribers, so reading one subscribes to the structure of the map
+        assert_eq!(state.scores().insert("bob".to_string(), 1), None);
+        assert!(alice.dirty());
+        assert!(scores.dirty());
+
+        assert_eq!(state.scores().insert("alice".to_string(), 2), None);
+        assert!(alice.dirty());
+        assert!(scores.dirty());
+
+        let mut alice_score = Tracker::new(|| {
+            state.scores().get(&"alice".to_string()).unwrap().read();
+        });
+        assert_eq!(state.scores().insert("alice".to_string(), 3), Some(2));
+        assert!(alice_score.dirty());
+        assert!(!scores.dirty());
+
+        assert_eq!(state.scores().remove(&"bob".to_string()), Some(1));
+        assert!(scores.dirty());
+        assert!(!alice_score.dirty());
+        assert_eq!(state.scores().keys().collect::<Vec<_>>(), ["alice"]);
+
+        // Removed keys start with new subscribers when they are inserted again
+        assert_eq!(state.scores().remove(&"alice".to_string()), Some(3));
+        assert!(alice_score.dirty());
+        state.scores().insert("alice".to_string(), 4);
+        let mut alice_score = Tracker::new(|| {
+            state.scores().get(&"alice".to_string()).unwrap().read();
+        });
+        state.scores().insert("alice".to_string(), 5);
+        assert!(alice_score.dirty());
+        assert_eq!(
+            state.scores().get(&"alice".to_string()).unwrap().cloned(),
+            5
+        );
+    });
+}
+
+#[derive(Store)]
+#[store(crate = "dioxus_signals")]
+struct Counter {
+    count: i32,
+}
+
+#[test]
+fn store_crate_path_can_be_overridden() {
+    in_dom(|| {
+        let counter = Store::new(Counter { count: 0 });
+        *counter.count().write() += 1;
+        assert_eq!(counter.count().cloned(), 1);
+    });
+}
@@ -0,0 +1,117 @@
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures_util::{FutureExt, StreamExt};
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+#[derive(Default)]
+struct State {
+    latest: Cell<Option<Signal<Option<i32>>>>,
+    all: Cell<Option<Signal<Vec<i32>>>>,
+    streams: Cell<Option<(UnboundedReceiver<i32>, UnboundedReceiver<i32>)>>,
+}
+
+/// Create a dom with a latest-only and an accumulating signal fed by the returned channels
+fn dom() -> (
+    VirtualDom,
+    Rc<State>,
+    UnboundedSender<i32>,
+    UnboundedSender<i32>,
+) {
+    let state = Rc::new(State::default());
+    let (latest_tx, latest_rx) = futures_channel::mpsc::unbounded();
+    let (all_tx, all_rx) = futures_channel::mpsc::unbounded();
+    state.streams.set(Some((latest_rx, all_rx)));
+    let dom = VirtualDom::new_with_props(
+        |state: Rc<State>| {
+            use_hook(|| {
+                let (latest_rx, all_rx) = state.streams.take().unwrap();
+                state.latest.set(Some(Signal::from_stream(latest_rx)));
+                state.all.set(Some(Signal::from_stream_accumulate(all_rx)));
+            });
+            rsx! {}
+        },
+        state.clone(),
+    );
+    (dom, state, latest_tx, all_tx)
+}
+
+/// Process work in the dom for some time
+async fn run_for(dom: &mut VirtualDom, duration: Duration) {
+    let _ = tokio::time::timeout(duration, async {
+        loop {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn streams_drive_signals() {
+    let (mut dom, state, latest_tx, all_tx) = dom();
+    dom.rebuild_in_place();
+    let latest = state.latest.get().unwrap();
+    let all = state.all.get().unwrap();
+    assert_eq!(dom.in_runtime(|| latest.cloned()), None);
+
+    for i in 1..=3 {
+        latest_tx.unbounded_send(i).unwrap();
+        all_tx.unbounded_send(i).unwrap();
+    }
+    run_for(&mut dom, Duration::from_millis(10)).await;
+    assert_eq!(dom.in_runtime(|| latest.cloned()), Some(3));
+    assert_eq!(dom.in_runtime(|| all.cloned()), [1, 2, 3]);
+
+    all_tx.unbounded_send(4).unwrap();
+    run_for(&mut dom, Duration::from_millis(10)).await;
+    assert_eq!(dom.in_runtime(|| all.cloned()), [1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn streams_that_are_always_ready_are_written_in_batches() {
+    let count = Rc::new(Cell::new(None));
+    let mut dom = VirtualDom::new_with_props(
+        |count: Rc<Cell<Option<Signal<Option<i32>>>>>| {
+            let signal = use_hook(|| Signal::from_stream(futures_util::stream::iter(1..)));
+            count.set(Some(signal));
+            // Reading the signal lets the dom rerender the component between batches
+            signal.read();
+            rsx! {}
+        },
+        count.clone(),
+    );
+    dom.rebuild_in_place();
+    let count = count.get().unwrap();
+
+    // The stream never runs out of items, but the task waits for the component to rerender after every batch
+    for batch in 1..=3 {
+        dom.wait_for_work().await;
+        dom.render_immediate(&mut NoOpMutations);
+        assert_eq!(dom.in_runtime(|| count.cloned()), Some(batch * 256));
+    }
+}
+
+#[test]
+fn signals_can_be_streamed() {
+    let dom = VirtualDom::new(|| rsx! {});
+    let mut stream = dom.in_runtime(|| {
+        ScopeId::ROOT.in_runtime(|| {
+            let mut count = Signal::new(0);
+            let mut stream = count.to_stream();
+            assert_eq!(stream.next().now_or_never(), Some(Some(0)));
+            assert_eq!(stream.next().now_or_never(), None);
+
+            // Writes between polls are yielded as one value
+            count.set(1);
+            count.set(2);
+            assert_eq!(stream.next().now_or_never(), Some(Some(2)));
+            assert_eq!(stream.next().now_or_never(), None);
+            stream
+        })
+    });
+
+    // The stream ends when the component it was created in is dropped
+    drop(dom);
+    assert_eq!(stream.next().now_or_never(), Some(None));
+}
@@ -8,8 +8,15 @@ use std::{
     time::Duration,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
 use super::fs_cache::PathMapFn;
 use super::memory_cache::InMemoryCache;
+use super::CacheStorage;
+
+#[cfg(target_arch = "wasm32")]
+type PathMapFn = Arc<dyn Fn(&str) -> PathBuf + Send + Sync>;
+
+type StorageFn = Arc<dyn Fn() -> Box<dyn CacheStorage> + Send + Sync>;
 
 /// A configuration for the incremental renderer.
 #[derive(Clone)]
@@ -17,7 +24,9 @@ pub struct IncrementalRendererConfig {
     static_dir: PathBuf,
     memory_cache_limit: usize,
     invalidate_after: Option<Duration>,
+    stale_while_revalidate: Option<Duration>,
     map_path: Option<PathMapFn>,
+    storage: Option<StorageFn>,
     clear_cache: bool,
     pre_render: bool,
 }
@@ -35,7 +44,9 @@ impl IncrementalRendererConfig {
             static_dir: PathBuf::from("./static"),
             memory_cache_limit: 10000,
             invalidate_after: None,
+            stale_while_revalidate: None,
             map_path: None,
+            storage: None,
             clear_cache: true,
             pre_render: false,
         }
@@ -54,6 +65,15 @@ impl IncrementalRendererConfig {
         self
     }
 
+    /// Store renders in a custom [`CacheStorage`] backend instead of the file system. This will override the default
+    /// storage configured with `static_dir` and `map_path`.
+    ///
+    /// The storage is cloned for each renderer that is built from this configuration.
+    pub fn storage<S: CacheStorage + Clone + 'static>(mut self, storage: S) -> Self {
+        self.storage = Some(Arc::new(move || Box::new(storage.clone())));
+        self
+    }
+
     /// Set the static directory.
     pub fn static_dir<P: AsRef<Path>>(mut self, static_dir: P) -> Self {
         self.static_dir = static_dir.as_ref().to_path_buf();
@@ -72,6 +92,15 @@ impl IncrementalRendererConfig {
         self
     }
 
+    /// Keep serving renders for some time after `invalidate_after` while a new render is generated in the background.
+    ///
+    /// Stale renders are marked with [`RenderFreshness::is_stale`](super::RenderFreshness::is_stale) and the
+    /// `stale-while-revalidate` cache control directive. This has no effect unless `invalidate_after` is set.
+    pub fn stale_while_revalidate(mut self, stale_while_revalidate: Duration) -> Self {
+        self.stale_while_revalidate = Some(stale_while_revalidate);
+        self
+    }
+
     /// Set whether to include hydration ids in the pre-rendered html.
     pub fn pre_render(mut self, pre_render: bool) -> Self {
         self.pre_render = pre_render;
@@ -80,15 +109,23 @@ impl IncrementalRendererConfig {
 
     /// Build the incremental renderer.
     pub fn build(self) -> IncrementalRenderer {
-        let mut renderer = IncrementalRenderer {
+        let storage = match self.storage {
+            Some(storage) => Some(storage()),
             #[cfg(not(target_arch = "wasm32"))]
-            file_system_cache: crate::incremental::fs_cache::FileSystemCache::new(
+            None => Some(Box::new(super::fs_cache::FileSystemCache::new(
                 self.static_dir.clone(),
                 self.map_path,
                 self.invalidate_after,
-            ),
-            memory_cache: InMemoryCache::new(self.memory_cache_limit, self.invalidate_after),
+            )) as Box<dyn CacheStorage>),
+            #[cfg(target_arch = "wasm32")]
+            None => None,
+        };
+        let mut renderer = IncrementalRenderer {
+            storage,
+            memory_cache: InMemoryCache::new(self.memory_cache_limit),
             invalidate_after: self.invalidate_after,
+            stale_while_revalidate: self.stale_while_revalidate,
+            regenerating: Default::default(),
         };
 
         if self.clear_cache {
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 
 /// Information about the freshness of a rendered response
 #[derive(Debug, Clone, Copy)]
@@ -9,29 +10,15 @@ pub struct RenderFreshness {
     age: u64,
     /// The maximum age of the rendered response
     max_age: Option<u64>,
+    /// How long after the maximum age the stale response may be served while it is regenerated
+    stale_while_revalidate: Option<u64>,
     /// The time the response was rendered
     timestamp: DateTime<Utc>,
+    /// A hash of the rendered response. This is only known for cached responses
+    etag: Option<u128>,
 }
 
 impl RenderFreshness {
-    /// Create new freshness information
-    pub(crate) fn new(age: u64, max_age: u64, timestamp: DateTime<Utc>) -> Self {
-        Self {
-            age,
-            max_age: Some(max_age),
-            timestamp,
-        }
-    }
-
-    /// Create new freshness information with only the age
-    pub(crate) fn new_age(age: u64, timestamp: DateTime<Utc>) -> Self {
-        Self {
-            age,
-            max_age: None,
-            timestamp,
-        }
-    }
-
     /// Create new freshness information from a timestamp
     pub(crate) fn created_at(timestamp: DateTime<Utc>, max_age: Option<Duration>) -> Self {
         Self {
@@ -40,7 +27,9 @@ impl RenderFreshness {
                 .num_seconds()
                 .unsigned_abs(),
             max_age: max_age.map(|d| d.as_secs()),
+            stale_while_revalidate: None,
             timestamp,
+            etag: None,
         }
     }
 
@@ -49,10 +38,29 @@ impl RenderFreshness {
         Self {
             age: 0,
             max_age: max_age.map(|d| d.as_secs()),
+            stale_while_revalidate: None,
             timestamp: Utc::now(),
+            etag: None,
         }
     }
 
+    /// Allow the response to be served for some time after it becomes stale while it is regenerated
+    pub fn with_stale_while_revalidate(mut self, stale_while_revalidate: Option<Duration>) -> Self {
+        self.stale_while_revalidate = stale_while_revalidate.map(|d| d.as_secs());
+        self
+    }
+
+    /// Set the ETag of the response to a hash of the rendered html
+    pub fn with_etag_of(mut self, html: &[u8]) -> Self {
+        self.etag = Some(hash_html(html));
+        self
+    }
+
+    pub(crate) fn with_etag(mut self, etag: Option<u128>) -> Self {
+        self.etag = etag;
+        self
+    }
+
     /// Get the age of the rendered response in seconds
     pub fn age(&self) -> u64 {
         self.age
@@ -63,20 +71,100 @@ impl RenderFreshness {
         self.max_age
     }
 
+    /// Get the number of seconds after the maximum age that the stale response may be served while it is regenerated
+    pub fn stale_while_revalidate(&self) -> Option<u64> {
+        self.stale_while_revalidate
+    }
+
+    /// Check if the response is older than its maximum age. Stale responses are only served while a new response is
+    /// rendered in the background.
+    pub fn is_stale(&self) -> bool {
+        self.max_age.is_some_and(|max_age| self.age >= max_age)
+    }
+
     /// Get the time the response was rendered
     pub fn timestamp(&self) -> DateTime<Utc> {
         self.timestamp
     }
 
-    /// Write the freshness to the response headers.
+    /// Get the ETag of the response if it is known. The ETag includes the surrounding quotes.
+    pub fn etag(&self) -> Option<String> {
+        self.etag.map(|hash| format!("\"{hash:032x}\""))
+    }
+
+    /// Get the time the response was rendered formatted as an HTTP date
+    pub fn last_modified(&self) -> String {
+        self.timestamp
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+    }
+
+    /// Check if a conditional request already has this response cached, so it can be answered with `304 Not Modified`.
+    ///
+    /// `If-None-Match` takes precedence over `If-Modified-Since`. Conditional requests are only honored for responses
+    /// with a known [ETag](RenderFreshness::etag), because responses that were just rendered may differ between requests.
+    pub fn is_not_modified(&self, request_headers: &http::HeaderMap<http::HeaderValue>) -> bool {
+        let Some(etag) = self.etag() else {
+            return false;
+        };
+
+        if let Some(if_none_match) = request_headers.get(http::header::IF_NONE_MATCH) {
+            let Ok(if_none_match) = if_none_match.to_str() else {
+                return false;
+            };
+            // If-None-Match uses the weak comparison, so W/ prefixes are ignored
+            return if_none_match
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag);
+        }
+
+        if let Some(if_modified_since) = request_headers.get(http::header::IF_MODIFIED_SINCE) {
+            let since = if_modified_since
+                .to_str()
+                .ok()
+                .and_then(|since| DateTime::parse_from_rfc2822(since).ok());
+            // HTTP dates only have second precision
+            return since.is_some_and(|since| self.timestamp.timestamp() <= since.timestamp());
+        }
+
+        false
+    }
+
+    /// Write the freshness to the response headers. `ETag` and `Last-Modified` are only written for cached responses
+    /// with a known [ETag](RenderFreshness::etag), because a response that was just rendered may differ on the next
+    /// request.
     pub fn write(&self, headers: &mut http::HeaderMap<http::HeaderValue>) {
         let age = self.age();
         headers.insert(http::header::AGE, age.into());
         if let Some(max_age) = self.max_age() {
+            let cache_control = match self.stale_while_revalidate() {
+                Some(stale_while_revalidate) => {
+                    format!("max-age={max_age}, stale-while-revalidate={stale_while_revalidate}")
+                }
+                None => format!("max-age={max_age}"),
+            };
             headers.insert(
                 http::header::CACHE_CONTROL,
-                http::HeaderValue::from_str(&format!("max-age={}", max_age)).unwrap(),
+                http::HeaderValue::from_str(&cache_control).unwrap(),
+            );
+        }
+        if let Some(etag) = self.etag() {
+            headers.insert(
+                http::header::ETAG,
+                http::HeaderValue::from_str(&etag).unwrap(),
+            );
+            headers.insert(
+                http::header::LAST_MODIFIED,
+                http::HeaderValue::from_str(&self.last_modified()).unwrap(),
             );
         }
     }
 }
+
+/// Hash rendered html for its ETag. The hash must be the same in every process and version of the server, so every
+/// replica sends the same ETag for the same render. This is the first 128 bits of the SHA-256 of the html.
+pub(crate) fn hash_html(html: &[u8]) -> u128 {
+    let digest = Sha256::digest(html);
+    u128::from_be_bytes(digest[..16].try_into().unwrap())
+}
@@ -2,15 +2,20 @@
 
 use chrono::{DateTime, Utc};
 
-use super::{IncrementalRendererError, RenderFreshness};
+use super::{CacheMetadata, CacheStorage, IncrementalRendererError};
+use rustc_hash::FxHashMap;
 use std::{path::PathBuf, sync::Arc, time::SystemTime};
 
 pub(crate) type PathMapFn = Arc<dyn Fn(&str) -> PathBuf + Send + Sync>;
 
+/// The default storage backend that writes each route to an `index.html` file in the static directory.
+///
+/// The files only contain the html, so tags are only tracked for routes cached by the current process.
 pub(crate) struct FileSystemCache {
     static_dir: PathBuf,
     map_path: PathMapFn,
     invalidate_after: Option<std::time::Duration>,
+    routes: FxHashMap<String, CacheMetadata>,
 }
 
 impl FileSystemCache {
@@ -32,53 +37,28 @@ impl FileSystemCache {
                 })
             }),
             invalidate_after,
+            routes: FxHashMap::default(),
         }
     }
 
-    pub fn put(
-        &mut self,
-        route: String,
-        timestamp: DateTime<Utc>,
-        data: Vec<u8>,
-    ) -> Result<(), IncrementalRendererError> {
-        use std::io::Write;
-        let file_path = self.route_as_path(&route, timestamp);
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
-            }
-        }
-        let file = std::fs::File::create(file_path)?;
-        let mut file = std::io::BufWriter::new(file);
-        file.write_all(&data)?;
-        Ok(())
-    }
-
-    pub fn clear(&mut self) {
-        // clear the static directory
-        let _ = std::fs::remove_dir_all(&self.static_dir);
-    }
-
-    pub fn invalidate(&mut self, route: &str) {
-        let file_path = self.find_file(route).unwrap().full_path;
-        if let Err(err) = std::fs::remove_file(file_path) {
-            tracing::error!("Failed to remove file: {}", err);
-        }
-    }
-
-    pub fn get(
+    fn read(
         &self,
         route: &str,
-    ) -> Result<Option<(RenderFreshness, Vec<u8>)>, IncrementalRendererError> {
+    ) -> Result<Option<(CacheMetadata, Vec<u8>)>, IncrementalRendererError> {
         if let Some(file_path) = self.find_file(route) {
-            if let Some(freshness) = file_path.freshness(self.invalidate_after) {
-                if let Ok(file) = std::fs::File::open(file_path.full_path) {
-                    let mut file = std::io::BufReader::new(file);
-                    let mut cache_hit = Vec::new();
-                    std::io::copy(&mut file, &mut cache_hit)?;
-                    tracing::trace!("file cache hit {:?}", route);
-                    return Ok(Some((freshness, cache_hit)));
-                }
+            if let Ok(file) = std::fs::File::open(file_path.full_path) {
+                let mut file = std::io::BufReader::new(file);
+                let mut cache_hit = Vec::new();
+                std::io::copy(&mut file, &mut cache_hit)?;
+                tracing::trace!("file cache hit {:?}", route);
+                let metadata = CacheMetadata {
+                    timestamp: file_path.timestamp.into(),
+                    tags: Vec::new(),
+                    // The ETag isn't stored, so the renderer hashes the html in the file again in case another
+                    // process wrote it
+                    etag: None,
+                };
+                return Ok(Some((metadata, cache_hit)));
             }
         }
 
@@ -87,35 +67,24 @@ impl FileSystemCache {
 
     fn find_file(&self, route: &str) -> Option<ValidCachedPath> {
         let mut file_path = (self.map_path)(route);
-        if let Some(deadline) = self.invalidate_after {
-            // find the first file that matches the route and is a html file
+        if self.track_timestamps() {
+            // Find the newest html file for the route. Expired renders are removed by the incremental renderer
             file_path.push("index");
-            if let Ok(dir) = std::fs::read_dir(file_path) {
-                for entry in dir.flatten() {
-                    if let Some(cached_path) = ValidCachedPath::try_from_path(entry.path()) {
-                        if let Ok(elapsed) = cached_path.timestamp.elapsed() {
-                            if elapsed < deadline {
-                                // The timestamp is valid, return the file
-                                return Some(cached_path);
-                            }
-                        }
-                        // if the timestamp is invalid or passed, delete the file
-                        if let Err(err) = std::fs::remove_file(entry.path()) {
-                            tracing::error!("Failed to remove file: {}", err);
-                        }
-                    }
-                }
-                None
-            } else {
-                None
-            }
+            std::fs::read_dir(file_path)
+                .ok()?
+                .flatten()
+                .filter_map(|entry| ValidCachedPath::try_from_path(entry.path()))
+                .max_by_key(|cached_path| cached_path.timestamp)
         } else {
             file_path.push("index.html");
-            file_path.exists().then_some({
-                ValidCachedPath {
-                    full_path: file_path,
-                    timestamp: SystemTime::now(),
-                }
+            // Without timestamps in the file name, fall back to the time the file was last written
+            let timestamp = std::fs::metadata(&file_path)
+                .ok()?
+                .modified()
+                .unwrap_or_else(|_| SystemTime::now());
+            Some(ValidCachedPath {
+                full_path: file_path,
+                timestamp,
             })
         }
     }
@@ -137,6 +106,73 @@ impl FileSystemCache {
     }
 }
 
+impl CacheStorage for FileSystemCache {
+    fn get(
+        &mut self,
+        route: &str,
+    ) -> Result<Option<(CacheMetadata, Vec<u8>)>, IncrementalRendererError> {
+        let Some((mut metadata, html)) = self.read(route)? else {
+            self.routes.remove(route);
+            return Ok(None);
+        };
+        if let Some(tracked) = self.routes.get(route) {
+            metadata.tags = tracked.tags.clone();
+        }
+        Ok(Some((metadata, html)))
+    }
+
+    fn put(
+        &mut self,
+        route: &str,
+        metadata: CacheMetadata,
+        html: Vec<u8>,
+    ) -> Result<(), IncrementalRendererError> {
+        use std::io::Write;
+        if self.track_timestamps() {
+            // Remove any older renders of the route so they are not found before the new render
+            let mut old_renders = (self.map_path)(route);
+            old_renders.push("index");
+            _ = std::fs::remove_dir_all(old_renders);
+        }
+        let file_path = self.route_as_path(route, metadata.timestamp);
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let file = std::fs::File::create(file_path)?;
+        let mut file = std::io::BufWriter::new(file);
+        file.write_all(&html)?;
+        self.routes.insert(route.to_string(), metadata);
+        Ok(())
+    }
+
+    fn invalidate(&mut self, route: &str) -> Result<(), IncrementalRendererError> {
+        self.routes.remove(route);
+        if let Some(file_path) = self.find_file(route) {
+            std::fs::remove_file(file_path.full_path)?;
+        }
+        Ok(())
+    }
+
+    fn list(&mut self) -> Result<Vec<(String, CacheMetadata)>, IncrementalRendererError> {
+        Ok(self
+            .routes
+            .iter()
+            .map(|(route, metadata)| (route.clone(), metadata.clone()))
+            .collect())
+    }
+
+    fn clear(&mut self) -> Result<(), IncrementalRendererError> {
+        self.routes.clear();
+        // clear the static directory
+        match std::fs::remove_dir_all(&self.static_dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
 pub(crate) struct ValidCachedPath {
     pub(crate) full_path: PathBuf,
     pub(crate) timestamp: std::time::SystemTime,
@@ -154,12 +190,6 @@ impl ValidCachedPath {
             timestamp,
         })
     }
-
-    pub fn freshness(&self, max_age: Option<std::time::Duration>) -> Option<RenderFreshness> {
-        let age = self.timestamp.elapsed().ok()?.as_secs();
-        let max_age = max_age.map(|max_age| max_age.as_secs());
-        Some(RenderFreshness::new(age, max_age?, self.timestamp.into()))
-    }
 }
 
 fn decode_timestamp(timestamp: &str) -> Option<std::time::SystemTime> {
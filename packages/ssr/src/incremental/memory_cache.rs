@@ -2,37 +2,42 @@
 
 #![allow(non_snake_case)]
 
-use chrono::offset::Utc;
-use chrono::DateTime;
 use rustc_hash::FxHasher;
 use std::{hash::BuildHasherDefault, num::NonZeroUsize};
 
-use super::freshness::RenderFreshness;
+use super::CacheMetadata;
+
+pub(crate) struct MemoryEntry {
+    pub(crate) metadata: CacheMetadata,
+    pub(crate) html: Vec<u8>,
+}
 
 pub(crate) struct InMemoryCache {
-    #[allow(clippy::type_complexity)]
-    lru: Option<lru::LruCache<String, (DateTime<Utc>, Vec<u8>), BuildHasherDefault<FxHasher>>>,
-    invalidate_after: Option<std::time::Duration>,
+    lru: Option<lru::LruCache<String, MemoryEntry, BuildHasherDefault<FxHasher>>>,
 }
 
 impl InMemoryCache {
-    pub fn new(memory_cache_limit: usize, invalidate_after: Option<std::time::Duration>) -> Self {
+    pub fn new(memory_cache_limit: usize) -> Self {
         Self {
             lru: NonZeroUsize::new(memory_cache_limit)
                 .map(|limit| lru::LruCache::with_hasher(limit, Default::default())),
-            invalidate_after,
         }
     }
 
+    /// If the memory cache is disabled, nothing is ever stored in it
+    pub fn enabled(&self) -> bool {
+        self.lru.is_some()
+    }
+
     pub fn clear(&mut self) {
         if let Some(cache) = &mut self.lru {
             cache.clear();
         }
     }
 
-    pub fn put(&mut self, route: String, timestamp: DateTime<Utc>, data: Vec<u8>) {
+    pub fn put(&mut self, route: String, metadata: CacheMetadata, html: Vec<u8>) {
         if let Some(cache) = &mut self.lru {
-            cache.put(route, (timestamp, data));
+            cache.put(route, MemoryEntry { metadata, html });
         }
     }
 
@@ -42,45 +47,26 @@ impl InMemoryCache {
         }
     }
 
-    pub fn try_get_or_insert<'a, F: FnOnce() -> Result<(DateTime<Utc>, Vec<u8>), E>, E>(
-        &'a mut self,
-        route: &str,
-        or_insert: F,
-    ) -> Result<Option<(RenderFreshness, &'a [u8])>, E> {
-        if let Some(memory_cache) = self.lru.as_mut() {
-            let (timestamp, _) = memory_cache.try_get_or_insert(route.to_string(), or_insert)?;
-
-            let now = Utc::now();
-            let elapsed = timestamp.signed_duration_since(now);
-            let age = elapsed.num_seconds();
-            // The cache entry is out of date, so we need to remove it.
-            if let Some(invalidate_after) = self.invalidate_after {
-                if elapsed.to_std().unwrap() > invalidate_after {
-                    tracing::trace!("memory cache out of date");
-                    memory_cache.pop(route);
-                    return Ok(None);
-                }
+    pub fn invalidate_tag(&mut self, tag: &str) {
+        if let Some(cache) = &mut self.lru {
+            let routes: Vec<_> = cache
+                .iter()
+                .filter(|(_, entry)| entry.metadata.tags.iter().any(|t| t == tag))
+                .map(|(route, _)| route.clone())
+                .collect();
+            for route in routes {
+                cache.pop(&route);
             }
-
-            // We need to reborrow because we may have invalidated the lifetime if the route was removed.
-            // We know it wasn't because we returned... but rust doesn't understand that.
-            let (timestamp, cache_hit) = memory_cache.get(route).unwrap();
-
-            return match self.invalidate_after {
-                Some(invalidate_after) => {
-                    tracing::trace!("memory cache hit");
-                    let max_age = invalidate_after.as_secs();
-                    let freshness = RenderFreshness::new(age as u64, max_age, *timestamp);
-                    Ok(Some((freshness, cache_hit)))
-                }
-                None => {
-                    tracing::trace!("memory cache hit");
-                    let freshness = RenderFreshness::new_age(age as u64, *timestamp);
-                    Ok(Some((freshness, cache_hit)))
-                }
-            };
         }
+    }
+
+    pub fn peek(&self, route: &str) -> Option<&MemoryEntry> {
+        self.lru.as_ref()?.peek(route)
+    }
 
-        Ok(None)
+    pub fn get(&mut self, route: &str) -> Option<&MemoryEntry> {
+        let entry = self.lru.as_mut()?.get(route)?;
+        tracing::trace!("memory cache hit {:?}", route);
+        Some(entry)
     }
 }
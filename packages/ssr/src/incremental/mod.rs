@@ -7,12 +7,22 @@ mod freshness;
 #[cfg(not(target_arch = "wasm32"))]
 mod fs_cache;
 mod memory_cache;
+#[cfg(all(feature = "sled", not(target_arch = "wasm32")))]
+mod sled_cache;
+mod storage;
 
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 pub use config::*;
 pub use freshness::*;
+use rustc_hash::FxHashSet;
+#[cfg(all(feature = "sled", not(target_arch = "wasm32")))]
+pub use sled_cache::*;
+pub use storage::*;
 
 use self::memory_cache::InMemoryCache;
 
@@ -26,12 +36,46 @@ pub struct CachedRender<'a> {
     pub response: &'a [u8],
 }
 
+/// A route that is stored in the cache. Returned from [`IncrementalRenderer::list`].
+#[derive(Debug, Clone)]
+pub struct CachedRoute {
+    /// The route that was rendered
+    pub route: String,
+    /// The freshness information for the rendered response
+    pub freshness: RenderFreshness,
+    /// The tags the route was cached with
+    pub tags: Vec<String>,
+}
+
 /// An incremental renderer.
 pub struct IncrementalRenderer {
     pub(crate) memory_cache: InMemoryCache,
-    #[cfg(not(target_arch = "wasm32"))]
-    pub(crate) file_system_cache: fs_cache::FileSystemCache,
+    pub(crate) storage: Option<Box<dyn CacheStorage>>,
     invalidate_after: Option<Duration>,
+    stale_while_revalidate: Option<Duration>,
+    regenerating: Arc<Mutex<FxHashSet<String>>>,
+}
+
+/// A guard returned from [`IncrementalRenderer::start_regeneration`]. The route is marked as regenerating until the
+/// guard is dropped.
+pub struct RegenerationGuard {
+    route: String,
+    regenerating: Arc<Mutex<FxHashSet<String>>>,
+}
+
+impl RegenerationGuard {
+    /// The route that is being regenerated
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+}
+
+impl Drop for RegenerationGuard {
+    fn drop(&mut self) {
+        if let Ok(mut regenerating) = self.regenerating.lock() {
+            regenerating.remove(&self.route);
+        }
+    }
 }
 
 impl IncrementalRenderer {
@@ -43,15 +87,57 @@ impl IncrementalRenderer {
     /// Remove a route from the cache.
     pub fn invalidate(&mut self, route: &str) {
         self.memory_cache.invalidate(route);
-        #[cfg(not(target_arch = "wasm32"))]
-        self.file_system_cache.invalidate(route);
+        if let Some(storage) = &mut self.storage {
+            if let Err(err) = storage.invalidate(route) {
+                tracing::error!("Failed to invalidate route {route:?}: {err}");
+            }
+        }
     }
 
     /// Remove all routes from the cache.
     pub fn invalidate_all(&mut self) {
         self.memory_cache.clear();
-        #[cfg(not(target_arch = "wasm32"))]
-        self.file_system_cache.clear();
+        if let Some(storage) = &mut self.storage {
+            if let Err(err) = storage.clear() {
+                tracing::error!("Failed to clear the incremental cache: {err}");
+            }
+        }
+    }
+
+    /// Remove every route that was cached with a tag and return the routes that were removed from the storage backend.
+    ///
+    /// ```rust
+    /// # use dioxus_ssr::incremental::IncrementalRenderer;
+    /// # let mut renderer = IncrementalRenderer::builder().build();
+    /// renderer.cache_with_tags("/user/42".to_string(), "<div>User 42</div>", ["user:42"]).unwrap();
+    /// renderer.cache_with_tags("/user/42/posts".to_string(), "<div>Posts</div>", ["user:42"]).unwrap();
+    ///
+    /// // The user was updated, so every page that shows the user needs to be rendered again
+    /// renderer.invalidate_tag("user:42").unwrap();
+    /// assert!(renderer.get("/user/42").unwrap().is_none());
+    /// ```
+    pub fn invalidate_tag(&mut self, tag: &str) -> Result<Vec<String>, IncrementalRendererError> {
+        self.memory_cache.invalidate_tag(tag);
+        match &mut self.storage {
+            Some(storage) => storage.invalidate_tag(tag),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// List every route in the storage backend along with the freshness of the render.
+    pub fn list(&mut self) -> Result<Vec<CachedRoute>, IncrementalRendererError> {
+        let Some(storage) = &mut self.storage else {
+            return Ok(Vec::new());
+        };
+        Ok(storage
+            .list()?
+            .into_iter()
+            .map(|(route, metadata)| CachedRoute {
+                route,
+                freshness: self.freshness(metadata.timestamp),
+                tags: metadata.tags,
+            })
+            .collect())
     }
 
     /// Cache a rendered response.
@@ -68,16 +154,25 @@ impl IncrementalRenderer {
         route: String,
         html: impl Into<Vec<u8>>,
     ) -> Result<RenderFreshness, IncrementalRendererError> {
-        let timestamp = Utc::now();
+        self.cache_with_tags(route, html, std::iter::empty::<String>())
+    }
+
+    /// Cache a rendered response with tags that can be used to invalidate it later with [`IncrementalRenderer::invalidate_tag`].
+    pub fn cache_with_tags(
+        &mut self,
+        route: String,
+        html: impl Into<Vec<u8>>,
+        tags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<RenderFreshness, IncrementalRendererError> {
         let html = html.into();
-        #[cfg(not(target_arch = "wasm32"))]
-        self.file_system_cache
-            .put(route.clone(), timestamp, html.clone())?;
-        self.memory_cache.put(route, timestamp, html);
-        Ok(RenderFreshness::created_at(
-            timestamp,
-            self.invalidate_after,
-        ))
+        let metadata =
+            CacheMetadata::now(tags.into_iter().map(Into::into).collect()).with_etag_of(&html);
+        let freshness = self.freshness(metadata.timestamp).with_etag(metadata.etag);
+        if let Some(storage) = &mut self.storage {
+            storage.put(&route, metadata.clone(), html.clone())?;
+        }
+        self.memory_cache.put(route, metadata, html);
+        Ok(freshness)
     }
 
     /// Try to get a cached response for a route.
@@ -93,7 +188,10 @@ impl IncrementalRenderer {
     /// assert_eq!(response.unwrap().response, b"<html><body>Hello world</body></html>");
     /// ```
     ///
-    /// If the route is not cached, `None` is returned.
+    /// If the route is not cached, `None` is returned. If the route is older than `invalidate_after` but still inside the
+    /// [`stale_while_revalidate`](IncrementalRendererConfig::stale_while_revalidate) window, the stale render is
+    /// returned and [`RenderFreshness::is_stale`] is true. The caller should serve the stale render and regenerate the
+    /// route in the background with [`IncrementalRenderer::start_regeneration`].
     ///
     /// ```rust
     /// # use dioxus_ssr::incremental::IncrementalRenderer;
@@ -106,41 +204,129 @@ impl IncrementalRenderer {
         &'a mut self,
         route: &str,
     ) -> Result<Option<CachedRender<'a>>, IncrementalRendererError> {
-        let Self {
-            memory_cache,
-            file_system_cache,
-            ..
-        } = self;
-
-        enum FsGetError {
-            NotPresent,
-            Error(IncrementalRendererError),
+        // Renders are served from memory, so there is nothing to return if the memory cache is disabled
+        if !self.memory_cache.enabled() {
+            return Ok(None);
         }
 
-        // The borrow checker prevents us from simply using a match/if and returning early. Instead we need to use the more complex closure API
-        // non lexical lifetimes will make this possible (it works with polonius)
-        let or_insert = || {
-            // check the file cache
-            #[cfg(not(target_arch = "wasm32"))]
-            return match file_system_cache.get(route) {
-                Ok(Some((freshness, bytes))) => Ok((freshness.timestamp(), bytes)),
-                Ok(None) => Err(FsGetError::NotPresent),
-                Err(e) => Err(FsGetError::Error(e)),
-            };
-
-            #[allow(unreachable_code)]
-            Err(FsGetError::NotPresent)
-        };
+        let in_memory = self
+            .memory_cache
+            .peek(route)
+            .map(|entry| entry.metadata.clone());
+        let mut expired = in_memory
+            .as_ref()
+            .map(|metadata| self.is_expired(metadata.timestamp));
+        if let (Some(false), Some(metadata)) = (expired, &in_memory) {
+            if self.changed_in_storage(route, metadata)? {
+                tracing::trace!("memory cache replaced in shared storage");
+                self.memory_cache.invalidate(route);
+                expired = None;
+            }
+        }
+        match expired {
+            Some(true) => {
+                tracing::trace!("memory cache out of date");
+                self.invalidate(route);
+                return Ok(None);
+            }
+            Some(false) => {}
+            None => {
+                let Some(storage) = &mut self.storage else {
+                    return Ok(None);
+                };
+                let Some((mut metadata, html)) = storage.get(route)? else {
+                    return Ok(None);
+                };
+                if self.is_expired(metadata.timestamp) {
+                    tracing::trace!("storage cache out of date");
+                    self.invalidate(route);
+                    return Ok(None);
+                }
+                if metadata.etag.is_none() {
+                    metadata = metadata.with_etag_of(&html);
+                }
+                self.memory_cache.put(route.to_string(), metadata, html);
+            }
+        }
 
-        match memory_cache.try_get_or_insert(route, or_insert) {
-            Ok(Some((freshness, bytes))) => Ok(Some(CachedRender {
+        let freshness = self.memory_cache.peek(route).map(|entry| {
+            self.freshness(entry.metadata.timestamp)
+                .with_etag(entry.metadata.etag)
+        });
+        Ok(self
+            .memory_cache
+            .get(route)
+            .zip(freshness)
+            .map(|(entry, freshness)| CachedRender {
                 route: route.to_string(),
                 freshness,
-                response: bytes,
-            })),
-            Err(FsGetError::NotPresent) | Ok(None) => Ok(None),
-            Err(FsGetError::Error(e)) => Err(e),
-        }
+                response: &entry.html,
+            }))
+    }
+
+    /// Mark a route as regenerating. Returns `None` if the route is already being regenerated, so concurrent requests
+    /// for a stale route only render it once.
+    ///
+    /// ```rust
+    /// # use dioxus_ssr::incremental::IncrementalRenderer;
+    /// let renderer = IncrementalRenderer::builder().build();
+    /// let guard = renderer.start_regeneration("/index").unwrap();
+    /// assert!(renderer.start_regeneration("/index").is_none());
+    ///
+    /// // Once the new render is cached, drop the guard to allow the route to be regenerated again
+    /// drop(guard);
+    /// assert!(!renderer.is_regenerating("/index"));
+    /// ```
+    pub fn start_regeneration(&self, route: &str) -> Option<RegenerationGuard> {
+        let mut regenerating = self.regenerating.lock().ok()?;
+        regenerating
+            .insert(route.to_string())
+            .then(|| RegenerationGuard {
+                route: route.to_string(),
+                regenerating: self.regenerating.clone(),
+            })
+    }
+
+    /// Check if a route is currently being regenerated
+    pub fn is_regenerating(&self, route: &str) -> bool {
+        self.regenerating
+            .lock()
+            .is_ok_and(|regenerating| regenerating.contains(route))
+    }
+
+    /// Check if another renderer replaced or removed a render in the shared storage since it was loaded into memory
+    fn changed_in_storage(
+        &mut self,
+        route: &str,
+        in_memory: &CacheMetadata,
+    ) -> Result<bool, IncrementalRendererError> {
+        let Some(storage) = self.storage.as_mut().filter(|storage| storage.is_shared()) else {
+            return Ok(false);
+        };
+        let Some(stored) = storage.metadata(route)? else {
+            return Ok(true);
+        };
+        // Backends may only store timestamps to the millisecond and may not store the etag
+        Ok(
+            stored.timestamp.timestamp_millis() != in_memory.timestamp.timestamp_millis()
+                || stored.etag.is_some_and(|etag| in_memory.etag != Some(etag)),
+        )
+    }
+
+    fn freshness(&self, timestamp: DateTime<Utc>) -> RenderFreshness {
+        RenderFreshness::created_at(timestamp, self.invalidate_after)
+            .with_stale_while_revalidate(self.stale_while_revalidate)
+    }
+
+    /// Check if a render is too old to be served, even as a stale render
+    fn is_expired(&self, timestamp: DateTime<Utc>) -> bool {
+        let Some(invalidate_after) = self.invalidate_after else {
+            return false;
+        };
+        let serve_until = invalidate_after + self.stale_while_revalidate.unwrap_or_default();
+        (Utc::now() - timestamp)
+            .to_std()
+            .is_ok_and(|age| age >= serve_until)
     }
 }
 
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use sled::transaction::{
+    ConflictableTransactionError, ConflictableTransactionResult, TransactionError, Transactional,
+    TransactionalTree,
+};
+use std::path::Path;
+
+use super::{CacheMetadata, CacheStorage, IncrementalRendererError};
+
+/// A storage backend that keeps renders in an embedded [sled](https://docs.rs/sled) database.
+///
+/// Unlike the default file system storage, the database stores the timestamp and tags of every render, so tags survive
+/// restarts and [`CacheStorage::invalidate_tag`] does not need to scan every route. Cloning the storage shares the same
+/// database, so renderers check the database before serving a route from memory.
+///
+/// ```rust, no_run
+/// # use dioxus_ssr::incremental::*;
+/// let storage = SledStorage::open("./cache").unwrap();
+/// let renderer = IncrementalRenderer::builder().storage(storage).build();
+/// ```
+#[derive(Clone)]
+pub struct SledStorage {
+    renders: sled::Tree,
+    metadata: sled::Tree,
+    // Keys are `tag\0route` so every route with a tag can be found with a prefix scan
+    tags: sled::Tree,
+}
+
+impl SledStorage {
+    /// Open or create a database at the given path
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IncrementalRendererError> {
+        Self::from_db(&sled::open(path).map_err(other)?)
+    }
+
+    /// Store renders in an existing database
+    pub fn from_db(db: &sled::Db) -> Result<Self, IncrementalRendererError> {
+        Ok(Self {
+            renders: db.open_tree("dioxus_renders").map_err(other)?,
+            metadata: db.open_tree("dioxus_render_metadata").map_err(other)?,
+            tags: db.open_tree("dioxus_render_tags").map_err(other)?,
+        })
+    }
+
+    fn read_metadata(
+        &self,
+        route: &str,
+    ) -> Result<Option<CacheMetadata>, IncrementalRendererError> {
+        let Some(bytes) = self.metadata.get(route).map_err(other)? else {
+            return Ok(None);
+        };
+        decode_metadata(&bytes).map(Some)
+    }
+}
+
+impl CacheStorage for SledStorage {
+    fn get(
+        &mut self,
+        route: &str,
+    ) -> Result<Option<(CacheMetadata, Vec<u8>)>, IncrementalRendererError> {
+        let Some(metadata) = self.read_metadata(route)? else {
+            return Ok(None);
+        };
+        let Some(html) = self.renders.get(route).map_err(other)? else {
+            return Ok(None);
+        };
+        Ok(Some((metadata, html.to_vec())))
+    }
+
+    fn metadata(&mut self, route: &str) -> Result<Option<CacheMetadata>, IncrementalRendererError> {
+        self.read_metadata(route)
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn put(
+        &mut self,
+        route: &str,
+        metadata: CacheMetadata,
+        html: Vec<u8>,
+    ) -> Result<(), IncrementalRendererError> {
+        let encoded = encode_metadata(&metadata)?;
+        // Other processes that share the database never see the render without its metadata or tags
+        (&self.renders, &self.metadata, &self.tags)
+            .transaction(|(renders, stored, tags)| {
+                remove_route(renders, stored, tags, route)?;
+                for tag in &metadata.tags {
+                    tags.insert(tag_key(tag, route), &[])?;
+                }
+                stored.insert(route, encoded.as_slice())?;
+                renders.insert(route, html.as_slice())?;
+                Ok(())
+            })
+            .map_err(transaction_error)
+    }
+
+    fn invalidate(&mut self, route: &str) -> Result<(), IncrementalRendererError> {
+        (&self.renders, &self.metadata, &self.tags)
+            .transaction(|(renders, stored, tags)| remove_route(renders, stored, tags, route))
+            .map_err(transaction_error)
+    }
+
+    fn list(&mut self) -> Result<Vec<(String, CacheMetadata)>, IncrementalRendererError> {
+        self.metadata
+            .iter()
+            .map(|entry| {
+                let (route, metadata) = entry.map_err(other)?;
+                let route = String::from_utf8(route.to_vec()).map_err(other)?;
+                Ok((route, decode_metadata(&metadata)?))
+            })
+            .collect()
+    }
+
+    fn clear(&mut self) -> Result<(), IncrementalRendererError> {
+        self.renders.clear().map_err(other)?;
+        self.metadata.clear().map_err(other)?;
+        self.tags.clear().map_err(other)?;
+        Ok(())
+    }
+
+    fn invalidate_tag(&mut self, tag: &str) -> Result<Vec<String>, IncrementalRendererError> {
+        let prefix = tag_key(tag, "");
+        let mut routes = Vec::new();
+        for key in self.tags.scan_prefix(&prefix).keys() {
+            let key = key.map_err(other)?;
+            let route = String::from_utf8(key[prefix.len()..].to_vec()).map_err(other)?;
+            routes.push(route);
+        }
+        for route in &routes {
+            self.invalidate(route)?;
+        }
+        Ok(routes)
+    }
+}
+
+/// Remove the render of a route along with its metadata and tags inside of a transaction
+fn remove_route(
+    renders: &TransactionalTree,
+    metadata: &TransactionalTree,
+    tags: &TransactionalTree,
+    route: &str,
+) -> ConflictableTransactionResult<(), IncrementalRendererError> {
+    if let Some(bytes) = metadata.remove(route)? {
+        let stored = decode_metadata(&bytes).map_err(ConflictableTransactionError::Abort)?;
+        for tag in &stored.tags {
+            tags.remove(tag_key(tag, route))?;
+        }
+    }
+    renders.remove(route)?;
+    Ok(())
+}
+
+fn tag_key(tag: &str, route: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(tag.len() + route.len() + 1);
+    key.extend_from_slice(tag.as_bytes());
+    key.push(0);
+    key.extend_from_slice(route.as_bytes());
+    key
+}
+
+fn encode_metadata(metadata: &CacheMetadata) -> Result<Vec<u8>, IncrementalRendererError> {
+    let json = serde_json::json!({
+        "timestamp": metadata.timestamp.timestamp_millis(),
+        "tags": metadata.tags,
+        "etag": metadata.etag.map(|etag| format!("{etag:032x}")),
+    });
+    serde_json::to_vec(&json).map_err(other)
+}
+
+fn decode_metadata(bytes: &[u8]) -> Result<CacheMetadata, IncrementalRendererError> {
+    #[derive(serde::Deserialize)]
+    struct Stored {
+        timestamp: i64,
+        tags: Vec<String>,
+        #[serde(default)]
+        etag: Option<String>,
+    }
+    let stored: Stored = serde_json::from_slice(bytes).map_err(other)?;
+    Ok(CacheMetadata {
+        timestamp: DateTime::<Utc>::from_timestamp_millis(stored.timestamp).unwrap_or_default(),
+        tags: stored.tags,
+        etag: stored
+            .etag
+            .and_then(|etag| u128::from_str_radix(&etag, 16).ok()),
+    })
+}
+
+fn transaction_error(err: TransactionError<IncrementalRendererError>) -> IncrementalRendererError {
+    match err {
+        TransactionError::Abort(err) => err,
+        TransactionError::Storage(err) => other(err),
+    }
+}
+
+fn other(err: impl std::error::Error + Send + Sync + 'static) -> IncrementalRendererError {
+    IncrementalRendererError::Other(Box::new(err))
+}
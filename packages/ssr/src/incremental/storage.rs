@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+
+use super::IncrementalRendererError;
+
+/// Information stored alongside a cached render
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheMetadata {
+    /// The time the route was rendered
+    pub timestamp: DateTime<Utc>,
+    /// The tags the render was cached with. Every route with a tag can be removed at once with
+    /// [`IncrementalRenderer::invalidate_tag`](super::IncrementalRenderer::invalidate_tag)
+    pub tags: Vec<String>,
+    /// A hash of the html that is sent as the ETag of the render. The hash is computed once when the route is cached.
+    /// Backends that don't store it can return `None`, and the hash is computed again when the render is loaded.
+    pub etag: Option<u128>,
+}
+
+impl CacheMetadata {
+    /// Create metadata for a render that happened now
+    pub fn now(tags: Vec<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            tags,
+            etag: None,
+        }
+    }
+
+    /// Set the ETag of the render to a hash of its html
+    pub fn with_etag_of(mut self, html: &[u8]) -> Self {
+        self.etag = Some(super::freshness::hash_html(html));
+        self
+    }
+}
+
+/// A storage backend for the [`IncrementalRenderer`](super::IncrementalRenderer).
+///
+/// The incremental renderer keeps recently used routes in an in memory LRU cache and falls back to the storage backend
+/// when a route is not in memory. By default, renders are stored in the file system. If you run multiple server
+/// replicas, you can implement this trait for a shared store so every replica can reuse the same renders.
+///
+/// Expiring renders is handled by the incremental renderer, so backends only need to store the timestamp of each render.
+///
+/// If other renderers can write to the same backend, return `true` from [`CacheStorage::is_shared`]. The renderer then
+/// checks the backend every time it serves a route from memory, so a route another replica invalidated or replaced is
+/// not served from the stale memory cache.
+pub trait CacheStorage: Send + Sync {
+    /// Get a cached render for a route
+    fn get(
+        &mut self,
+        route: &str,
+    ) -> Result<Option<(CacheMetadata, Vec<u8>)>, IncrementalRendererError>;
+
+    /// Store a render for a route, replacing any existing render
+    fn put(
+        &mut self,
+        route: &str,
+        metadata: CacheMetadata,
+        html: Vec<u8>,
+    ) -> Result<(), IncrementalRendererError>;
+
+    /// Get the metadata of the render for a route without the html. Shared backends should override this with a
+    /// cheaper lookup because it is called every time a route is served from memory.
+    fn metadata(&mut self, route: &str) -> Result<Option<CacheMetadata>, IncrementalRendererError> {
+        Ok(self.get(route)?.map(|(metadata, _)| metadata))
+    }
+
+    /// Check if other renderers can change the renders in this backend. Returns `false` by default.
+    fn is_shared(&self) -> bool {
+        false
+    }
+
+    /// Remove the render for a route
+    fn invalidate(&mut self, route: &str) -> Result<(), IncrementalRendererError>;
+
+    /// List every route that is stored along with its metadata
+    fn list(&mut self) -> Result<Vec<(String, CacheMetadata)>, IncrementalRendererError>;
+
+    /// Remove every render
+    fn clear(&mut self) -> Result<(), IncrementalRendererError>;
+
+    /// Remove every render that was cached with the tag and return the routes that were removed
+    ///
+    /// The default implementation scans every route with [`CacheStorage::list`]. Backends that can index tags should
+    /// override this.
+    fn invalidate_tag(&mut self, tag: &str) -> Result<Vec<String>, IncrementalRendererError> {
+        let mut removed = Vec::new();
+        for (route, metadata) in self.list()? {
+            if metadata.tags.iter().any(|t| t == tag) {
+                self.invalidate(&route)?;
+                removed.push(route);
+            }
+        }
+        Ok(removed)
+    }
+}
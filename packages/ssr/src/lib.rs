@@ -7,6 +7,8 @@ pub mod config;
 #[cfg(feature = "incremental")]
 pub mod incremental;
 pub mod renderer;
+#[cfg(feature = "streaming")]
+pub mod streaming;
 pub mod template;
 
 use dioxus_core::{Element, VirtualDom};
@@ -17,7 +17,7 @@ pub struct Renderer {
     pub pre_render: bool,
 
     /// A callback used to render components. You can set this callback to control what components are rendered and add wrappers around components that are not present in CSR
-    render_components: Option<ComponentRenderCallback>,
+    pub(crate) render_components: Option<ComponentRenderCallback>,
 
     /// A cache of templates that have been rendered
     template_cache: FxHashMap<usize, Arc<StringCache>>,
@@ -0,0 +1,152 @@
+//! Stream server side rendered html out of order as suspense boundaries resolve.
+//!
+//! The streaming renderer writes the page in chunks:
+//! ```html
+//! <!-- The shell is written immediately. Suspended boundaries render their fallback between two comments -->
+//! <div>
+//!     Header
+//!     <!--ds-0--><div>Loading user info...</div><!--/ds-0-->
+//!     Footer
+//! </div>
+//! <!-- Once the suspense boundary resolves, the content is written in a hidden div and swapped into place -->
+//! <div hidden id="ds-0"><div>Final HTML</div></div>
+//! <script>dxSwap(0)</script>
+//! ```
+//!
+//! Unlike the fullstack renderer, this does not require the wasm bundle to swap the resolved content into the page, so
+//! it works with any server that can write to an [`AsyncWrite`].
+
+use crate::Renderer;
+use dioxus_core::prelude::*;
+use rustc_hash::FxHashMap;
+use std::{
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// The script that swaps resolved suspense boundaries into the placeholder comments
+const SWAP_SCRIPT: &str = r#"<script>function dxSwap(i){var t=document.getElementById("ds-"+i),w=document.createTreeWalker(document,128),n,s,e;while(n=w.nextNode()){if(n.data=="ds-"+i)s=n;else if(n.data=="/ds-"+i){e=n;break}}if(!t||!s||!e)return;while(s.nextSibling!==e)s.nextSibling.remove();while(t.firstChild)e.before(t.firstChild);s.remove();e.remove();t.remove()}</script>"#;
+
+#[derive(Default)]
+struct PendingBoundaries {
+    next_id: usize,
+    mounts: FxHashMap<ScopeId, usize>,
+}
+
+impl Renderer {
+    /// Render the VirtualDom into an [`AsyncWrite`], writing the shell immediately and streaming the content of each
+    /// [`SuspenseBoundary`] as it resolves.
+    ///
+    /// The VirtualDom must already be built with [`VirtualDom::rebuild_in_place`]. Each chunk is flushed as soon as it
+    /// is written. The resolved chunks are swapped into place with a small inline script, so they should be written
+    /// inside the `<body>` of the page. Any [component render callback](Renderer::set_render_components) is
+    /// replaced while streaming and restored afterwards.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// # async fn serve(mut response: impl tokio::io::AsyncWrite + Unpin) -> std::io::Result<()> {
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.rebuild_in_place();
+    ///
+    /// response.write_all(b"<!DOCTYPE html><html><body>").await?;
+    /// dioxus_ssr::Renderer::new()
+    ///     .render_streaming(&mut dom, &mut response)
+    ///     .await?;
+    /// response.write_all(b"</body></html>").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn render_streaming<W: AsyncWrite + Unpin + ?Sized>(
+        &mut self,
+        dom: &mut VirtualDom,
+        to: &mut W,
+    ) -> std::io::Result<()> {
+        let pending = Arc::new(Mutex::new(PendingBoundaries::default()));
+        let old_render_components = self.render_components.take();
+
+        {
+            let pending = pending.clone();
+            self.set_render_components(move |renderer, to, dom, scope| {
+                let suspended =
+                    SuspenseContext::downcast_suspense_boundary_from_scope(&dom.runtime(), scope)
+                        .filter(|suspense| suspense.has_suspended_tasks())
+                        .is_some();
+                if !suspended {
+                    return renderer.render_scope(to, dom, scope);
+                }
+
+                let id = {
+                    let mut pending = pending.lock().unwrap();
+                    let id = pending.next_id;
+                    pending.next_id += 1;
+                    pending.mounts.insert(scope, id);
+                    id
+                };
+                write!(to, "<!--ds-{id}-->")?;
+                renderer.render_scope(to, dom, scope)?;
+                write!(to, "<!--/ds-{id}-->")
+            });
+        }
+
+        let result = self.stream_chunks(dom, to, &pending).await;
+        self.render_components = old_render_components;
+        result
+    }
+
+    async fn stream_chunks<W: AsyncWrite + Unpin + ?Sized>(
+        &mut self,
+        dom: &mut VirtualDom,
+        to: &mut W,
+        pending: &Mutex<PendingBoundaries>,
+    ) -> std::io::Result<()> {
+        let mut chunk = String::new();
+        self.render_to(&mut chunk, dom)
+            .map_err(std::io::Error::other)?;
+        if !pending.lock().unwrap().mounts.is_empty() {
+            chunk.push_str(SWAP_SCRIPT);
+        }
+        write_chunk(to, &chunk).await?;
+
+        while dom.suspended_tasks_remaining() {
+            dom.wait_for_suspense_work().await;
+            let resolved = dom.render_suspense_immediate().await;
+
+            for scope in resolved {
+                // If the boundary was removed or resolved before its placeholder was ever written, there is nothing to swap
+                let Some(id) = pending.lock().unwrap().mounts.remove(&scope) else {
+                    continue;
+                };
+
+                let mut chunk = String::new();
+                write!(chunk, r#"<div hidden id="ds-{id}">"#).map_err(std::io::Error::other)?;
+                self.render_scope(&mut chunk, dom, scope)
+                    .map_err(std::io::Error::other)?;
+                write!(chunk, "</div><script>dxSwap({id})</script>")
+                    .map_err(std::io::Error::other)?;
+                write_chunk(to, &chunk).await?;
+
+                // The content was already sent, so freeze the boundary to prevent future reruns of its children
+                if let Some(suspense) =
+                    SuspenseContext::downcast_suspense_boundary_from_scope(&dom.runtime(), scope)
+                {
+                    suspense.freeze();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn write_chunk<W: AsyncWrite + Unpin + ?Sized>(
+    to: &mut W,
+    chunk: &str,
+) -> std::io::Result<()> {
+    to.write_all(chunk.as_bytes()).await?;
+    to.flush().await
+}
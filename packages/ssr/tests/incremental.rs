@@ -0,0 +1,287 @@
+#![cfg(feature = "incremental")]
+
+use dioxus_ssr::incremental::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type Renders = HashMap<String, (CacheMetadata, Vec<u8>)>;
+
+/// A storage backend that shares renders between every renderer it is cloned into
+#[derive(Clone, Default)]
+struct SharedStorage {
+    renders: Arc<Mutex<Renders>>,
+}
+
+impl CacheStorage for SharedStorage {
+    fn get(
+        &mut self,
+        route: &str,
+    ) -> Result<Option<(CacheMetadata, Vec<u8>)>, IncrementalRendererError> {
+        Ok(self.renders.lock().unwrap().get(route).cloned())
+    }
+
+    fn put(
+        &mut self,
+        route: &str,
+        metadata: CacheMetadata,
+        html: Vec<u8>,
+    ) -> Result<(), IncrementalRendererError> {
+        self.renders
+            .lock()
+            .unwrap()
+            .insert(route.to_string(), (metadata, html));
+        Ok(())
+    }
+
+    fn invalidate(&mut self, route: &str) -> Result<(), IncrementalRendererError> {
+        self.renders.lock().unwrap().remove(route);
+        Ok(())
+    }
+
+    fn list(&mut self) -> Result<Vec<(String, CacheMetadata)>, IncrementalRendererError> {
+        Ok(self
+            .renders
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(route, (metadata, _))| (route.clone(), metadata.clone()))
+            .collect())
+    }
+
+    fn clear(&mut self) -> Result<(), IncrementalRendererError> {
+        self.renders.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn custom_storage_is_shared_between_renderers() {
+    let storage = SharedStorage::default();
+    let config = IncrementalRenderer::builder()
+        .storage(storage.clone())
+        .clear_cache(false);
+
+    let mut first = config.clone().build();
+    let mut second = config.build();
+
+    first.cache("/index".to_string(), "hello").unwrap();
+    assert!(storage.renders.lock().unwrap().contains_key("/index"));
+
+    // The second renderer has never seen the route, so it is loaded from the shared storage
+    let cached = second.get("/index").unwrap().unwrap();
+    assert_eq!(cached.response, b"hello");
+
+    // Renders that change in the shared storage are not served from memory
+    first.cache("/index".to_string(), "hello again").unwrap();
+    let cached = second.get("/index").unwrap().unwrap();
+    assert_eq!(cached.response, b"hello again");
+
+    first.invalidate("/index");
+    assert!(second.get("/index").unwrap().is_none());
+}
+
+#[test]
+fn invalidate_tag_removes_every_tagged_route() {
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(SharedStorage::default())
+        .build();
+
+    renderer
+        .cache_with_tags("/user/42".to_string(), "user", ["user:42"])
+        .unwrap();
+    renderer
+        .cache_with_tags("/user/42/posts".to_string(), "posts", ["user:42", "posts"])
+        .unwrap();
+    renderer.cache("/about".to_string(), "about").unwrap();
+
+    let mut removed = renderer.invalidate_tag("user:42").unwrap();
+    removed.sort();
+    assert_eq!(removed, ["/user/42", "/user/42/posts"]);
+
+    assert!(renderer.get("/user/42").unwrap().is_none());
+    assert!(renderer.get("/user/42/posts").unwrap().is_none());
+    assert!(renderer.get("/about").unwrap().is_some());
+
+    let routes = renderer.list().unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].route, "/about");
+    assert!(routes[0].tags.is_empty());
+}
+
+#[test]
+fn expired_renders_are_removed_from_storage() {
+    let storage = SharedStorage::default();
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(storage.clone())
+        .invalidate_after(Duration::from_secs(60))
+        .build();
+
+    let mut metadata = CacheMetadata::now(Vec::new());
+    metadata.timestamp -= chrono::Duration::seconds(120);
+    storage
+        .clone()
+        .put("/old", metadata, b"old".to_vec())
+        .unwrap();
+
+    assert!(renderer.get("/old").unwrap().is_none());
+    assert!(storage.renders.lock().unwrap().is_empty());
+}
+
+#[test]
+fn file_system_storage_tracks_tags() {
+    let dir = std::env::temp_dir().join("dioxus-ssr-incremental-tags");
+    let mut renderer = IncrementalRenderer::builder().static_dir(&dir).build();
+
+    renderer
+        .cache_with_tags("/post/1".to_string(), "post", ["post:1"])
+        .unwrap();
+    assert!(dir.join("post/1/index.html").exists());
+
+    assert_eq!(renderer.invalidate_tag("post:1").unwrap(), ["/post/1"]);
+    assert!(!dir.join("post/1/index.html").exists());
+    renderer.invalidate_all();
+}
+
+#[cfg(feature = "sled")]
+#[test]
+fn sled_storage_persists_tags() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let storage = SledStorage::from_db(&db).unwrap();
+
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(storage.clone())
+        .build();
+    renderer
+        .cache_with_tags("/user/1".to_string(), "user", ["user:1"])
+        .unwrap();
+    let about = renderer.cache("/about".to_string(), "about").unwrap();
+
+    // A new renderer without anything in memory can still invalidate by tag
+    let mut restarted = IncrementalRenderer::builder()
+        .storage(storage)
+        .clear_cache(false)
+        .build();
+    assert_eq!(restarted.list().unwrap().len(), 2);
+    assert_eq!(restarted.invalidate_tag("user:1").unwrap(), ["/user/1"]);
+    assert!(restarted.get("/user/1").unwrap().is_none());
+    let cached = restarted.get("/about").unwrap().unwrap();
+    assert_eq!(cached.response, b"about");
+    assert_eq!(cached.freshness.etag(), about.etag());
+
+    // The first renderer still has the route in memory, but the database no longer does
+    assert!(renderer.get("/user/1").unwrap().is_none());
+}
+
+#[test]
+fn stale_renders_are_served_while_revalidating() {
+    let storage = SharedStorage::default();
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(storage.clone())
+        .invalidate_after(Duration::from_secs(60))
+        .stale_while_revalidate(Duration::from_secs(60))
+        .build();
+
+    let mut stale = CacheMetadata::now(Vec::new());
+    stale.timestamp -= chrono::Duration::seconds(90);
+    let mut expired = CacheMetadata::now(Vec::new());
+    expired.timestamp -= chrono::Duration::seconds(150);
+    let mut storage_handle = storage.clone();
+    storage_handle
+        .put("/stale", stale, b"stale".to_vec())
+        .unwrap();
+    storage_handle
+        .put("/expired", expired, b"expired".to_vec())
+        .unwrap();
+
+    let cached = renderer.get("/stale").unwrap().unwrap();
+    assert_eq!(cached.response, b"stale");
+    assert!(cached.freshness.is_stale());
+    assert!(renderer.get("/expired").unwrap().is_none());
+
+    // Only the first request regenerates the route
+    let guard = renderer.start_regeneration("/stale").unwrap();
+    assert!(renderer.start_regeneration("/stale").is_none());
+    renderer.cache("/stale".to_string(), "fresh").unwrap();
+    drop(guard);
+    assert!(!renderer.is_regenerating("/stale"));
+
+    let cached = renderer.get("/stale").unwrap().unwrap();
+    assert_eq!(cached.response, b"fresh");
+    assert!(!cached.freshness.is_stale());
+}
+
+#[test]
+fn freshness_writes_cache_headers() {
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(SharedStorage::default())
+        .invalidate_after(Duration::from_secs(60))
+        .stale_while_revalidate(Duration::from_secs(30))
+        .build();
+    let freshness = renderer.cache("/index".to_string(), "hello").unwrap();
+
+    let mut headers = http::HeaderMap::new();
+    freshness.write(&mut headers);
+    assert_eq!(
+        headers[http::header::CACHE_CONTROL],
+        "max-age=60, stale-while-revalidate=30"
+    );
+    assert!(headers.contains_key(http::header::LAST_MODIFIED));
+
+    // The ETag only changes when the html changes
+    let etag = headers[http::header::ETAG].clone();
+    let cached = renderer.get("/index").unwrap().unwrap();
+    assert_eq!(cached.freshness.etag().unwrap(), etag);
+    let changed = renderer.cache("/index".to_string(), "changed").unwrap();
+    assert_ne!(changed.etag().unwrap(), etag);
+
+    // Every replica must send the same ETag for the same html, so it is a stable hash of the html
+    assert_eq!(etag, "\"2cf24dba5fb0a30e26e83b2ac5b9e29e\"");
+
+    // Renders that are not cached may be different on the next request, so they can't be validated
+    let mut headers = http::HeaderMap::new();
+    RenderFreshness::now(None).write(&mut headers);
+    assert!(!headers.contains_key(http::header::ETAG));
+    assert!(!headers.contains_key(http::header::LAST_MODIFIED));
+}
+
+#[test]
+fn conditional_requests_match_cached_renders() {
+    let mut renderer = IncrementalRenderer::builder()
+        .storage(SharedStorage::default())
+        .build();
+    let freshness = renderer.cache("/index".to_string(), "hello").unwrap();
+    let etag = freshness.etag().unwrap();
+
+    let conditional = |name, value: &str| {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    };
+
+    assert!(freshness.is_not_modified(&conditional(http::header::IF_NONE_MATCH, &etag)));
+    assert!(freshness.is_not_modified(&conditional(
+        http::header::IF_NONE_MATCH,
+        &format!("\"other\", W/{etag}")
+    )));
+    assert!(!freshness.is_not_modified(&conditional(http::header::IF_NONE_MATCH, "\"other\"")));
+
+    assert!(freshness.is_not_modified(&conditional(
+        http::header::IF_MODIFIED_SINCE,
+        &freshness.last_modified()
+    )));
+    assert!(!freshness.is_not_modified(&conditional(
+        http::header::IF_MODIFIED_SINCE,
+        "Sun, 06 Nov 1994 08:49:37 GMT"
+    )));
+
+    // Renders that are not cached never match, because the next render may be different
+    let uncached = RenderFreshness::now(None);
+    assert!(!uncached.is_not_modified(&conditional(http::header::IF_NONE_MATCH, "*")));
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "streaming")]
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::AsyncWrite;
+
+/// A writer that records every flushed chunk
+#[derive(Default)]
+struct Chunks {
+    current: Vec<u8>,
+    flushed: Vec<String>,
+}
+
+impl AsyncWrite for Chunks {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.current.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let chunk = String::from_utf8(std::mem::take(&mut self.current)).unwrap();
+        self.flushed.push(chunk);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[component]
+fn Slow(millis: u64, children: Element) -> Element {
+    let message = use_resource(move || async move {
+        tokio::time::sleep(Duration::from_millis(millis)).await;
+        format!("waited {millis}")
+    })
+    .suspend()?;
+
+    rsx! {
+        div { "{message}" }
+        {children}
+    }
+}
+
+#[tokio::test]
+async fn streams_resolved_boundaries_out_of_order() {
+    fn app() -> Element {
+        rsx! {
+            "header"
+            SuspenseBoundary { fallback: |_| rsx! { "loading slow" },
+                Slow { millis: 50 }
+            }
+            SuspenseBoundary { fallback: |_| rsx! { "loading fast" },
+                Slow { millis: 10 }
+            }
+            "footer"
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+
+    let mut chunks = Chunks::default();
+    dioxus_ssr::Renderer::new()
+        .render_streaming(&mut dom, &mut chunks)
+        .await
+        .unwrap();
+
+    let chunks = chunks.flushed;
+    assert_eq!(chunks.len(), 3);
+
+    // The shell contains the fallbacks wrapped in placeholder comments and the swap script
+    assert!(chunks[0].starts_with(
+        "header<!--ds-0-->loading slow<!--/ds-0--><!--ds-1-->loading fast<!--/ds-1-->footer<script>"
+    ));
+
+    // The fast boundary resolves first
+    assert_eq!(
+        chunks[1],
+        r#"<div hidden id="ds-1"><div>waited 10</div></div><script>dxSwap(1)</script>"#
+    );
+    assert_eq!(
+        chunks[2],
+        r#"<div hidden id="ds-0"><div>waited 50</div></div><script>dxSwap(0)</script>"#
+    );
+}
+
+#[tokio::test]
+async fn nested_boundaries_get_new_placeholders() {
+    fn app() -> Element {
+        rsx! {
+            SuspenseBoundary { fallback: |_| rsx! { "loading outer" },
+                Slow { millis: 10,
+                    SuspenseBoundary { fallback: |_| rsx! { "loading inner" },
+                        Slow { millis: 50 }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+
+    let mut chunks = Chunks::default();
+    dioxus_ssr::Renderer::new()
+        .render_streaming(&mut dom, &mut chunks)
+        .await
+        .unwrap();
+
+    let chunks = chunks.flushed;
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks[0].starts_with("<!--ds-0-->loading outer<!--/ds-0--><script>"));
+    assert_eq!(
+        chunks[1],
+        r#"<div hidden id="ds-0"><div>waited 10</div><!--ds-1-->loading inner<!--/ds-1--></div><script>dxSwap(0)</script>"#
+    );
+    assert_eq!(
+        chunks[2],
+        r#"<div hidden id="ds-1"><div>waited 50</div></div><script>dxSwap(1)</script>"#
+    );
+}
+
+#[tokio::test]
+async fn without_suspense_renders_a_single_chunk() {
+    fn app() -> Element {
+        rsx! { div { "hello" } }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+
+    let mut chunks = Chunks::default();
+    dioxus_ssr::Renderer::new()
+        .render_streaming(&mut dom, &mut chunks)
+        .await
+        .unwrap();
+
+    assert_eq!(chunks.flushed, ["<div>hello</div>"]);
+}
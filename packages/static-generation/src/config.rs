@@ -1,5 +1,6 @@
 //! Launch helper macros for fullstack apps
 
+use dioxus_router::sitemap::SitemapEntry;
 use std::path::PathBuf;
 
 /// Settings for a statically generated site that may be hydrated in the browser
@@ -26,6 +27,14 @@ pub struct Config {
     #[cfg(feature = "server")]
     pub(crate) github_pages: bool,
 
+    #[cfg(feature = "server")]
+    pub(crate) sitemap_base_url: Option<String>,
+
+    #[cfg(feature = "server")]
+    #[allow(clippy::type_complexity)]
+    pub(crate) sitemap_entry:
+        Option<Box<dyn Fn(SitemapEntry) -> SitemapEntry + Send + Sync + 'static>>,
+
     #[cfg(feature = "web")]
     #[allow(unused)]
     pub(crate) web_cfg: dioxus_web::Config,
@@ -49,6 +58,10 @@ impl Default for Config {
             additional_routes: vec!["/".to_string()],
             #[cfg(feature = "server")]
             github_pages: false,
+            #[cfg(feature = "server")]
+            sitemap_base_url: None,
+            #[cfg(feature = "server")]
+            sitemap_entry: None,
             #[cfg(feature = "web")]
             web_cfg: dioxus_web::Config::default(),
         }
@@ -132,6 +145,51 @@ impl Config {
         self
     }
 
+    /// Write a `sitemap.xml` file with every pre-rendered route to the output directory. The urls in the sitemap are
+    /// relative to `base_url` (e.g. `https://example.com`). Entries have no metadata unless you add it with
+    /// [`Config::sitemap_entry`].
+    ///
+    /// Routes with dynamic segments are only pre-rendered if the router can enumerate them with
+    /// `#[route("/:dynamic", enumerate = function)]`.
+    ///
+    /// This method will only effect static site generation.
+    #[allow(unused)]
+    pub fn sitemap(mut self, base_url: impl Into<String>) -> Self {
+        #[cfg(feature = "server")]
+        {
+            self.sitemap_base_url = Some(base_url.into());
+        }
+        self
+    }
+
+    /// Add metadata like the last modified date, change frequency or priority to the sitemap entry of each route. The
+    /// function receives an entry for the route without any metadata and returns the entry to write to the sitemap.
+    ///
+    /// ```rust
+    /// # use dioxus_static_site_generation::Config;
+    /// use dioxus_router::sitemap::ChangeFrequency;
+    ///
+    /// let config = Config::new()
+    ///     .sitemap("https://example.com")
+    ///     .sitemap_entry(|entry| match entry.route() {
+    ///         "/" => entry.priority(1.0).change_frequency(ChangeFrequency::Daily),
+    ///         _ => entry.last_modified("2024-06-01"),
+    ///     });
+    /// ```
+    ///
+    /// This method will only effect static site generation.
+    #[allow(unused)]
+    pub fn sitemap_entry<F: Fn(SitemapEntry) -> SitemapEntry + Send + Sync + 'static>(
+        mut self,
+        sitemap_entry: F,
+    ) -> Self {
+        #[cfg(feature = "server")]
+        {
+            self.sitemap_entry = Some(Box::new(sitemap_entry));
+        }
+        self
+    }
+
     /// A preset for github pages. This will output your files in the `/docs` directory and set up a `404.html` file.
     pub fn github_pages(self) -> Self {
         #[allow(unused_mut)]
@@ -1,5 +1,5 @@
 use dioxus_lib::prelude::*;
-use dioxus_router::prelude::*;
+use dioxus_router::sitemap::{Sitemap, SitemapEntry};
 use dioxus_ssr::incremental::*;
 use dioxus_ssr::renderer;
 use std::collections::HashSet;
@@ -16,14 +16,14 @@ fn server_context_for_route(route: &str) -> dioxus_fullstack::prelude::DioxusSer
     DioxusServerContext::new(parts)
 }
 
-/// Try to extract the site map by finding the root router that a component renders.
-fn extract_site_map(app: fn() -> Element) -> Option<&'static [SiteMapSegment]> {
+/// Try to extract every route the app can render by finding the root router that a component renders.
+fn extract_routes(app: fn() -> Element) -> Option<Vec<String>> {
     let mut vdom = VirtualDom::new(app);
 
     vdom.rebuild_in_place();
 
     vdom.in_runtime(|| {
-        ScopeId::ROOT.in_runtime(|| dioxus_router::prelude::root_router().map(|r| r.site_map()))
+        ScopeId::ROOT.in_runtime(|| dioxus_router::prelude::root_router().map(|r| r.all_routes()))
     })
 }
 
@@ -41,27 +41,30 @@ pub async fn generate_static_site(
     let mut cache = config.create_cache();
 
     let mut routes_to_render: HashSet<String> = config.additional_routes.iter().cloned().collect();
-    if let Some(site_map) = block_in_place(|| extract_site_map(app)) {
-        let flat_site_map = site_map.iter().flat_map(SiteMapSegment::flatten);
-        for route in flat_site_map {
-            let Some(static_route) = route
-                .iter()
-                .filter(|s| s.to_child().is_none())
-                .map(SegmentType::to_static)
-                .collect::<Option<Vec<_>>>()
-            else {
-                continue;
-            };
-            let url = format!("/{}", static_route.join("/"));
-
-            routes_to_render.insert(url);
-        }
+    if let Some(routes) = block_in_place(|| extract_routes(app)) {
+        routes_to_render.extend(routes);
     } else {
-        tracing::trace!("No site map found, rendering the additional routes");
+        tracing::trace!("No router found, rendering the additional routes");
     }
 
+    let mut sitemap_entries = Vec::new();
     for url in routes_to_render {
-        prerender_route(app, url, &mut renderer, &mut cache, &config).await?;
+        prerender_route(app, url.clone(), &mut renderer, &mut cache, &config).await?;
+        // The github pages 404 page is not a real page on the site
+        if config.github_pages && url == "/404" {
+            continue;
+        }
+        let entry = SitemapEntry::new(url);
+        sitemap_entries.push(match &config.sitemap_entry {
+            Some(sitemap_entry) => sitemap_entry(entry),
+            None => entry,
+        });
+    }
+
+    if let Some(base_url) = &config.sitemap_base_url {
+        sitemap_entries.sort_by(|a, b| a.route().cmp(b.route()));
+        let sitemap = Sitemap::new(base_url.clone()).with_entries(sitemap_entries);
+        std::fs::write(config.output_dir.join("sitemap.xml"), sitemap.to_string())?;
     }
 
     // Copy over the web output dir into the static output dir
@@ -134,8 +137,9 @@ async fn prerender_route(
 }
 
 #[test]
-fn extract_site_map_works() {
+fn extract_routes_works() {
     use dioxus::prelude::*;
+    use dioxus_router::prelude::*;
 
     #[derive(Clone, Routable, Debug, PartialEq)]
     enum Route {
@@ -167,6 +171,6 @@ fn extract_site_map_works() {
         }
     }
 
-    let site_map = extract_site_map(app);
-    assert_eq!(site_map, Some(Route::SITE_MAP));
+    let routes = extract_routes(app);
+    assert_eq!(routes, Some(vec!["/".to_string(), "/about".to_string()]));
 }
@@ -43,6 +43,14 @@ pub use document::WebDocument;
 #[cfg(all(feature = "hot_reload", debug_assertions))]
 mod hot_reload;
 
+#[cfg(feature = "persistent")]
+mod storage;
+#[cfg(feature = "persistent")]
+pub use storage::WebStorage;
+
+#[cfg(feature = "query")]
+mod query;
+
 mod hydration;
 #[allow(unused)]
 pub use hydration::*;
@@ -65,6 +73,12 @@ pub async fn run(virtual_dom: VirtualDom, web_config: Config) -> ! {
     #[cfg(feature = "document")]
     dom.in_runtime(document::init_document);
 
+    #[cfg(feature = "persistent")]
+    dom.in_runtime(|| storage::init_storage(web_config.hydrate));
+
+    #[cfg(feature = "query")]
+    dom.in_runtime(query::init_query_events);
+
     #[cfg(feature = "panic_hook")]
     if web_config.default_panic_hook {
         console_error_panic_hook::set_once();
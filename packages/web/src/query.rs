@@ -0,0 +1,32 @@
+use std::rc::Rc;
+
+use dioxus_core::prelude::{Runtime, RuntimeGuard, ScopeId};
+use dioxus_hooks::QueryClient;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::Window;
+
+/// Refetch stale queries when the window is focused or the network reconnects
+pub(crate) fn init_query_events() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let Ok(runtime) = Runtime::current() else {
+        return;
+    };
+
+    listen(&window, &runtime, "focus", QueryClient::window_focused);
+    listen(&window, &runtime, "online", QueryClient::reconnected);
+}
+
+fn listen(window: &Window, runtime: &Rc<Runtime>, event: &str, refetch: fn(&QueryClient)) {
+    let runtime = runtime.clone();
+    let listener = Closure::<dyn FnMut()>::new(move || {
+        let _guard = RuntimeGuard::new(runtime.clone());
+        // The client is created the first time a component uses a query
+        if let Some(client) = ScopeId::ROOT.has_context::<QueryClient>() {
+            refetch(&client);
+        }
+    });
+    _ = window.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+    listener.forget();
+}
@@ -0,0 +1,99 @@
+use std::rc::Rc;
+
+use dioxus_core::ScopeId;
+use dioxus_signals::{PersistentStorage, StorageSubscription};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Storage, StorageEvent};
+
+/// Provides the `localStorage` storage for persistent signals if no storage was provided
+pub fn init_storage(hydrate: bool) {
+    if ScopeId::ROOT
+        .has_context::<Rc<dyn PersistentStorage>>()
+        .is_none()
+    {
+        if let Some(storage) = WebStorage::local() {
+            let provider: Rc<dyn PersistentStorage> = Rc::new(storage.hydrating(hydrate));
+            ScopeId::ROOT.provide_context(provider);
+        }
+    }
+}
+
+/// The web-target's storage for persistent signals. Values are saved in `localStorage` or `sessionStorage`, and
+/// signals are updated when another tab changes the same key.
+pub struct WebStorage {
+    storage: Storage,
+    hydrating: bool,
+}
+
+impl WebStorage {
+    /// Save values in `localStorage`. They are kept until the user clears the site data.
+    pub fn local() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Some(Self {
+            storage,
+            hydrating: false,
+        })
+    }
+
+    /// Save values in `sessionStorage`. They are kept until the tab is closed.
+    pub fn session() -> Option<Self> {
+        let storage = web_sys::window()?.session_storage().ok()??;
+        Some(Self {
+            storage,
+            hydrating: false,
+        })
+    }
+
+    /// Set if the app is hydrating html rendered on the server. While hydrating, signals only load their saved
+    /// value after the first render so the first render matches the server.
+    pub fn hydrating(mut self, hydrating: bool) -> Self {
+        self.hydrating = hydrating;
+        self
+    }
+}
+
+impl PersistentStorage for WebStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.storage.get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: String) {
+        if let Err(err) = self.storage.set_item(key, &value) {
+            tracing::error!("Failed to save {key:?} to web storage: {err:?}");
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        _ = self.storage.remove_item(key);
+    }
+
+    fn subscribe(&self, key: &str, on_change: Box<dyn Fn(Option<String>)>) -> StorageSubscription {
+        let Some(window) = web_sys::window() else {
+            return StorageSubscription::default();
+        };
+
+        // Storage events are only sent to the other tabs that share the storage
+        let storage = self.storage.clone();
+        let key = key.to_string();
+        let listener = Closure::<dyn FnMut(StorageEvent)>::new(move |event: StorageEvent| {
+            // The key is null if the whole storage was cleared
+            let changed = match event.key() {
+                Some(changed) => changed == key,
+                None => true,
+            };
+            if changed && event.storage_area().as_ref() == Some(&storage) {
+                on_change(event.new_value());
+            }
+        });
+        _ = window.add_event_listener_with_callback("storage", listener.as_ref().unchecked_ref());
+
+        StorageSubscription::new(move || {
+            _ = window
+                .remove_event_listener_with_callback("storage", listener.as_ref().unchecked_ref());
+        })
+    }
+
+    fn load_after_hydration(&self) -> bool {
+        self.hydrating
+    }
+}